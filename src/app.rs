@@ -2,16 +2,23 @@
 // Application principale xfinder
 
 use eframe::egui;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
-use crossbeam_channel::{unbounded, Receiver, Sender};
-
-use crate::search::{FileScanner, SearchIndex, SearchResult, FileWatcher, SearchOptions};
+use std::time::{Duration, Instant};
+use crossbeam_channel::{unbounded, Receiver, Select, Sender};
+
+use crate::search::exclusions::ExclusionMatcher;
+use crate::search::incremental::{sync_root, FileChange, KnownFiles, SyncSummary};
+use crate::search::reconcile::reconcile_root;
+use crate::search::mounts::MountTable;
+use crate::search::ignore_rules::IgnoreTree;
+use crate::search::{FileScanner, IndexWriterHandle, ScanConfig, SearchIndex, SearchResult, FileWatcher, FileEvent, SearchOptions, QueryParseError, IndexManifest, IndexCompatibility, Completion, PatternMode, FolderCount};
 use crate::ui::{render_main_ui, render_assist_me_ui, render_side_panel, render_top_panel, render_preview_panel, render_settings_modal, render_statistics_modal};
 use crate::audio_player::AudioPlayer;
 use crate::database::Database;
-use crate::config::AppConfig;
+use crate::database::queries::{CheckpointMode, FileRecord, OpenAction, SearchHistoryRecord, WatchedFolderRecord};
+use crate::config::{AppConfig, ExclusionsConfig, RootConfig};
 use crate::system::{SystemTray, Scheduler, restore_window, hide_from_taskbar, show_in_taskbar};
 use crate::semantic::{SemanticIndexer, BackgroundIndexer, IndexingStats};
 use std::sync::Mutex;
@@ -21,8 +28,60 @@ use chrono::NaiveDate;
 #[derive(Debug, Clone)]
 pub struct IndexProgress {
     pub indexed_count: usize,
-    pub total_files: usize,
+    pub total_files: usize,      // Fichiers trouvés jusqu'ici (définitif quand scanning = false)
     pub current_path: String,
+    pub files_per_sec: f64,      // Débit réel de l'indexation
+    pub scanning: bool,          // Parcours des dossiers encore en cours
+    pub sync: Option<SyncSummary>, // Bilan d'un rafraîchissement incrémental (message final)
+}
+
+// Fichiers traités entre deux points de reprise (commit de l'index et de la base)
+const CHECKPOINT_INTERVAL: usize = 5000;
+
+// Reprise d'une indexation interrompue (voir resume_interrupted_indexing)
+struct IndexingResume {
+    mode: CheckpointMode,
+    done_dirs: Arc<std::collections::HashSet<PathBuf>>, // Fichiers déjà indexés et validés
+}
+
+// Message de progression de la reconstruction de l'index
+#[derive(Debug, Clone)]
+pub enum IndexRebuildEvent {
+    Progress { done: usize, total: usize },
+    Finished(usize),
+    Failed(String),
+}
+
+// Reconstruction de l'index en arrière-plan (schéma ou n-grams modifiés)
+// L'ancien index continue de servir les recherches quand son schéma le permet
+pub struct IndexRebuild {
+    pub reason: String,
+    pub done: usize,
+    pub total: usize,
+    from_legacy_location: bool, // Index Tantivy directement dans index_dir (anciennes versions)
+    source_dir: PathBuf,
+    rx: Option<Receiver<IndexRebuildEvent>>, // None tant que la copie n'a pas démarré
+}
+
+// Message de progression de la réconciliation au démarrage du watchdog
+#[derive(Debug, Clone)]
+pub enum ReconcileEvent {
+    Progress { root: String, checked: usize },
+    RootDone { root: String, events: Vec<FileEvent> },
+    Finished,
+}
+
+// Réconciliation au démarrage du watchdog (voir search::reconcile): les
+// changements faits pendant que xfinder était fermé sont mis en file
+// d'attente du watchdog. Chaque dossier n'est surveillé qu'une fois
+// réconcilié: ses changements passent avant ses événements en direct
+pub struct StartupReconcile {
+    pub roots_total: usize,
+    pub roots_done: usize,
+    pub current_root: String,
+    pub checked: usize,  // Fichiers vérifiés dans le dossier en cours
+    pub changes: usize,  // Différences trouvées jusqu'ici
+    rx: Receiver<ReconcileEvent>,
 }
 
 // Type de fichier pour filtrage
@@ -39,34 +98,40 @@ pub enum FileTypeFilter {
 }
 
 impl FileTypeFilter {
-    pub fn matches(&self, filename: &str) -> bool {
-        let ext = std::path::Path::new(filename)
-            .extension()
-            .and_then(|s| s.to_str())
-            .unwrap_or("")
-            .to_lowercase();
+    // Catégories avec une liste d'extensions (toutes sauf All et Other)
+    const CATEGORIES: [FileTypeFilter; 6] = [
+        FileTypeFilter::Documents,
+        FileTypeFilter::Images,
+        FileTypeFilter::Videos,
+        FileTypeFilter::Audio,
+        FileTypeFilter::Archives,
+        FileTypeFilter::Code,
+    ];
+
+    // Extensions (minuscules, sans point) de la catégorie
+    pub fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            FileTypeFilter::All | FileTypeFilter::Other => &[],
+            FileTypeFilter::Documents => &["pdf", "docx", "doc", "txt", "md", "odt", "rtf", "xlsx", "xls", "pptx", "ppt"],
+            FileTypeFilter::Images => &["jpg", "jpeg", "png", "gif", "svg", "bmp", "webp", "ico", "tiff"],
+            FileTypeFilter::Videos => &["mp4", "avi", "mkv", "mov", "wmv", "flv", "webm", "m4v"],
+            FileTypeFilter::Audio => &["mp3", "wav", "ogg", "flac", "m4a", "wma", "aac"],
+            FileTypeFilter::Archives => &["zip", "rar", "7z", "tar", "gz", "bz2", "xz"],
+            FileTypeFilter::Code => &["rs", "js", "ts", "py", "java", "cpp", "c", "h", "cs", "go", "rb", "php", "html", "css", "json", "xml"],
+        }
+    }
 
+    // Extensions à exiger / exclure pour appliquer ce filtre dans Tantivy
+    pub fn to_extension_filters(self) -> (Vec<String>, Vec<String>) {
+        let to_strings = |exts: &[&str]| exts.iter().map(|e| e.to_string()).collect::<Vec<_>>();
         match self {
-            FileTypeFilter::All => true,
-            FileTypeFilter::Documents => matches!(ext.as_str(),
-                "pdf" | "docx" | "doc" | "txt" | "md" | "odt" | "rtf" | "xlsx" | "xls" | "pptx" | "ppt"),
-            FileTypeFilter::Images => matches!(ext.as_str(),
-                "jpg" | "jpeg" | "png" | "gif" | "svg" | "bmp" | "webp" | "ico" | "tiff"),
-            FileTypeFilter::Videos => matches!(ext.as_str(),
-                "mp4" | "avi" | "mkv" | "mov" | "wmv" | "flv" | "webm" | "m4v"),
-            FileTypeFilter::Audio => matches!(ext.as_str(),
-                "mp3" | "wav" | "ogg" | "flac" | "m4a" | "wma" | "aac"),
-            FileTypeFilter::Archives => matches!(ext.as_str(),
-                "zip" | "rar" | "7z" | "tar" | "gz" | "bz2" | "xz"),
-            FileTypeFilter::Code => matches!(ext.as_str(),
-                "rs" | "js" | "ts" | "py" | "java" | "cpp" | "c" | "h" | "cs" | "go" | "rb" | "php" | "html" | "css" | "json" | "xml"),
-            FileTypeFilter::Other => !matches!(self, FileTypeFilter::All) &&
-                !FileTypeFilter::Documents.matches(filename) &&
-                !FileTypeFilter::Images.matches(filename) &&
-                !FileTypeFilter::Videos.matches(filename) &&
-                !FileTypeFilter::Audio.matches(filename) &&
-                !FileTypeFilter::Archives.matches(filename) &&
-                !FileTypeFilter::Code.matches(filename),
+            FileTypeFilter::All => (Vec::new(), Vec::new()),
+            // "Autres" = tout ce qui n'appartient à aucune catégorie
+            FileTypeFilter::Other => (
+                Vec::new(),
+                Self::CATEGORIES.iter().flat_map(|c| to_strings(c.extensions())).collect(),
+            ),
+            category => (to_strings(category.extensions()), Vec::new()),
         }
     }
 
@@ -84,31 +149,8 @@ impl FileTypeFilter {
     }
 }
 
-// Ordre de tri des résultats
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum SortBy {
-    Relevance,    // Score Tantivy (défaut)
-    NameAsc,      // A→Z
-    NameDesc,     // Z→A
-    DateAsc,      // Ancien → Récent
-    DateDesc,     // Récent → Ancien
-    SizeAsc,      // Petit → Grand
-    SizeDesc,     // Grand → Petit
-}
-
-impl SortBy {
-    pub fn label(&self) -> &'static str {
-        match self {
-            SortBy::Relevance => "Pertinence",
-            SortBy::NameAsc => "Nom (A→Z)",
-            SortBy::NameDesc => "Nom (Z→A)",
-            SortBy::DateAsc => "Date (ancien→récent)",
-            SortBy::DateDesc => "Date (récent→ancien)",
-            SortBy::SizeAsc => "Taille (petit→grand)",
-            SortBy::SizeDesc => "Taille (grand→petit)",
-        }
-    }
-}
+// Ordre de tri des résultats (trié par Tantivy, voir search::SortBy)
+pub use crate::search::SortBy;
 
 // Onglets de la fenêtre de paramètres
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -147,10 +189,26 @@ pub struct AssistMeSource {
     pub chunk_index: usize,
 }
 
+// Nombre max de fichiers favorisés par la frecency dans une recherche
+const FRECENCY_SEARCH_LIMIT: u32 = 200;
+
+// Nombre max de suggestions "Vouliez-vous dire" pour une recherche sans résultat
+const MAX_SUGGESTIONS: usize = 3;
+
+// Complétion pendant la saisie: nombre de propositions, requêtes de l'historique
+// considérées, et délai sans frappe avant de lancer la recherche complète
+const MAX_COMPLETIONS: usize = 8;
+const COMPLETION_HISTORY_LIMIT: u32 = 100;
+const SEARCH_DEBOUNCE: Duration = Duration::from_millis(250);
+
+// Nombre max de dossiers dans la répartition des résultats (panneau latéral)
+const FOLDER_COUNTS_LIMIT: usize = 12;
+
 pub struct XFinderApp {
     pub search_query: String,
-    pub search_results: Vec<SearchResult>,      // Résultats filtrés/triés (affichés)
-    pub raw_search_results: Vec<SearchResult>,  // Résultats bruts de Tantivy (originaux)
+    pub search_results: Vec<SearchResult>,      // Résultats filtrés/triés (pages chargées)
+    pub search_total_hits: usize,                // Nombre total de correspondances
+    pub search_suggestions: Vec<String>,         // Requêtes corrigées si aucun résultat
     pub search_index: Option<SearchIndex>,
     pub database: Option<Arc<Database>>,         // Base SQLite pour métadonnées
     pub file_watcher: Option<FileWatcher>,
@@ -159,9 +217,11 @@ pub struct XFinderApp {
     pub index_dir: PathBuf,
     pub scan_paths: Vec<String>,
     pub index_status: IndexStatus,
+    pub index_rebuild: Option<IndexRebuild>,
     pub indexing_in_progress: bool,
     pub indexing_paused: Arc<AtomicBool>,
     pub error_message: Option<String>,           // Erreurs mode Classic
+    pub query_error: Option<String>,             // Erreur de syntaxe de la requête
     pub assist_me_error: Option<String>,         // Erreurs mode Assist Me
     pub preview_file_path: Option<String>,
     pub max_files_to_index: usize,
@@ -169,6 +229,12 @@ pub struct XFinderApp {
     pub results_display_limit: usize,
     pub watchdog_enabled: bool,
     pub watchdog_update_count: usize,
+    pub startup_reconcile: Option<StartupReconcile>,
+    pub watchdog_resync_count: usize,            // Resynchronisations après perte d'événements
+    pub watchdog_resyncing: Vec<PathBuf>,        // Dossiers en cours de resynchronisation
+    watchdog_dirty_roots: Vec<PathBuf>,          // Dossiers à resynchroniser dès que possible
+    resync_tx: Sender<(PathBuf, Result<SyncSummary, String>)>,
+    resync_rx: Receiver<(PathBuf, Result<SyncSummary, String>)>,
     pub scan_entire_pc: bool,
     // Options de recherche avancée
     pub search_exact_match: bool,
@@ -177,6 +243,13 @@ pub struct XFinderApp {
     pub search_in_path: bool,
     pub search_fuzzy: bool,
     pub fuzzy_distance: u8,
+    pub search_pattern_mode: PatternMode, // Texte, glob ou regex sur le nom
+    pub search_scope: Vec<String>,        // Dossiers où chercher (vide = partout)
+    pub folder_counts: Vec<FolderCount>,  // Répartition des résultats par dossier
+    // Requête et options de la dernière recherche (pages suivantes)
+    last_search: Option<(String, SearchOptions)>,
+    // Rang du prochain document à charger (doublons écartés compris)
+    search_next_offset: usize,
     // Configuration de l'indexation (n-grams)
     pub min_ngram_size: usize,
     pub max_ngram_size: usize,
@@ -186,6 +259,12 @@ pub struct XFinderApp {
     pub filter_size_min: Option<u64>,  // en bytes
     pub filter_size_max: Option<u64>,  // en bytes
     pub sort_by: SortBy,
+    frecency_scores: Option<Vec<(String, f64)>>, // Cache frecency (None = à recharger)
+    // Complétion de la barre de recherche
+    pub search_completions: Vec<Completion>,
+    pub completion_selected: Option<usize>,
+    search_pending_since: Option<Instant>,        // Dernière frappe pas encore cherchée
+    top_searches: Option<Vec<(String, u32)>>,     // Cache historique (None = à recharger)
     // Exclusions d'indexation
     pub excluded_dirs: Vec<String>,      // Dossiers à exclure
     pub excluded_extensions: Vec<String>, // Extensions à exclure (.tmp, .log, etc.)
@@ -196,6 +275,7 @@ pub struct XFinderApp {
     pub settings_tab: SettingsTab,         // Onglet actif dans les paramètres
     pub new_extension_input: String,       // Input temporaire pour ajouter une extension
     pub new_pattern_input: String,         // Input temporaire pour ajouter un pattern
+    pub exclusion_test_input: String,      // Chemin à tester dans les paramètres d'exclusion
     pub editing_date_filter: bool,         // Mode édition pour le filtre de date
     pub date_filter_input: String,         // Input temporaire pour éditer la date
     progress_rx: Option<Receiver<IndexProgress>>,
@@ -214,6 +294,7 @@ pub struct XFinderApp {
     // System integration
     pub system_tray: Option<SystemTray>,
     pub scheduler: Option<Scheduler>,
+    scheduled_sync: Arc<AtomicBool>, // Levé par le scheduler, traité au prochain frame
     pub hotkey_manager: Option<crate::system::HotkeyManager>,
     // Lazy initialization flag
     lazy_initialized: bool,
@@ -227,6 +308,9 @@ pub struct IndexStatus {
     pub indexed_path: Option<String>,
     pub current_indexed: usize,
     pub total_to_index: usize,
+    pub files_per_sec: f64,
+    pub scanning: bool,
+    pub rebuild_failed: bool, // Évite de relancer en boucle une reconstruction qui échoue
 }
 
 impl Default for XFinderApp {
@@ -242,6 +326,9 @@ impl Default for XFinderApp {
         let config = AppConfig::load(AppConfig::default_path())
             .unwrap_or_else(|_| AppConfig::default());
 
+        // Résultats des resynchronisations du watchdog
+        let (resync_tx, resync_rx) = unbounded();
+
         // Utiliser les valeurs de la config
         let scan_paths = config.scan_paths.clone();
         let excluded_extensions = config.exclusions.extensions.clone();
@@ -257,7 +344,8 @@ impl Default for XFinderApp {
         Self {
             search_query: String::new(),
             search_results: Vec::new(),
-            raw_search_results: Vec::new(),
+            search_total_hits: 0,
+            search_suggestions: Vec::new(),
             search_index: None,
             database: None,  // ⚡ Lazy loaded
             file_watcher: None,
@@ -266,9 +354,11 @@ impl Default for XFinderApp {
             index_dir,
             scan_paths,
             index_status: IndexStatus::default(),
+            index_rebuild: None,
             indexing_in_progress: false,
             indexing_paused: Arc::new(AtomicBool::new(false)),
             error_message: None,
+            query_error: None,
             assist_me_error: None,
             preview_file_path: None,
             max_files_to_index,
@@ -276,6 +366,12 @@ impl Default for XFinderApp {
             results_display_limit,
             watchdog_enabled,
             watchdog_update_count: 0,
+            startup_reconcile: None,
+            watchdog_resync_count: 0,
+            watchdog_resyncing: Vec::new(),
+            watchdog_dirty_roots: Vec::new(),
+            resync_tx,
+            resync_rx,
             scan_entire_pc: false,
             // Options de recherche par défaut
             search_exact_match: false,
@@ -284,6 +380,11 @@ impl Default for XFinderApp {
             search_in_path: true,
             search_fuzzy: false,
             fuzzy_distance: 1,
+            search_pattern_mode: PatternMode::Text,
+            search_scope: Vec::new(),
+            folder_counts: Vec::new(),
+            last_search: None,
+            search_next_offset: 0,
             // Utiliser les valeurs de config pour n-grams
             min_ngram_size,
             max_ngram_size,
@@ -293,6 +394,11 @@ impl Default for XFinderApp {
             filter_size_min: None,
             filter_size_max: None,
             sort_by: SortBy::Relevance,
+            frecency_scores: None,
+            search_completions: Vec::new(),
+            completion_selected: None,
+            search_pending_since: None,
+            top_searches: None,
             // Utiliser les exclusions de la config
             excluded_dirs,
             excluded_extensions,
@@ -303,6 +409,7 @@ impl Default for XFinderApp {
             settings_tab: SettingsTab::default(),
             new_extension_input: String::new(),
             new_pattern_input: String::new(),
+            exclusion_test_input: String::new(),
             editing_date_filter: false,
             date_filter_input: String::new(),
             progress_rx: None,
@@ -321,6 +428,7 @@ impl Default for XFinderApp {
             // System integration
             system_tray: None,  // ⚡ Lazy loaded
             scheduler: None,  // Sera initialisé après si activé dans la config
+            scheduled_sync: Arc::new(AtomicBool::new(false)),
             hotkey_manager: None,  // ⚡ Lazy loaded
             lazy_initialized: false,
         }
@@ -328,16 +436,214 @@ impl Default for XFinderApp {
 }
 
 impl XFinderApp {
+    // Dossier de l'index Tantivy
+    // Sous-dossier dédié: index_dir contient aussi la base SQLite et la config
+    pub fn tantivy_dir(&self) -> PathBuf {
+        self.index_dir.join("tantivy")
+    }
+
+    // Dossier temporaire de reconstruction (remplace tantivy_dir à la fin)
+    fn tantivy_rebuild_dir(&self) -> PathBuf {
+        self.index_dir.join("tantivy.rebuild")
+    }
+
     pub fn load_index(&mut self) {
-        match SearchIndex::new(&self.index_dir, self.min_ngram_size, self.max_ngram_size) {
-            Ok(index) => {
-                self.search_index = Some(index);
-                self.index_status.is_ready = true;
-                // Ne pas effacer error_message ici pour garder le message de succès
+        if self.index_rebuild.is_some() {
+            return; // L'index actuel est remplacé à la fin de la reconstruction
+        }
+
+        let tantivy_dir = self.tantivy_dir();
+        let expected = IndexManifest::new(self.min_ngram_size, self.max_ngram_size);
+
+        // Anciennes versions: index Tantivy directement dans index_dir
+        if !tantivy_dir.join("meta.json").exists() && self.index_dir.join("meta.json").exists() {
+            if !self.index_status.rebuild_failed {
+                self.search_index = None;
+                self.index_status.is_ready = false;
+                self.start_index_rebuild(self.index_dir.clone(), true, "Migration de l'ancien index".to_string());
+                self.error_message = Some(
+                    "Migration de l'ancien index: recherche indisponible jusqu'à la fin (schéma trop ancien pour être lu)"
+                        .to_string(),
+                );
             }
+            return;
+        }
+
+        let compatibility = match IndexManifest::check(&tantivy_dir, &expected) {
+            Ok(compatibility) => compatibility,
             Err(e) => {
                 self.error_message = Some(format!("Erreur chargement index: {}", e));
                 self.index_status.is_ready = false;
+                return;
+            }
+        };
+
+        match compatibility {
+            IndexCompatibility::Missing | IndexCompatibility::UpToDate => {
+                match SearchIndex::new(&tantivy_dir, self.min_ngram_size, self.max_ngram_size) {
+                    Ok(index) => {
+                        self.search_index = Some(index);
+                        self.index_status.is_ready = true;
+                        // Ne pas effacer error_message ici pour garder le message de succès
+                    }
+                    Err(e) => {
+                        self.error_message = Some(format!("Erreur chargement index: {}", e));
+                        self.index_status.is_ready = false;
+                    }
+                }
+            }
+            IndexCompatibility::SettingsChanged { current } => {
+                // Servir les recherches avec l'ancien index pendant la reconstruction
+                // (celui déjà chargé s'il y en a un: un seul writer sur ses fichiers)
+                if self.search_index.is_none() {
+                    self.search_index = SearchIndex::open_existing(&tantivy_dir).ok();
+                }
+                self.index_status.is_ready = self.search_index.is_some();
+                if !self.index_status.rebuild_failed {
+                    let reason = format!("Paramètres modifiés ({})", current.describe_changes(&expected));
+                    self.start_index_rebuild(tantivy_dir, false, reason);
+                }
+            }
+            IndexCompatibility::SchemaOutdated { found } => {
+                // Pas d'ouverture en lecture seule comme pour SettingsChanged: la
+                // recherche, le tri et les filtres s'appuient sur des champs
+                // (name_sort, dirs, filename_words...) absents des anciens schémas.
+                // Seule la copie (rebuild) sait lire un ancien index.
+                self.search_index = None;
+                self.index_status.is_ready = false;
+                if !self.index_status.rebuild_failed {
+                    let reason = format!("Index d'une ancienne version (schéma v{})", found.unwrap_or(1));
+                    self.error_message = Some(format!(
+                        "{}: recherche indisponible jusqu'à la fin de la reconstruction (schéma trop ancien pour être lu)",
+                        reason
+                    ));
+                    self.start_index_rebuild(tantivy_dir, false, reason);
+                }
+            }
+        }
+    }
+
+    // Prépare la reconstruction de l'index (lancée par process_index_rebuild)
+    // Dès maintenant plus rien n'écrit dans l'ancien index: les événements du
+    // watchdog restent en file d'attente et seront appliqués au nouvel index
+    fn start_index_rebuild(&mut self, source_dir: PathBuf, from_legacy_location: bool, reason: String) {
+        self.index_rebuild = Some(IndexRebuild {
+            reason,
+            done: 0,
+            total: 0,
+            from_legacy_location,
+            source_dir,
+            rx: None,
+        });
+    }
+
+    // Lance la copie de l'index dans un thread séparé
+    // Les documents sont relus depuis l'index source (pas de rescan disque)
+    fn spawn_index_rebuild(&mut self) {
+        let rebuild_dir = self.tantivy_rebuild_dir();
+        let min_ngram_size = self.min_ngram_size;
+        let max_ngram_size = self.max_ngram_size;
+        let Some(ref mut rebuild) = self.index_rebuild else {
+            return;
+        };
+        let source_dir = rebuild.source_dir.clone();
+
+        let (tx, rx) = unbounded::<IndexRebuildEvent>();
+        rebuild.rx = Some(rx);
+
+        std::thread::spawn(move || {
+            let progress_tx = tx.clone();
+            let result = SearchIndex::rebuild(&source_dir, &rebuild_dir, min_ngram_size, max_ngram_size, |done, total| {
+                let _ = progress_tx.send(IndexRebuildEvent::Progress { done, total });
+            });
+            let _ = tx.send(match result {
+                Ok(count) => IndexRebuildEvent::Finished(count),
+                Err(e) => IndexRebuildEvent::Failed(e.to_string()),
+            });
+        });
+    }
+
+    // Traiter la progression de la reconstruction et installer le nouvel index
+    fn process_index_rebuild(&mut self) {
+        // Copie pas encore lancée: attendre la fin des resynchronisations en
+        // cours (elles écrivent dans l'ancien index), puis valider les écritures
+        // en attente pour que la copie soit complète
+        if self.index_rebuild.as_ref().is_some_and(|rebuild| rebuild.rx.is_none()) {
+            self.collect_watchdog_resyncs();
+            if !self.watchdog_resyncing.is_empty() {
+                return;
+            }
+            if let Some(ref index) = self.search_index {
+                if let Err(e) = index.flush() {
+                    self.error_message = Some(format!("Erreur écriture index: {}", e));
+                }
+            }
+            self.spawn_index_rebuild();
+            return;
+        }
+
+        let mut outcome = None;
+
+        if let Some(IndexRebuild { rx: Some(ref rx), ref mut done, ref mut total, .. }) = self.index_rebuild {
+            while let Ok(event) = rx.try_recv() {
+                match event {
+                    IndexRebuildEvent::Progress { done: copied, total: to_copy } => {
+                        *done = copied;
+                        *total = to_copy;
+                    }
+                    IndexRebuildEvent::Finished(count) => outcome = Some(Ok(count)),
+                    IndexRebuildEvent::Failed(e) => outcome = Some(Err(e)),
+                }
+            }
+        }
+
+        let Some(outcome) = outcome else {
+            return;
+        };
+        let rebuild = match self.index_rebuild.take() {
+            Some(rebuild) => rebuild,
+            None => return,
+        };
+
+        match outcome {
+            Ok(count) => {
+                // CRITIQUE: fermer l'ancien index avant de remplacer ses fichiers
+                // Le writer partagé est arrêté pour toutes les copies (verrous
+                // Windows, aucune écriture dans le dossier .old supprimé)
+                if let Some(index) = self.search_index.take() {
+                    if let Err(e) = index.close_writer() {
+                        self.error_message = Some(format!("Erreur écriture index: {}", e));
+                    }
+                }
+                match SearchIndex::replace_with_rebuilt(&self.tantivy_dir(), &self.tantivy_rebuild_dir()) {
+                    Ok(()) => {
+                        if rebuild.from_legacy_location {
+                            let _ = SearchIndex::remove_index_files(&self.index_dir);
+                        }
+                        self.index_status.file_count = count;
+                        self.error_message = Some(format!("Index reconstruit ({}): {} fichiers", rebuild.reason, count));
+                    }
+                    Err(e) => {
+                        self.index_status.rebuild_failed = true;
+                        self.error_message = Some(format!("Erreur installation index reconstruit: {}", e));
+                    }
+                }
+                // Les événements du watchdog en attente s'appliquent maintenant
+                // au nouvel index (voir process_watchdog_events)
+                self.load_index();
+
+                // Rafraîchir les résultats affichés avec le nouvel index
+                if self.search_index.is_some() && !self.search_query.trim().is_empty() {
+                    self.perform_search();
+                }
+            }
+            Err(e) => {
+                self.index_status.rebuild_failed = true;
+                let _ = std::fs::remove_dir_all(self.tantivy_rebuild_dir());
+                self.error_message = Some(format!(
+                    "Échec reconstruction index: {} (lancez une Nouvelle Indexation)",
+                    e
+                ));
             }
         }
     }
@@ -359,6 +665,11 @@ impl XFinderApp {
             self.database = Database::new(&db_path)
                 .ok()
                 .map(Arc::new);
+
+            // Purger l'historique d'ouverture qui ne compte plus dans la frecency
+            if let Some(ref db) = self.database {
+                let _ = db.cleanup_old_open_events();
+            }
         }
 
         // 2. Initialiser l'audio player (si nécessaire)
@@ -376,9 +687,42 @@ impl XFinderApp {
             self.hotkey_manager = crate::system::HotkeyManager::new().ok();
         }
 
+        // 5. Démarrer l'indexation planifiée (si activée dans config)
+        if self.config.system.scheduler_enabled {
+            self.start_scheduler();
+        }
+
+        // 6. Reprendre l'indexation interrompue à la dernière fermeture
+        self.resume_interrupted_indexing();
+
+        // 7. Relancer le watchdog s'il était actif, après avoir rattrapé les
+        // changements faits pendant la fermeture (sauf si une indexation
+        // reprise s'en charge)
+        if self.watchdog_enabled {
+            let reconcile = !self.indexing_in_progress;
+            self.start_watchdog(reconcile);
+        }
+
         self.lazy_initialized = true;
     }
 
+    /// Démarre l'indexation planifiée: à l'heure configurée, un rafraîchissement
+    /// incrémental est demandé (lancé par update(), sur le thread de l'UI)
+    pub fn start_scheduler(&mut self) {
+        self.stop_scheduler();
+        let scheduler = Scheduler::new(self.config.system.scheduler_hour, self.config.system.scheduler_minute);
+        let scheduled_sync = self.scheduled_sync.clone();
+        scheduler.start(move || scheduled_sync.store(true, Ordering::Relaxed));
+        self.scheduler = Some(scheduler);
+    }
+
+    /// Arrête l'indexation planifiée
+    pub fn stop_scheduler(&mut self) {
+        if let Some(scheduler) = self.scheduler.take() {
+            scheduler.stop();
+        }
+    }
+
     /// Initialise le système d'indexation sémantique (Assist Me)
     /// Appelé à la demande quand l'utilisateur active le mode Assist Me
     pub fn init_semantic_indexing(&mut self) {
@@ -480,14 +824,11 @@ impl XFinderApp {
         println!("✅ Starting semantic indexing...");
 
         // Collecter les fichiers à indexer
-        use crate::search::FileScanner;
         let scanner = FileScanner::new();
 
         // TODO: Utiliser config.assist_me.scan_paths quand dual-mode config sera implémenté
         let scan_paths = self.scan_paths.clone();
-        let excluded_extensions = self.excluded_extensions.clone();
-        let excluded_patterns = self.excluded_patterns.clone();
-        let excluded_dirs = self.excluded_dirs.clone();
+        let scan_configs: Vec<ScanConfig> = scan_paths.iter().map(|path| self.root_scan_config(path)).collect();
 
         // Cloner le background_indexer pour le thread
         let bg_indexer = self.background_indexer.as_ref().unwrap().clone();
@@ -507,30 +848,22 @@ impl XFinderApp {
                 }
 
                 // Scanner les fichiers (sans limite pour semantic)
-                match scanner.scan_directory(
-                    &scan_path,
-                    usize::MAX, // Pas de limite
-                    &excluded_extensions,
-                    &excluded_patterns,
-                    &excluded_dirs
-                ) {
-                    Ok(files) => {
-                        println!("✅ Found {} files in {}", files.len(), path_str);
-                        for file_entry in files {
-                            // Enqueue le fichier pour indexation sémantique
-                            if let Err(e) = bg_indexer.enqueue_file(file_entry.path.clone()) {
-                                eprintln!("❌ Failed to enqueue {}: {}", file_entry.path, e);
-                            } else {
-                                total_files += 1;
-                                if total_files % 100 == 0 {
-                                    println!("📊 Enqueued {} files so far...", total_files);
-                                }
-                            }
+                let files: Vec<_> = scanner
+                    .scan(&scan_path, scan_configs[idx].clone())
+                    .map(|file| file.entry)
+                    .collect();
+
+                println!("✅ Found {} files in {}", files.len(), path_str);
+                for file_entry in files {
+                    // Enqueue le fichier pour indexation sémantique
+                    if let Err(e) = bg_indexer.enqueue_file(file_entry.path.clone()) {
+                        eprintln!("❌ Failed to enqueue {}: {}", file_entry.path, e);
+                    } else {
+                        total_files += 1;
+                        if total_files % 100 == 0 {
+                            println!("📊 Enqueued {} files so far...", total_files);
                         }
                     }
-                    Err(e) => {
-                        eprintln!("❌ Failed to scan {}: {}", path_str, e);
-                    }
                 }
             }
 
@@ -557,6 +890,11 @@ impl XFinderApp {
         self.config.ui.results_display_limit = self.results_display_limit;
         self.config.ui.watchdog_enabled = self.watchdog_enabled;
 
+        // Le watchdog applique les nouvelles exclusions tout de suite
+        if let Some(ref mut watcher) = self.file_watcher {
+            watcher.set_exclusions(&self.config.exclusions);
+        }
+
         // Sauvegarder dans le fichier
         if let Err(e) = self.config.save(AppConfig::default_path()) {
             eprintln!("Erreur sauvegarde config: {}", e);
@@ -565,10 +903,46 @@ impl XFinderApp {
 
     // Lance une nouvelle indexation dans un thread séparé (pas de freeze UI)
     pub fn start_indexing(&mut self, clear_existing: bool) {
+        self.launch_indexing(clear_existing, None);
+    }
+
+    // Reprend l'indexation interrompue à la dernière fermeture, s'il y en a une
+    // (point de reprise en base), dans l'état de pause où elle a été laissée
+    pub fn resume_interrupted_indexing(&mut self) {
+        let Some(db) = self.database.clone() else {
+            return;
+        };
+        let Ok(Some(checkpoint)) = db.get_checkpoint() else {
+            return;
+        };
+        let done_dirs = db.get_checkpoint_dirs().unwrap_or_default().into_iter().map(PathBuf::from).collect();
+
+        self.launch_indexing(false, Some(IndexingResume {
+            mode: checkpoint.mode,
+            done_dirs: Arc::new(done_dirs),
+        }));
+        if !self.indexing_in_progress {
+            return;
+        }
+        if checkpoint.paused {
+            self.pause_indexing();
+        }
+        self.error_message = Some(match checkpoint.last_dir {
+            Some(dir) => format!("Reprise de l'indexation interrompue apres {}", dir),
+            None => "Reprise de l'indexation interrompue".to_string(),
+        });
+    }
+
+    fn launch_indexing(&mut self, clear_existing: bool, resume: Option<IndexingResume>) {
         if self.indexing_in_progress {
             return; // Déjà en cours
         }
 
+        if self.index_rebuild.is_some() {
+            self.error_message = Some("Reconstruction de l'index en cours, patientez".to_string());
+            return;
+        }
+
         self.indexing_in_progress = true;
         self.error_message = None;
         self.index_status.current_indexed = 0;
@@ -580,6 +954,7 @@ impl XFinderApp {
             self.search_index = None; // Drop l'ancien index pour libérer les fichiers
             self.file_watcher = None; // Fermer le watchdog aussi
             self.index_status.is_ready = false;
+            self.index_status.rebuild_failed = false;
         }
 
         // Ne charger l'index existant QUE si on fait un refresh (pas une nouvelle indexation)
@@ -587,6 +962,13 @@ impl XFinderApp {
             self.load_index();
         }
 
+        // Index incompatible détecté au chargement: la reconstruction passe d'abord
+        if self.index_rebuild.is_some() {
+            self.indexing_in_progress = false;
+            self.error_message = Some("Reconstruction de l'index en cours, rafraîchissez ensuite".to_string());
+            return;
+        }
+
         // Vérifier que tous les chemins existent
         for path_str in &self.scan_paths {
             let path = PathBuf::from(path_str);
@@ -598,7 +980,8 @@ impl XFinderApp {
         }
 
         // Cloner les données nécessaires pour le thread
-        let index_dir = self.index_dir.clone();
+        let app_dir = self.index_dir.clone();
+        let index_dir = self.tantivy_dir();
         let scan_paths = self.scan_paths.clone();
         let max_files = if self.no_file_limit {
             usize::MAX
@@ -607,10 +990,10 @@ impl XFinderApp {
         };
         let min_ngram_size = self.min_ngram_size;
         let max_ngram_size = self.max_ngram_size;
-        // Cloner les exclusions pour le thread
-        let excluded_extensions = self.excluded_extensions.clone();
-        let excluded_patterns = self.excluded_patterns.clone();
-        let excluded_dirs = self.excluded_dirs.clone();
+        // Règles de parcours par dossier (exclusions, .gitignore...) pour le thread
+        let scan_configs: Vec<ScanConfig> = scan_paths.iter().map(|path| self.root_scan_config(path)).collect();
+        // Refresh: partager l'index chargé (et son writer) avec le watchdog
+        let existing_index = if clear_existing { None } else { self.search_index.clone() };
         // Cloner la database pour le thread
         let database = self.database.clone();
         // Cloner le flag de pause pour le thread
@@ -623,105 +1006,254 @@ impl XFinderApp {
         // Réinitialiser la pause au début de l'indexation
         self.indexing_paused.store(false, Ordering::Relaxed);
 
+        // Point de reprise: une indexation reprise continue le sien
+        let mode = match &resume {
+            Some(resume) => resume.mode,
+            None if clear_existing => CheckpointMode::Full,
+            None => CheckpointMode::Sync,
+        };
+        if resume.is_none() {
+            if let Some(ref db) = database {
+                let _ = db.begin_checkpoint(mode);
+            }
+        }
+        let done_dirs = resume.map(|resume| resume.done_dirs);
+
         // Lancer l'indexation dans un thread séparé
         std::thread::spawn(move || {
             // Effacer complètement si demandé (pour forcer nouveau schéma/tokenizer)
             if clear_existing {
                 let _ = SearchIndex::delete_completely(&index_dir);
+                // Ancien index à la racine (à côté de la DB): ne supprimer que ses fichiers
+                if app_dir.join("meta.json").exists() {
+                    let _ = SearchIndex::remove_index_files(&app_dir);
+                }
             }
 
             // Charger l'index (nouveau schéma si on a effacé)
-            let index = match SearchIndex::new(&index_dir, min_ngram_size, max_ngram_size) {
-                Ok(idx) => idx,
-                Err(_) => return,
+            let index = match existing_index {
+                Some(idx) => idx,
+                None => match SearchIndex::new(&index_dir, min_ngram_size, max_ngram_size) {
+                    Ok(idx) => idx,
+                    Err(_) => return,
+                },
             };
 
             let scanner = FileScanner::new();
-            let files_per_path = max_files / scan_paths.len().max(1);
 
-            let mut writer = match index.create_writer() {
+            // Rafraîchissement: ne traiter que ce qui a changé depuis le dernier
+            // scan (table files). Sans base, tout est réindexé, de même qu'une
+            // reconstruction (reprise ou non: l'index a été vidé).
+            let known = if mode == CheckpointMode::Full {
+                None
+            } else {
+                database
+                    .as_ref()
+                    .and_then(|db| db.get_file_states().ok())
+                    .map(|states| Arc::new(KnownFiles::new(states)))
+            };
+            let mut summary = SyncSummary::default();
+
+            // Writer partagé: commits par lots, le watchdog peut écrire en parallèle
+            let writer = match index.writer() {
                 Ok(w) => w,
                 Err(_) => return,
             };
+            // Écriture en masse: commits aux lots et points de reprise seulement
+            let bulk = writer.bulk();
 
+            let started = std::time::Instant::now();
+            let started_at = chrono::Utc::now().timestamp();
             let mut total_indexed = 0;
-            let mut db_batch: Vec<crate::database::queries::FileRecord> = Vec::with_capacity(1000);
+            let mut db_batch: Vec<FileRecord> = Vec::with_capacity(1000);
+            // Dossiers terminés pas encore enregistrés dans le point de reprise
+            let mut completed_dirs: Vec<(String, String)> = Vec::new();
+
+            // Tous les dossiers sont scannés en même temps (threads répartis
+            // entre eux): les fichiers arrivent du scan parallèle (métadonnées et
+            // hash déjà lus par ses workers) au fur et à mesure
+            let defaults = ScanConfig::default();
+            let roots = scan_paths.len().max(1);
+            let mut scans: Vec<_> = scan_paths
+                .iter()
+                .zip(scan_configs)
+                .enumerate()
+                .map(|(root, (path_str, scan_config))| {
+                    let scan = scanner.scan(Path::new(path_str), ScanConfig {
+                        compute_hash: database.is_some(),
+                        known: known.clone(),
+                        track_dirs: database.is_some(),
+                        done_dirs: done_dirs.clone(),
+                        walker_threads: (defaults.walker_threads / roots).max(1),
+                        metadata_threads: (defaults.metadata_threads / roots).max(1),
+                        ..scan_config
+                    });
+                    (root, scan)
+                })
+                .collect();
+            let mut indexed_per_root = vec![0usize; scan_paths.len()];
+            let mut size_per_root = vec![0u64; scan_paths.len()];
+            let mut reports = Vec::with_capacity(scan_paths.len());
+
+            // Budget de fichiers partagé équitablement: chaque fichier est pris
+            // parmi les scans prêts, au hasard. Un petit dossier termine sous sa
+            // part et laisse le reste aux gros; une fois le budget épuisé, les
+            // scans encore en cours sont arrêtés et leurs sous-arbres non
+            // parcourus relevés dans leur rapport.
+            while !scans.is_empty() && total_indexed < max_files {
+                let (i, received) = {
+                    let mut select = Select::new();
+                    for (_, scan) in &scans {
+                        select.recv(scan.receiver());
+                    }
+                    let op = select.select();
+                    let i = op.index();
+                    (i, op.recv(scans[i].1.receiver()))
+                };
+                let Ok(scanned) = received else {
+                    // Scan terminé: tous ses fichiers ont été reçus
+                    let (root, scan) = scans.swap_remove(i);
+                    completed_dirs.extend(take_completed_dirs(&scan_paths[root], &scan));
+                    reports.push((root, scan.finish()));
+                    continue;
+                };
 
-            // Scanner chaque dossier
-            for path_str in &scan_paths {
-                let scan_path = PathBuf::from(path_str);
+                // Vérifier si l'indexation est en pause
+                // (les channels bornés des scans mettent aussi le parcours en pause)
+                while indexing_paused.load(Ordering::Relaxed) {
+                    std::thread::sleep(std::time::Duration::from_millis(100));
+                }
 
-                if let Ok(files) = scanner.scan_directory(
-                    &scan_path,
-                    files_per_path,
-                    &excluded_extensions,
-                    &excluded_patterns,
-                    &excluded_dirs
-                ) {
-                    let total_files = files.len();
-
-                    for (i, file) in files.iter().enumerate() {
-                        // Vérifier si l'indexation est en pause
-                        while indexing_paused.load(Ordering::Relaxed) {
-                            std::thread::sleep(std::time::Duration::from_millis(100));
-                        }
+                let file = scanned.entry;
+                // Refresh: remplacer le document existant au lieu de le dupliquer
+                // (fichier inchangé: déjà à jour dans l'index et la base)
+                let queued = match scanned.change {
+                    FileChange::Unchanged => Ok(()),
+                    FileChange::Added if clear_existing => writer.add(file.clone()),
+                    FileChange::Added | FileChange::Modified => writer.update(file.clone()),
+                };
+
+                if queued.is_ok() {
+                    total_indexed += 1;
+                    indexed_per_root[scans[i].0] += 1;
+                    size_per_root[scans[i].0] += file.size;
+                    summary.record(scanned.change);
+                    scans[i].1.file_done(&file.path);
+
+                    // Collecter métadonnées pour SQLite (déjà lues par le scanner)
+                    // Fichier inchangé: sa ligne est déjà à jour
+                    if database.is_some() && scanned.change != FileChange::Unchanged {
+                        let now = chrono::Utc::now().timestamp();
+                        let file_record = FileRecord {
+                            id: crate::database::queries::file_id(&file.path),
+                            path: file.path.clone(),
+                            filename: file.filename.clone(),
+                            extension: std::path::Path::new(&file.path)
+                                .extension()
+                                .and_then(|s| s.to_str())
+                                .map(|s| format!(".{}", s)),
+                            size: file.size,
+                            modified: file.modified.unwrap_or(now),
+                            created: file.created.unwrap_or(now),
+                            hash: scanned.hash,
+                            indexed_at: now,
+                        };
+                        db_batch.push(file_record);
+                    }
 
-                        // Yield tous les 1000 fichiers pour ne pas monopoliser le CPU
-                        if i % 1000 == 0 && i > 0 {
-                            std::thread::yield_now();
+                    // Point de reprise périodique (batch insert SQLite + commit de l'index)
+                    if let Some(ref db) = database {
+                        if total_indexed % CHECKPOINT_INTERVAL == 0 {
+                            for (root, scan) in &scans {
+                                completed_dirs.extend(take_completed_dirs(&scan_paths[*root], scan));
+                            }
+                            commit_checkpoint(db, &writer, &mut db_batch, &mut completed_dirs, total_indexed);
                         }
+                    }
 
-                        if index.add_file(&mut writer, &file.path, &file.filename).is_ok() {
-                            total_indexed += 1;
-
-                            // Collecter métadonnées pour SQLite
-                            if let Some(ref db) = database {
-                                if let Ok(metadata) = std::fs::metadata(&file.path) {
-                                    let now = chrono::Utc::now().timestamp();
-                                    let file_record = crate::database::queries::FileRecord {
-                                        id: format!("{:x}", file.path.as_bytes().iter().fold(0u64, |acc, &b| acc.wrapping_mul(31).wrapping_add(b as u64))),
-                                        path: file.path.clone(),
-                                        filename: file.filename.clone(),
-                                        extension: std::path::Path::new(&file.path)
-                                            .extension()
-                                            .and_then(|s| s.to_str())
-                                            .map(|s| format!(".{}", s)),
-                                        size: metadata.len(),
-                                        modified: metadata.modified()
-                                            .ok()
-                                            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
-                                            .map(|d| d.as_secs() as i64)
-                                            .unwrap_or(now),
-                                        created: metadata.created()
-                                            .ok()
-                                            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
-                                            .map(|d| d.as_secs() as i64)
-                                            .unwrap_or(now),
-                                        hash: crate::hash::hash_file_fast(std::path::Path::new(&file.path)).ok(),
-                                        indexed_at: now,
-                                    };
-                                    db_batch.push(file_record);
-
-                                    // Batch insert tous les 5000 fichiers (optimisé)
-                                    if db_batch.len() >= 5000 {
-                                        let _ = db.batch_upsert_files(&db_batch);
-                                        db_batch.clear();
-                                    }
-                                }
-                            }
+                    // Envoyer progression à chaque fichier pour une barre fluide
+                    // Le channel unbounded est non-bloquant et l'UI prend la dernière valeur
+                    // Total: fichiers indexés + trouvés mais pas encore reçus
+                    let mut total_files = total_indexed;
+                    let mut scanning = false;
+                    for (root, scan) in &scans {
+                        let scan_progress = scan.progress();
+                        total_files += scan_progress.files_found.saturating_sub(indexed_per_root[*root]);
+                        scanning |= !scan_progress.walk_done;
+                    }
+                    let _ = progress_tx.send(IndexProgress {
+                        indexed_count: total_indexed,
+                        total_files: total_files.min(max_files),
+                        current_path: file.filename,
+                        files_per_sec: total_indexed as f64 / started.elapsed().as_secs_f64().max(0.001),
+                        scanning,
+                        sync: None,
+                    });
+                }
+            }
 
-                            // Envoyer progression à chaque fichier pour une barre fluide
-                            // Le channel unbounded est non-bloquant et l'UI prend la dernière valeur
-                            let _ = progress_tx.send(IndexProgress {
-                                indexed_count: total_indexed,
-                                total_files,
-                                current_path: file.filename.clone(),
-                            });
-                        }
+            // Budget épuisé: arrêter les scans restants
+            for (root, scan) in scans {
+                reports.push((root, scan.finish()));
+            }
+
+            // Fichiers connus que le scan n'a pas retrouvés: disparus ou exclus
+            // (seulement si le dossier a été vu en entier: une limite de fichiers
+            // ou un dossier illisible laisse des fichiers existants non vus)
+            if let (Some(known), Some(db)) = (&known, &database) {
+                for (root, report) in &reports {
+                    if report.file_limit_hit {
+                        continue;
+                    }
+                    let mut vanished = known.vanished_under(Path::new(&scan_paths[*root]));
+                    // Reprise: les fichiers des dossiers déjà terminés n'ont pas été revus
+                    if let Some(done_dirs) = &done_dirs {
+                        vanished.retain(|path| !Path::new(path).parent().is_some_and(|dir| done_dirs.contains(dir)));
+                    }
+                    vanished.retain(|path| !report.is_unreadable(Path::new(path)));
+                    for path in &vanished {
+                        let _ = writer.delete(path);
+                    }
+                    if db.batch_delete_files(&vanished).is_ok() {
+                        summary.removed += vanished.len();
                     }
                 }
             }
 
+            // Bilan de chaque dossier (sous-arbres tronqués), pour les statistiques
+            if let Some(ref db) = database {
+                let now = chrono::Utc::now().timestamp();
+                // Date du dernier parcours complet (voir search::reconcile)
+                let folders = db.get_watched_folders().unwrap_or_default();
+                for (root, report) in &reports {
+                    if report.file_limit_hit {
+                        continue;
+                    }
+                    let created_at = folders.iter().find(|folder| folder.path == scan_paths[*root]).map(|folder| folder.created_at);
+                    let _ = db.upsert_watched_folder(&WatchedFolderRecord {
+                        path: scan_paths[*root].clone(),
+                        last_scan: Some(started_at),
+                        file_count: indexed_per_root[*root] as u64,
+                        total_size: size_per_root[*root],
+                        enabled: true,
+                        created_at: created_at.unwrap_or(now),
+                    });
+                }
+                for (root, report) in reports {
+                    let _ = db.save_scan_report(&crate::database::queries::ScanReportRecord {
+                        root: scan_paths[root].clone(),
+                        files_indexed: indexed_per_root[root] as u64,
+                        truncated_total: report.truncated_total as u64,
+                        truncated: report
+                            .truncated
+                            .into_iter()
+                            .map(|dir| (dir.path, dir.reason.as_str().to_string()))
+                            .collect(),
+                        scanned_at: now,
+                    });
+                }
+            }
+
             // Flush dernier batch SQLite
             if !db_batch.is_empty() {
                 if let Some(ref db) = database {
@@ -729,27 +1261,46 @@ impl XFinderApp {
                 }
             }
 
-            // Commit final
-            let _ = writer.commit();
+            // Commit final, puis libérer le writer (et son verrou) si l'index
+            // n'est pas partagé avec l'UI
+            drop(bulk);
+            let flushed = writer.flush();
+            drop(writer);
+            drop(index);
+
+            // Indexation terminée: plus rien à reprendre
+            if flushed.is_ok() {
+                if let Some(ref db) = database {
+                    let _ = db.clear_checkpoint();
+                }
+            }
 
             // Envoyer progression finale
             let _ = progress_tx.send(IndexProgress {
                 indexed_count: total_indexed,
                 total_files: total_indexed,
                 current_path: "Termine".to_string(),
+                files_per_sec: total_indexed as f64 / started.elapsed().as_secs_f64().max(0.001),
+                scanning: false,
+                sync: known.is_some().then_some(summary),
             });
         });
     }
 
-    // Rafraîchit l'index actuel (ajoute nouveaux fichiers par-dessus)
+    // Rafraîchit l'index actuel: synchronisation incrémentale (seuls les
+    // fichiers ajoutés, modifiés ou supprimés depuis le dernier scan sont traités)
     pub fn refresh_index(&mut self) {
         self.start_indexing(false);
     }
 
     // Met en pause l'indexation en cours
+    // (mémorisé dans le point de reprise: l'indexation reprendra en pause)
     pub fn pause_indexing(&mut self) {
         if self.indexing_in_progress {
             self.indexing_paused.store(true, Ordering::Relaxed);
+            if let Some(ref db) = self.database {
+                let _ = db.set_checkpoint_paused(true);
+            }
         }
     }
 
@@ -757,6 +1308,9 @@ impl XFinderApp {
     pub fn resume_indexing(&mut self) {
         if self.indexing_in_progress {
             self.indexing_paused.store(false, Ordering::Relaxed);
+            if let Some(ref db) = self.database {
+                let _ = db.set_checkpoint_paused(false);
+            }
         }
     }
 
@@ -781,9 +1335,99 @@ impl XFinderApp {
         }
     }
 
+    // Exclusions courantes (listes éditées dans les paramètres + options de la config)
+    pub fn exclusions_config(&self) -> ExclusionsConfig {
+        ExclusionsConfig {
+            extensions: self.excluded_extensions.clone(),
+            patterns: self.excluded_patterns.clone(),
+            dirs: self.excluded_dirs.clone(),
+            ..self.config.exclusions.clone()
+        }
+    }
+
+    // Règles de parcours d'un dossier indexé (exclusions, profondeur, .gitignore,
+    // liens, systèmes de fichiers): indexation, réconciliation et resynchronisation
+    fn root_scan_config(&self, path: &str) -> ScanConfig {
+        let root_config = self.config.root_config(path);
+        ScanConfig {
+            max_depth: root_config.max_depth,
+            exclusions: root_config.exclusions(&self.exclusions_config()),
+            respect_vcs_ignore: root_config.respect_vcs_ignore,
+            follow_symlinks: root_config.follow_symlinks,
+            same_filesystem: root_config.same_filesystem,
+            skip_pseudo_fs: root_config.skip_pseudo_fs,
+            ..ScanConfig::default()
+        }
+    }
+
+    // Pourquoi ce chemin n'est-il pas indexé ? (None: il est indexé)
+    // Exclusions de la config, puis fichiers .gitignore/.ignore/.xfinderignore
+    pub fn explain_exclusion(&self, path: &str) -> Option<String> {
+        let path = PathBuf::from(path.trim());
+        let metadata = std::fs::metadata(&path).ok();
+        let is_dir = metadata.as_ref().is_some_and(|m| m.is_dir());
+        let size = metadata.as_ref().filter(|m| m.is_file()).map(|m| m.len());
+
+        let mut matcher = ExclusionMatcher::new(&self.exclusions_config());
+        for root in &self.scan_paths {
+            matcher.set_root_hidden(Path::new(root), self.config.root_config(root).exclude_hidden);
+        }
+        if let Some(reason) = matcher.explain(&path, is_dir, size) {
+            return Some(format!("Exclu: {}", reason));
+        }
+
+        let mut ignore = IgnoreTree::new();
+        for root in &self.scan_paths {
+            ignore.add_root(Path::new(root), self.config.root_config(root).respect_vcs_ignore);
+        }
+        if ignore.is_ignored(&path, is_dir) {
+            return Some("Exclu: règle d'un fichier .gitignore, .ignore ou .xfinderignore".to_string());
+        }
+        if !self.scan_paths.iter().any(|root| path.starts_with(root)) {
+            return Some("Hors des dossiers à indexer".to_string());
+        }
+        None
+    }
+
+    // Lire ou non les .gitignore d'un dossier indexé
+    // (pris en compte par le watchdog tout de suite, par le scan au prochain rafraîchissement)
+    pub fn set_respect_vcs_ignore(&mut self, path: &str, respect_vcs_ignore: bool) {
+        let mut root = self.config.root_config(path);
+        root.respect_vcs_ignore = respect_vcs_ignore;
+        self.config.set_root_config(root);
+        if let Some(ref mut watcher) = self.file_watcher {
+            watcher.set_respect_vcs_ignore(&PathBuf::from(path), respect_vcs_ignore);
+        }
+        self.save_config();
+    }
+
+    // Profondeur max parcourue sous un dossier indexé (None: illimitée)
+    // Prise en compte au prochain rafraîchissement
+    pub fn set_root_max_depth(&mut self, path: &str, max_depth: Option<usize>) {
+        let mut root = self.config.root_config(path);
+        root.max_depth = max_depth;
+        self.config.set_root_config(root);
+        self.save_config();
+    }
+
+    // Politique de parcours d'un dossier indexé (liens, cachés, montages)
+    // Les cachés sont pris en compte par le watchdog tout de suite, le reste
+    // au prochain rafraîchissement
+    pub fn set_root_traversal(&mut self, root: RootConfig) {
+        if let Some(ref mut watcher) = self.file_watcher {
+            watcher.set_root_hidden(&PathBuf::from(&root.path), root.exclude_hidden);
+        }
+        self.config.set_root_config(root);
+        self.save_config();
+    }
+
     pub fn remove_scan_path(&mut self, index: usize) {
         if index < self.scan_paths.len() {
-            self.scan_paths.remove(index);
+            let removed = self.scan_paths.remove(index);
+            self.config.roots.retain(|root| root.path != removed);
+            if let Some(ref db) = self.database {
+                let _ = db.delete_scan_report(&removed);
+            }
             self.save_config();
         }
     }
@@ -818,14 +1462,25 @@ impl XFinderApp {
     }
 
     pub fn perform_search(&mut self) {
+        self.query_error = None;
+        self.search_suggestions.clear();
+
         if self.search_query.trim().is_empty() {
             self.search_results.clear();
-            self.raw_search_results.clear();
+            self.search_total_hits = 0;
+            self.search_next_offset = 0;
+            self.folder_counts.clear();
+            self.last_search = None;
             return;
         }
 
+        let frecency = self.frecency_for_search();
+
         if let Some(ref index) = self.search_index {
             // Construire les options de recherche
+            // Les filtres (type, date, taille) et le tri sont appliqués par Tantivy,
+            // avant la limite de résultats
+            let (extensions, excluded_extensions) = self.filter_file_type.to_extension_filters();
             let options = SearchOptions {
                 exact_match: self.search_exact_match,
                 case_sensitive: self.search_case_sensitive,
@@ -833,22 +1488,55 @@ impl XFinderApp {
                 search_in_path: self.search_in_path,
                 fuzzy_search: self.search_fuzzy,
                 fuzzy_distance: self.fuzzy_distance,
+                extensions,
+                excluded_extensions,
+                size_min: self.filter_size_min,
+                size_max: self.filter_size_max,
+                modified_after: self.filter_date_after.and_then(|date| {
+                    date.and_hms_opt(0, 0, 0)
+                        .and_then(|dt| dt.and_local_timezone(chrono::Local).earliest())
+                        .map(|dt| dt.timestamp())
+                }),
+                sort_by: self.sort_by,
+                pattern_mode: self.search_pattern_mode,
+                scope: self.search_scope.clone(),
+                frecency,
             };
 
-            // Cherche jusqu'à 10000 résultats pour infinite scroll
-            match index.search(&self.search_query, 10000, options) {
-                Ok(results) => {
-                    // Stocker les résultats bruts de Tantivy
-                    self.raw_search_results = results;
-                    self.results_display_limit = 50; // Reset à 50
-                    // Appliquer les filtres et le tri (copie depuis raw_search_results)
-                    self.apply_filters_and_sort();
+            // Première page seulement, les suivantes via load_more_results
+            match index.search_page(&self.search_query, 0, self.results_display_limit, options.clone()) {
+                Ok(page) => {
+                    let results = page.results;
+                    // Répartition par dossier pour le panneau latéral
+                    self.folder_counts = if results.is_empty() {
+                        Vec::new()
+                    } else {
+                        index
+                            .folder_counts(&self.search_query, FOLDER_COUNTS_LIMIT, &options)
+                            .unwrap_or_default()
+                    };
+                    // Aucun résultat: proposer des corrections (même sans recherche floue)
+                    if results.is_empty() && self.search_pattern_mode == PatternMode::Text {
+                        self.search_suggestions = index
+                            .suggest(&self.search_query, MAX_SUGGESTIONS)
+                            .unwrap_or_default();
+                    }
+                    self.search_results = results;
+                    self.search_total_hits = page.total;
+                    self.search_next_offset = page.consumed;
+                    self.last_search = Some((self.search_query.clone(), options));
                     // Ne pas effacer error_message pour garder les infos d'indexation
                 }
                 Err(e) => {
-                    self.error_message = Some(format!("Erreur recherche: {}", e));
-                    self.search_results.clear();
-                    self.raw_search_results.clear();
+                    if let Some(parse_error) = e.downcast_ref::<QueryParseError>() {
+                        // Requête en cours de saisie (ex: "rapport OR"): garder les résultats précédents
+                        self.query_error = Some(parse_error.to_string());
+                    } else {
+                        self.error_message = Some(format!("Erreur recherche: {}", e));
+                        self.search_results.clear();
+                        self.search_total_hits = 0;
+                        self.last_search = None;
+                    }
                 }
             }
         } else {
@@ -859,112 +1547,200 @@ impl XFinderApp {
                 self.perform_search();
                 return;
             }
+            if let Some(ref rebuild) = self.index_rebuild {
+                self.error_message = Some(format!(
+                    "Reconstruction de l'index en cours ({}/{}): recherche disponible à la fin",
+                    rebuild.done, rebuild.total
+                ));
+                return;
+            }
             self.error_message =
                 Some("Index non charge. Lancez une indexation d'abord.".to_string());
         }
     }
 
-    // Applique les filtres et le tri sur les résultats de recherche
-    pub fn apply_filters_and_sort(&mut self) {
-        // Toujours partir d'une copie fraîche des résultats bruts de Tantivy
-        // Cela permet de changer de filtre sans perdre les résultats originaux
-        self.search_results = self.raw_search_results.clone();
+    // Scores de frecency à mélanger au classement (vide si désactivé)
+    fn frecency_for_search(&mut self) -> Vec<(String, f64)> {
+        if !self.config.ui.frecency_enabled {
+            return Vec::new();
+        }
+        let Some(ref db) = self.database else {
+            return Vec::new();
+        };
 
-        // 1. Filtrer par type de fichier
-        if self.filter_file_type != FileTypeFilter::All {
-            self.search_results.retain(|result| {
-                self.filter_file_type.matches(&result.filename)
-            });
+        if self.frecency_scores.is_none() {
+            self.frecency_scores = Some(db.get_frecency_scores(FRECENCY_SEARCH_LIMIT).unwrap_or_default());
         }
+        self.frecency_scores.clone().unwrap_or_default()
+    }
 
-        // 2. Filtrer par date (après une certaine date)
-        if let Some(ref date_after) = self.filter_date_after {
-            self.search_results.retain(|result| {
-                if let Some(ref modified_str) = result.modified {
-                    // Parse la date au format "YYYY-MM-DD HH:MM:SS"
-                    if let Some(date_part) = modified_str.split(' ').next() {
-                        if let Ok(file_date) = chrono::NaiveDate::parse_from_str(date_part, "%Y-%m-%d") {
-                            return file_date >= *date_after;
-                        }
-                    }
+    // Enregistre l'ouverture d'un résultat (Ouvrir, Prévisualiser, Dossier)
+    pub fn record_open(&mut self, path: &str, action: OpenAction) {
+        if let Some(ref db) = self.database {
+            if db.add_open_event(path, action).is_ok() {
+                self.frecency_scores = None;
+            }
+        }
+    }
+
+    // Efface l'historique d'ouverture et rafraîchit le classement
+    pub fn clear_open_history(&mut self) {
+        if let Some(ref db) = self.database {
+            match db.clear_open_events() {
+                Ok(count) => {
+                    self.error_message = Some(format!("Historique d'ouverture effacé ({} entrées)", count));
                 }
-                // Garder si pas de date de modification ou erreur de parsing
-                true
-            });
+                Err(e) => {
+                    self.error_message = Some(format!("Erreur effacement historique: {}", e));
+                }
+            }
         }
+        self.frecency_scores = None;
+        self.perform_search();
+    }
 
-        // 3. Filtrer par taille
-        if let Some(min_size) = self.filter_size_min {
-            self.search_results.retain(|result| result.size_bytes >= min_size);
-        }
-        if let Some(max_size) = self.filter_size_max {
-            self.search_results.retain(|result| result.size_bytes <= max_size);
-        }
-
-        // 4. Trier les résultats
-        match self.sort_by {
-            SortBy::Relevance => {
-                // Déjà trié par score de Tantivy
-            },
-            SortBy::NameAsc => {
-                self.search_results.sort_by(|a, b| a.filename.to_lowercase().cmp(&b.filename.to_lowercase()));
-            },
-            SortBy::NameDesc => {
-                self.search_results.sort_by(|a, b| b.filename.to_lowercase().cmp(&a.filename.to_lowercase()));
-            },
-            SortBy::DateAsc => {
-                self.search_results.sort_by(|a, b| {
-                    match (&a.modified, &b.modified) {
-                        (Some(d1), Some(d2)) => d1.cmp(d2),
-                        (Some(_), None) => std::cmp::Ordering::Less,
-                        (None, Some(_)) => std::cmp::Ordering::Greater,
-                        (None, None) => std::cmp::Ordering::Equal,
-                    }
-                });
-            },
-            SortBy::DateDesc => {
-                self.search_results.sort_by(|a, b| {
-                    match (&a.modified, &b.modified) {
-                        (Some(d1), Some(d2)) => d2.cmp(d1),
-                        (Some(_), None) => std::cmp::Ordering::Less,
-                        (None, Some(_)) => std::cmp::Ordering::Greater,
-                        (None, None) => std::cmp::Ordering::Equal,
-                    }
-                });
-            },
-            SortBy::SizeAsc => {
-                self.search_results.sort_by(|a, b| a.size_bytes.cmp(&b.size_bytes));
-            },
-            SortBy::SizeDesc => {
-                self.search_results.sort_by(|a, b| b.size_bytes.cmp(&a.size_bytes));
-            },
+    // Saisie dans la barre de recherche: complétions immédiates (dictionnaire
+    // de termes, quelques ms), recherche complète après SEARCH_DEBOUNCE sans frappe
+    pub fn on_search_query_changed(&mut self) {
+        self.completion_selected = None;
+        self.search_pending_since = Some(Instant::now());
+
+        if self.top_searches.is_none() {
+            if let Some(ref db) = self.database {
+                self.top_searches = Some(db.get_top_searches(COMPLETION_HISTORY_LIMIT).unwrap_or_default());
+            }
+        }
+
+        self.search_completions = match self.search_index {
+            // Pas de complétion de mots pour un motif glob/regex
+            Some(ref index) if !self.search_query.trim().is_empty() && self.search_pattern_mode == PatternMode::Text => index
+                .complete(&self.search_query, self.top_searches.as_deref().unwrap_or_default(), MAX_COMPLETIONS)
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        };
+    }
+
+    // Lance la recherche en attente une fois la saisie terminée
+    pub fn process_search_debounce(&mut self, ctx: &egui::Context) {
+        let Some(since) = self.search_pending_since else {
+            return;
+        };
+        let elapsed = since.elapsed();
+        if elapsed >= SEARCH_DEBOUNCE {
+            self.search_pending_since = None;
+            self.perform_search();
+        } else {
+            ctx.request_repaint_after(SEARCH_DEBOUNCE - elapsed);
+        }
+    }
+
+    // Recherche validée (Entrée, bouton, complétion choisie): immédiate et
+    // enregistrée dans l'historique
+    pub fn submit_search(&mut self) {
+        self.search_pending_since = None;
+        self.search_completions.clear();
+        self.completion_selected = None;
+
+        let start = Instant::now();
+        self.perform_search();
+        if self.query_error.is_none() {
+            self.record_search(start.elapsed());
+        }
+    }
+
+    // Remplace la requête par la complétion choisie et la lance
+    pub fn accept_completion(&mut self, idx: usize) {
+        if let Some(completion) = self.search_completions.get(idx) {
+            self.search_query = completion.text.clone();
+            self.submit_search();
         }
     }
 
+    fn record_search(&mut self, elapsed: Duration) {
+        let query = self.search_query.trim();
+        let Some(ref db) = self.database else {
+            return;
+        };
+        if query.is_empty() {
+            return;
+        }
+
+        let now = chrono::Utc::now();
+        let record = SearchHistoryRecord {
+            id: format!("search-{}", now.timestamp_nanos_opt().unwrap_or_default()),
+            query: query.to_string(),
+            results_count: self.search_total_hits as u32,
+            execution_time_ms: elapsed.as_millis() as u32,
+            timestamp: now.timestamp(),
+        };
+        if db.add_search_history(&record).is_ok() {
+            self.top_searches = None;
+        }
+    }
+
+    // Limite la recherche à des dossiers (vide = partout) et la relance
+    pub fn set_search_scope(&mut self, scope: Vec<String>) {
+        self.search_scope = scope;
+        self.perform_search();
+    }
+
+    // Reste-t-il des correspondances à charger ?
+    pub fn has_more_results(&self) -> bool {
+        self.last_search.is_some() && self.search_next_offset < self.search_total_hits
+    }
+
+    // Charge la page suivante de la dernière recherche (mêmes requête et options)
     pub fn load_more_results(&mut self) {
-        self.results_display_limit += 50;
+        let (Some(ref index), Some((query, options))) = (&self.search_index, &self.last_search) else {
+            return;
+        };
+        match index.search_page(query, self.search_next_offset, self.results_display_limit, options.clone()) {
+            Ok(page) => {
+                self.search_results.extend(page.results);
+                self.search_total_hits = page.total;
+                self.search_next_offset += page.consumed;
+            }
+            Err(e) => {
+                self.error_message = Some(format!("Erreur recherche: {}", e));
+            }
+        }
     }
 
     // Active le watchdog sur tous les dossiers surveillés
     pub fn enable_watchdog(&mut self) {
-        if self.watchdog_enabled {
+        if self.watchdog_enabled && self.file_watcher.is_some() {
             return; // Déjà activé
         }
+        self.start_watchdog(true);
+    }
+
+    // Démarre le watchdog, avec ou sans réconciliation préalable (inutile
+    // juste après une indexation)
+    fn start_watchdog(&mut self, reconcile: bool) {
+        if self.file_watcher.is_some() {
+            return;
+        }
 
         match FileWatcher::new() {
             Ok(mut watcher) => {
-                // Surveiller tous les dossiers
-                for path_str in &self.scan_paths {
-                    let path = PathBuf::from(path_str);
-                    if path.exists() {
-                        if let Err(e) = watcher.watch_path(&path) {
+                watcher.set_exclusions(&self.exclusions_config());
+                watcher.set_quiet_window(Duration::from_millis(self.config.ui.watchdog_quiet_ms));
+                // Avant les watch_root: aucun dossier scruté encore
+                let _ = watcher.set_poll_interval(Duration::from_secs(self.config.ui.watchdog_poll_secs.max(1)));
+                self.file_watcher = Some(watcher);
+
+                // Surveiller tous les dossiers, ou chacun à la fin de sa
+                // réconciliation (voir process_startup_reconcile)
+                if !(reconcile && self.start_reconcile()) {
+                    for path_str in self.scan_paths.clone() {
+                        if let Err(e) = self.watch_scan_root(&path_str) {
+                            self.file_watcher = None;
                             self.error_message = Some(format!("Erreur watchdog {}: {}", path_str, e));
                             return;
                         }
                     }
                 }
 
-                self.file_watcher = Some(watcher);
                 self.watchdog_enabled = true;
                 self.save_config();
                 self.error_message = Some(format!("Watchdog active sur {} dossiers", self.scan_paths.len()));
@@ -975,8 +1751,158 @@ impl XFinderApp {
         }
     }
 
+    // Fenêtre de calme du watchdog: une rafale d'événements sur un fichier
+    // n'est appliquée qu'une fois le fichier calme
+    pub fn set_watchdog_quiet_ms(&mut self, quiet_ms: u64) {
+        self.config.ui.watchdog_quiet_ms = quiet_ms;
+        if let Some(ref mut watcher) = self.file_watcher {
+            watcher.set_quiet_window(Duration::from_millis(quiet_ms));
+        }
+        self.save_config();
+    }
+
+    // Surveille un dossier indexé avec ses règles (fichiers ignore, cachés)
+    fn watch_scan_root(&mut self, path_str: &str) -> anyhow::Result<()> {
+        let path = PathBuf::from(path_str);
+        let root_config = self.config.root_config(path_str);
+        let Some(ref mut watcher) = self.file_watcher else {
+            return Ok(());
+        };
+        if path.exists() {
+            watcher.watch_root(&path, root_config.respect_vcs_ignore)?;
+            watcher.set_root_hidden(&path, root_config.exclude_hidden);
+        }
+        Ok(())
+    }
+
+    // Réconciliation des dossiers surveillés avec la table files, en
+    // arrière-plan (voir StartupReconcile); false si elle n'a pas démarré
+    fn start_reconcile(&mut self) -> bool {
+        let Some(database) = self.database.clone() else {
+            return false; // Sans base, rien à quoi comparer
+        };
+        if self.search_index.is_none() {
+            self.load_index();
+        }
+
+        let roots: Vec<(PathBuf, ScanConfig)> = self
+            .scan_paths
+            .iter()
+            .filter(|path| Path::new(path.as_str()).exists())
+            .map(|path| (PathBuf::from(path), self.root_scan_config(path)))
+            .collect();
+
+        let (tx, rx) = unbounded::<ReconcileEvent>();
+        self.startup_reconcile = Some(StartupReconcile {
+            roots_total: roots.len(),
+            roots_done: 0,
+            current_root: String::new(),
+            checked: 0,
+            changes: 0,
+            rx,
+        });
+
+        std::thread::spawn(move || {
+            let folders = database.get_watched_folders().unwrap_or_default();
+            let (mounts, declared) = (MountTable::load(), MountTable::load_fstab());
+            for (root, scan_config) in roots {
+                let root_str = root.to_string_lossy().to_string();
+                let folder = folders.iter().find(|folder| folder.path == root_str);
+                // Partage pas encore monté: son point de montage vide n'est pas
+                // le signe que ses fichiers ont été supprimés
+                if mounts.is_unmounted(&root, &declared) {
+                    let _ = tx.send(ReconcileEvent::RootDone { root: root_str, events: Vec::new() });
+                    continue;
+                }
+                let known = database.get_file_states_under(&root_str).unwrap_or_default();
+                // Jamais indexé: c'est à l'indexation de le faire
+                if known.is_empty() && folder.and_then(|folder| folder.last_scan).is_none() {
+                    let _ = tx.send(ReconcileEvent::RootDone { root: root_str, events: Vec::new() });
+                    continue;
+                }
+
+                let started = chrono::Utc::now().timestamp();
+                let progress_tx = tx.clone();
+                let result = reconcile_root(
+                    &root,
+                    KnownFiles::new(known),
+                    folder.and_then(|folder| folder.last_scan),
+                    scan_config,
+                    |checked| {
+                        let _ = progress_tx.send(ReconcileEvent::Progress { root: root_str.clone(), checked });
+                    },
+                );
+                if result.complete {
+                    let _ = database.upsert_watched_folder(&WatchedFolderRecord {
+                        path: root_str.clone(),
+                        last_scan: Some(started),
+                        file_count: result.file_count,
+                        total_size: result.total_size,
+                        enabled: true,
+                        created_at: folder.map(|folder| folder.created_at).unwrap_or(started),
+                    });
+                }
+                let _ = tx.send(ReconcileEvent::RootDone { root: root_str, events: result.events });
+            }
+            let _ = tx.send(ReconcileEvent::Finished);
+        });
+        true
+    }
+
+    // Suit la réconciliation au démarrage: un dossier réconcilié est mis
+    // sous surveillance, ses changements en tête de file
+    fn process_startup_reconcile(&mut self) {
+        let Some(ref mut reconcile) = self.startup_reconcile else {
+            return;
+        };
+        let mut finished = false;
+        let mut done = Vec::new();
+        while let Ok(event) = reconcile.rx.try_recv() {
+            match event {
+                ReconcileEvent::Progress { root, checked } => {
+                    reconcile.current_root = root;
+                    reconcile.checked = checked;
+                }
+                ReconcileEvent::RootDone { root, events } => {
+                    reconcile.roots_done += 1;
+                    reconcile.changes += events.len();
+                    done.push((root, events));
+                }
+                ReconcileEvent::Finished => finished = true,
+            }
+        }
+        let changes = reconcile.changes;
+
+        for (root, events) in done {
+            if let Some(ref watcher) = self.file_watcher {
+                watcher.queue_events(events);
+            }
+            if let Err(e) = self.watch_scan_root(&root) {
+                self.error_message = Some(format!("Erreur watchdog {}: {}", root, e));
+            }
+        }
+        if finished {
+            self.startup_reconcile = None;
+            if changes > 0 {
+                self.error_message = Some(format!("{} changements depuis la derniere session, mise a jour de l'index", changes));
+            }
+        }
+    }
+
+    // Intervalle de scrutation des dossiers sans événements natifs
+    pub fn set_watchdog_poll_secs(&mut self, poll_secs: u64) {
+        self.config.ui.watchdog_poll_secs = poll_secs.max(1);
+        if let Some(ref mut watcher) = self.file_watcher {
+            if let Err(e) = watcher.set_poll_interval(Duration::from_secs(self.config.ui.watchdog_poll_secs)) {
+                self.error_message = Some(format!("Erreur watchdog: {}", e));
+            }
+        }
+        self.save_config();
+    }
+
     // Désactive le watchdog
     pub fn disable_watchdog(&mut self) {
+        self.startup_reconcile = None;
         self.file_watcher = None;
         self.watchdog_enabled = false;
         self.save_config();
@@ -990,15 +1916,17 @@ impl XFinderApp {
             return;
         }
 
+        // Pendant une reconstruction, les événements restent en file d'attente
+        // et seront appliqués au nouvel index
+        if self.index_rebuild.is_some() {
+            return;
+        }
+
+        self.process_startup_reconcile();
+
         if let Some(ref watcher) = self.file_watcher {
             if let Some(ref index) = self.search_index {
-                match watcher.apply_events_batch(
-                    index,
-                    self.database.as_ref(),
-                    &self.excluded_extensions,
-                    &self.excluded_patterns,
-                    &self.excluded_dirs
-                ) {
+                match watcher.apply_events_batch(index, self.database.as_ref()) {
                     Ok(count) if count > 0 => {
                         self.watchdog_update_count += count;
                         self.index_status.file_count += count; // Approximatif
@@ -1010,6 +1938,65 @@ impl XFinderApp {
                 }
             }
         }
+
+        self.process_watchdog_resyncs();
+    }
+
+    // Événements perdus par le watchdog (rafale trop grosse, débordement du
+    // noyau): les dossiers concernés sont resynchronisés en arrière-plan,
+    // comme un rafraîchissement incrémental limité à chacun d'eux
+    fn process_watchdog_resyncs(&mut self) {
+        self.collect_watchdog_resyncs();
+
+        if let Some(ref watcher) = self.file_watcher {
+            for root in watcher.take_dirty_roots() {
+                if !self.watchdog_dirty_roots.contains(&root) {
+                    self.watchdog_dirty_roots.push(root);
+                }
+            }
+        }
+
+        // Une indexation en cours pourrait avoir déjà passé ces dossiers: attendre
+        // sa fin. Un dossier déjà en cours de resynchronisation est refait ensuite.
+        if self.indexing_in_progress || self.watchdog_dirty_roots.is_empty() {
+            return;
+        }
+        let (Some(index), Some(database)) = (self.search_index.clone(), self.database.clone()) else {
+            return;
+        };
+        let ready: Vec<PathBuf> = self
+            .watchdog_dirty_roots
+            .iter()
+            .filter(|root| !self.watchdog_resyncing.contains(root))
+            .cloned()
+            .collect();
+        for root in ready {
+            self.watchdog_dirty_roots.retain(|path| *path != root);
+            self.watchdog_resyncing.push(root.clone());
+
+            let scan_config = self.root_scan_config(&root.to_string_lossy());
+            let (index, database, tx) = (index.clone(), database.clone(), self.resync_tx.clone());
+            std::thread::spawn(move || {
+                let result = sync_root(&index, &database, &root, scan_config).map_err(|e| e.to_string());
+                let _ = tx.send((root, result));
+            });
+        }
+    }
+
+    // Résultats des resynchronisations terminées
+    fn collect_watchdog_resyncs(&mut self) {
+        while let Ok((root, result)) = self.resync_rx.try_recv() {
+            self.watchdog_resyncing.retain(|path| *path != root);
+            match result {
+                Ok(summary) => {
+                    self.watchdog_resync_count += 1;
+                    self.watchdog_update_count += summary.added + summary.updated + summary.removed;
+                }
+                Err(e) => {
+                    self.error_message = Some(format!("Erreur resynchronisation {}: {}", root.display(), e));
+                }
+            }
+        }
     }
 
     // Traiter les messages de progression de l'indexation
@@ -1047,16 +2034,20 @@ impl XFinderApp {
     fn process_indexing_progress(&mut self) {
         let mut is_done = false;
         let mut final_count = 0;
+        let mut sync = None;
 
         if let Some(ref rx) = self.progress_rx {
             while let Ok(progress) = rx.try_recv() {
                 self.index_status.current_indexed = progress.indexed_count;
                 self.index_status.total_to_index = progress.total_files;
+                self.index_status.files_per_sec = progress.files_per_sec;
+                self.index_status.scanning = progress.scanning;
 
                 // Si terminé
                 if progress.current_path == "Termine" {
                     is_done = true;
                     final_count = progress.indexed_count;
+                    sync = progress.sync;
                 }
             }
         }
@@ -1070,15 +2061,22 @@ impl XFinderApp {
                     .to_string(),
             );
             self.index_status.indexed_path = Some(self.scan_paths.join(", "));
-            self.error_message = Some(format!(
-                "{} fichiers indexes depuis {} dossiers",
-                final_count,
-                self.scan_paths.len()
-            ));
+            self.error_message = Some(match sync {
+                Some(summary) => format!("Index synchronise: {}", summary),
+                None => format!("{} fichiers indexes depuis {} dossiers", final_count, self.scan_paths.len()),
+            });
             self.progress_rx = None;
 
             // Recharger le nouvel index créé par le thread
-            self.load_index();
+            // (un refresh a écrit dans l'index déjà chargé: rien à recharger)
+            if self.search_index.is_none() {
+                self.load_index();
+            }
+
+            // Nouvelle indexation: le watchdog a été fermé avec l'ancien index
+            if self.watchdog_enabled && self.file_watcher.is_none() {
+                self.start_watchdog(false);
+            }
         }
     }
 
@@ -1226,6 +2224,31 @@ impl XFinderApp {
     }
 }
 
+// Dossiers terminés d'un scan, avec leur racine (pour le point de reprise)
+fn take_completed_dirs<'a>(root: &'a str, scan: &crate::search::scanner::ScanHandle) -> impl Iterator<Item = (String, String)> + 'a {
+    scan.take_completed_dirs()
+        .into_iter()
+        .map(move |dir| (root.to_string(), dir.to_string_lossy().to_string()))
+}
+
+// Point de reprise: valide la base puis l'index, et seulement ensuite
+// enregistre les dossiers terminés (leurs fichiers sont alors durables)
+fn commit_checkpoint(
+    db: &Database,
+    writer: &IndexWriterHandle,
+    db_batch: &mut Vec<FileRecord>,
+    completed_dirs: &mut Vec<(String, String)>,
+    files_done: usize,
+) {
+    if db.batch_upsert_files(db_batch).is_err() || writer.flush().is_err() {
+        return; // Réessayé au prochain point de reprise
+    }
+    db_batch.clear();
+    if db.save_checkpoint(files_done as u64, completed_dirs).is_ok() {
+        completed_dirs.clear();
+    }
+}
+
 impl eframe::App for XFinderApp {
     fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
         // Lazy initialization au premier frame (DB, Tray, Hotkey)
@@ -1261,12 +2284,23 @@ impl eframe::App for XFinderApp {
         // Traiter la progression de l'indexation
         self.process_indexing_progress();
 
+        // Indexation planifiée: synchronisation incrémentale
+        if self.scheduled_sync.swap(false, Ordering::Relaxed) && !self.indexing_in_progress {
+            self.refresh_index();
+        }
+
+        // Traiter la reconstruction de l'index (migration de schéma / n-grams)
+        self.process_index_rebuild();
+
         // Traiter les statistiques d'indexation sémantique
         self.process_semantic_indexing_stats();
 
         // Traiter les résultats de recherche sémantique
         self.process_search_results();
 
+        // Recherche différée pendant la saisie
+        self.process_search_debounce(ctx);
+
         render_top_panel(ctx, self);
         render_side_panel(ctx, self);
 
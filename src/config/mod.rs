@@ -23,6 +23,40 @@ pub struct AppConfig {
 
     #[serde(default)]
     pub assist_me: AssistMeConfig,
+
+    /// Options propres à chaque dossier indexé (absent: valeurs par défaut)
+    #[serde(default)]
+    pub roots: Vec<RootConfig>,
+}
+
+/// Options d'un dossier indexé
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RootConfig {
+    pub path: String,
+
+    /// Respecter les .gitignore (.ignore et .xfinderignore sont toujours lus)
+    #[serde(default = "default_true")]
+    pub respect_vcs_ignore: bool,
+
+    /// Profondeur max parcourue sous le dossier (absent: illimitée)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_depth: Option<usize>,
+
+    /// Suivre les liens symboliques (un dossier déjà visité n'est pas reparcouru)
+    #[serde(default)]
+    pub follow_symlinks: bool,
+
+    /// Exclure les fichiers et dossiers cachés (absent: réglage global des exclusions)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exclude_hidden: Option<bool>,
+
+    /// Rester sur le système de fichiers du dossier (ne pas traverser les montages)
+    #[serde(default)]
+    pub same_filesystem: bool,
+
+    /// Ignorer les pseudo-systèmes de fichiers (proc, sysfs, devtmpfs, tmpfs...)
+    #[serde(default = "default_true")]
+    pub skip_pseudo_fs: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,6 +69,14 @@ pub struct ExclusionsConfig {
 
     #[serde(default)]
     pub dirs: Vec<String>,
+
+    /// Exclure les fichiers et dossiers cachés (nom commençant par ".")
+    #[serde(default)]
+    pub hidden: bool,
+
+    /// Taille max d'un fichier indexé, en Mo (0 = pas de limite)
+    #[serde(default)]
+    pub max_file_size_mb: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -60,8 +102,20 @@ pub struct UiConfig {
     #[serde(default)]
     pub watchdog_enabled: bool,
 
+    /// Calme (ms) attendu avant d'appliquer les changements d'un fichier surveillé
+    #[serde(default = "default_watchdog_quiet_ms")]
+    pub watchdog_quiet_ms: u64,
+
+    /// Intervalle (s) de scrutation des dossiers sans événements natifs (partages réseau, FUSE)
+    #[serde(default = "default_watchdog_poll_secs")]
+    pub watchdog_poll_secs: u64,
+
     #[serde(default)]
     pub minimize_to_tray: bool,
+
+    /// Favoriser les fichiers souvent ouverts dans le tri par pertinence
+    #[serde(default = "default_true")]
+    pub frecency_enabled: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -154,6 +208,14 @@ fn default_results_display_limit() -> usize {
     50
 }
 
+fn default_watchdog_quiet_ms() -> u64 {
+    300
+}
+
+fn default_watchdog_poll_secs() -> u64 {
+    30
+}
+
 fn default_scheduler_hour() -> u32 {
     2  // 2h AM
 }
@@ -189,6 +251,8 @@ impl Default for ExclusionsConfig {
             extensions: default_excluded_extensions(),
             patterns: default_excluded_patterns(),
             dirs: Vec::new(),
+            hidden: false,
+            max_file_size_mb: 0,
         }
     }
 }
@@ -209,7 +273,10 @@ impl Default for UiConfig {
         Self {
             results_display_limit: default_results_display_limit(),
             watchdog_enabled: false,
+            watchdog_quiet_ms: default_watchdog_quiet_ms(),
+            watchdog_poll_secs: default_watchdog_poll_secs(),
             minimize_to_tray: true,
+            frecency_enabled: true,
         }
     }
 }
@@ -248,6 +315,43 @@ impl Default for AppConfig {
             ui: UiConfig::default(),
             system: SystemConfig::default(),
             assist_me: AssistMeConfig::default(),
+            roots: Vec::new(),
+        }
+    }
+}
+
+impl ExclusionsConfig {
+    /// Aucune exclusion
+    pub fn none() -> Self {
+        Self {
+            extensions: Vec::new(),
+            patterns: Vec::new(),
+            dirs: Vec::new(),
+            hidden: false,
+            max_file_size_mb: 0,
+        }
+    }
+}
+
+impl RootConfig {
+    /// Options par défaut d'un dossier
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            respect_vcs_ignore: true,
+            max_depth: None,
+            follow_symlinks: false,
+            exclude_hidden: None,
+            same_filesystem: false,
+            skip_pseudo_fs: true,
+        }
+    }
+
+    /// Exclusions appliquées sous ce dossier (réglage des cachés propre au dossier)
+    pub fn exclusions(&self, global: &ExclusionsConfig) -> ExclusionsConfig {
+        ExclusionsConfig {
+            hidden: self.exclude_hidden.unwrap_or(global.hidden),
+            ..global.clone()
         }
     }
 }
@@ -285,6 +389,24 @@ impl AppConfig {
         Ok(())
     }
 
+    /// Options d'un dossier indexé (valeurs par défaut s'il n'en a pas)
+    pub fn root_config(&self, path: &str) -> RootConfig {
+        self.roots
+            .iter()
+            .find(|root| root.path == path)
+            .cloned()
+            .unwrap_or_else(|| RootConfig::new(path))
+    }
+
+    /// Enregistre les options d'un dossier (remplace les précédentes)
+    pub fn set_root_config(&mut self, root: RootConfig) {
+        self.roots.retain(|existing| existing.path != root.path);
+        // Les options par défaut n'ont pas besoin d'être écrites
+        if root != RootConfig::new(&root.path) {
+            self.roots.push(root);
+        }
+    }
+
     /// Chemin par défaut du fichier de config
     pub fn default_path() -> PathBuf {
         dirs::home_dir()
@@ -337,4 +459,78 @@ mod tests {
         assert!(toml_str.contains("[indexing]"));
         assert!(toml_str.contains("[ui]"));
     }
+
+    #[test]
+    fn test_root_config() {
+        use tempfile::tempdir;
+
+        let dir = tempdir().unwrap();
+        let config_path = dir.path().join("test_config.toml");
+
+        let mut config = AppConfig::default();
+        assert!(config.root_config("/projets").respect_vcs_ignore);
+
+        config.set_root_config(RootConfig {
+            respect_vcs_ignore: false,
+            ..RootConfig::new("/projets")
+        });
+        config.save(&config_path).unwrap();
+
+        let loaded = AppConfig::load(&config_path).unwrap();
+        assert!(!loaded.root_config("/projets").respect_vcs_ignore);
+        assert!(loaded.root_config("/autre").respect_vcs_ignore);
+
+        // Retour aux valeurs par défaut: l'entrée disparaît
+        config.set_root_config(RootConfig::new("/projets"));
+        assert!(config.roots.is_empty());
+    }
+
+    #[test]
+    fn test_root_config_max_depth() {
+        use tempfile::tempdir;
+
+        let dir = tempdir().unwrap();
+        let config_path = dir.path().join("test_config.toml");
+
+        // Profondeur illimitée par défaut
+        let mut config = AppConfig::default();
+        assert_eq!(config.root_config("/projets").max_depth, None);
+
+        config.set_root_config(RootConfig {
+            max_depth: Some(3),
+            ..RootConfig::new("/projets")
+        });
+        config.save(&config_path).unwrap();
+
+        let loaded = AppConfig::load(&config_path).unwrap();
+        assert_eq!(loaded.root_config("/projets").max_depth, Some(3));
+        assert_eq!(loaded.root_config("/autre").max_depth, None);
+    }
+
+    #[test]
+    fn test_root_config_traversal() {
+        use tempfile::tempdir;
+
+        let dir = tempdir().unwrap();
+        let config_path = dir.path().join("test_config.toml");
+
+        // Parcours: liens non suivis, pseudo-FS ignorés, cachés selon le réglage global
+        let mut config = AppConfig::default();
+        let defaults = config.root_config("/autre");
+        assert!(!defaults.follow_symlinks && !defaults.same_filesystem && defaults.skip_pseudo_fs);
+        assert_eq!(defaults.exclude_hidden, None);
+
+        config.set_root_config(RootConfig {
+            follow_symlinks: true,
+            exclude_hidden: Some(false),
+            same_filesystem: true,
+            skip_pseudo_fs: false,
+            ..RootConfig::new("/projets")
+        });
+        config.save(&config_path).unwrap();
+
+        let loaded = AppConfig::load(&config_path).unwrap().root_config("/projets");
+        assert!(loaded.follow_symlinks && loaded.same_filesystem && !loaded.skip_pseudo_fs);
+        assert_eq!(loaded.exclude_hidden, Some(false));
+    }
 }
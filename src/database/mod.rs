@@ -115,6 +115,16 @@ impl Database {
         self.with_conn(|conn| queries::get_file_by_path(conn, path))
     }
 
+    /// (chemin, taille, date de modification) de tous les fichiers indexés
+    pub fn get_file_states(&self) -> Result<Vec<(String, u64, i64)>> {
+        self.with_conn(queries::get_file_states)
+    }
+
+    /// États des fichiers indexés sous un dossier
+    pub fn get_file_states_under(&self, dir: &str) -> Result<Vec<(String, u64, i64)>> {
+        self.with_conn(|conn| queries::get_file_states_under(conn, dir))
+    }
+
     /// Supprime un fichier
     pub fn delete_file(&self, path: &str) -> Result<()> {
         self.with_conn(|conn| queries::delete_file(conn, path))
@@ -125,6 +135,31 @@ impl Database {
         self.with_conn(|conn| queries::batch_delete_files(conn, paths))
     }
 
+    /// Renomme un fichier
+    pub fn rename_file(&self, from: &str, to: &str) -> Result<bool> {
+        self.with_conn(|conn| queries::rename_file(conn, from, to))
+    }
+
+    /// Dossier déplacé: réécrit les chemins de ses fichiers
+    pub fn rename_dir(&self, from: &str, to: &str) -> Result<usize> {
+        self.with_conn(|conn| queries::rename_dir(conn, from, to))
+    }
+
+    /// Supprime les fichiers d'un dossier (tous niveaux)
+    pub fn delete_files_under(&self, dir: &str) -> Result<usize> {
+        self.with_conn(|conn| queries::delete_files_under(conn, dir))
+    }
+
+    /// Enregistre un dossier surveillé (date du dernier parcours complet...)
+    pub fn upsert_watched_folder(&self, folder: &queries::WatchedFolderRecord) -> Result<()> {
+        self.with_conn(|conn| queries::upsert_watched_folder(conn, folder))
+    }
+
+    /// Dossiers surveillés actifs
+    pub fn get_watched_folders(&self) -> Result<Vec<queries::WatchedFolderRecord>> {
+        self.with_conn(queries::get_watched_folders)
+    }
+
     /// Compte total de fichiers
     pub fn count_files(&self) -> Result<u64> {
         self.with_conn(|conn| queries::count_files(conn))
@@ -145,6 +180,29 @@ impl Database {
         self.with_conn(|conn| queries::get_top_searches(conn, limit))
     }
 
+    /// Enregistre l'ouverture d'un résultat (frecency)
+    pub fn add_open_event(&self, path: &str, action: queries::OpenAction) -> Result<()> {
+        let now = chrono::Utc::now().timestamp();
+        self.with_conn(|conn| queries::add_open_event(conn, path, action, now))
+    }
+
+    /// Scores de frecency des fichiers les plus ouverts
+    pub fn get_frecency_scores(&self, limit: u32) -> Result<Vec<(String, f64)>> {
+        let now = chrono::Utc::now().timestamp();
+        self.with_conn(|conn| queries::get_frecency_scores(conn, now, limit))
+    }
+
+    /// Efface l'historique d'ouverture
+    pub fn clear_open_events(&self) -> Result<usize> {
+        self.with_conn(queries::clear_open_events)
+    }
+
+    /// Purge les événements d'ouverture trop anciens
+    pub fn cleanup_old_open_events(&self) -> Result<usize> {
+        let now = chrono::Utc::now().timestamp();
+        self.with_conn(|conn| queries::cleanup_old_open_events(conn, now))
+    }
+
     /// Ajoute un log d'erreur
     pub fn add_error_log(&self, record: &queries::ErrorLogRecord) -> Result<()> {
         self.with_conn(|conn| queries::add_error_log(conn, record))
@@ -165,6 +223,53 @@ impl Database {
         self.with_conn(|conn| queries::get_config(conn, key))
     }
 
+    /// Enregistre le bilan du scan d'un dossier (remplace le précédent)
+    pub fn save_scan_report(&self, report: &queries::ScanReportRecord) -> Result<()> {
+        self.with_conn(|conn| queries::save_scan_report(conn, report))
+    }
+
+    /// Bilans du dernier scan de chaque dossier
+    pub fn get_scan_reports(&self) -> Result<Vec<queries::ScanReportRecord>> {
+        self.with_conn(queries::get_scan_reports)
+    }
+
+    /// Oublie le bilan d'un dossier
+    pub fn delete_scan_report(&self, root: &str) -> Result<()> {
+        self.with_conn(|conn| queries::delete_scan_report(conn, root))
+    }
+
+    /// Démarre le point de reprise d'une nouvelle indexation
+    pub fn begin_checkpoint(&self, mode: queries::CheckpointMode) -> Result<()> {
+        let now = chrono::Utc::now().timestamp();
+        self.with_conn(|conn| queries::begin_checkpoint(conn, mode, now))
+    }
+
+    /// Avance le point de reprise (dossiers terminés: (racine, chemin))
+    pub fn save_checkpoint(&self, files_done: u64, completed: &[(String, String)]) -> Result<()> {
+        let now = chrono::Utc::now().timestamp();
+        self.with_conn(|conn| queries::save_checkpoint(conn, files_done, completed, now))
+    }
+
+    /// Mémorise la pause de l'indexation en cours
+    pub fn set_checkpoint_paused(&self, paused: bool) -> Result<()> {
+        self.with_conn(|conn| queries::set_checkpoint_paused(conn, paused))
+    }
+
+    /// Point de reprise d'une indexation interrompue
+    pub fn get_checkpoint(&self) -> Result<Option<queries::CheckpointRecord>> {
+        self.with_conn(queries::get_checkpoint)
+    }
+
+    /// Dossiers déjà terminés par l'indexation interrompue
+    pub fn get_checkpoint_dirs(&self) -> Result<Vec<String>> {
+        self.with_conn(queries::get_checkpoint_dirs)
+    }
+
+    /// Indexation terminée: supprime le point de reprise
+    pub fn clear_checkpoint(&self) -> Result<()> {
+        self.with_conn(queries::clear_checkpoint)
+    }
+
     /// Trouve tous les fichiers dupliqués (même hash blake3)
     pub fn find_duplicates(&self) -> Result<Vec<queries::DuplicateGroup>> {
         self.with_conn(|conn| queries::find_duplicates(conn))
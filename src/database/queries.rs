@@ -108,6 +108,27 @@ pub fn get_file_by_path(conn: &Connection, path: &str) -> Result<Option<FileReco
     }
 }
 
+/// (chemin, taille, date de modification) de tous les fichiers indexés
+/// Sert à la synchronisation incrémentale (voir search::incremental)
+pub fn get_file_states(conn: &Connection) -> Result<Vec<(String, u64, i64)>> {
+    let mut stmt = conn.prepare("SELECT path, size, modified FROM files")?;
+    let rows = stmt.query_map([], |row| {
+        Ok((row.get(0)?, row.get::<_, i64>(1)? as u64, row.get(2)?))
+    })?;
+
+    rows.collect()
+}
+
+/// Comme get_file_states, limité aux fichiers sous un dossier (tous niveaux)
+pub fn get_file_states_under(conn: &Connection, dir: &str) -> Result<Vec<(String, u64, i64)>> {
+    let mut stmt = conn.prepare("SELECT path, size, modified FROM files WHERE substr(path, 1, length(?1)) = ?1")?;
+    let rows = stmt.query_map(params![dir_prefix(dir)], |row| {
+        Ok((row.get(0)?, row.get::<_, i64>(1)? as u64, row.get(2)?))
+    })?;
+
+    rows.collect()
+}
+
 /// Supprime un fichier
 pub fn delete_file(conn: &Connection, path: &str) -> Result<()> {
     conn.execute("DELETE FROM files WHERE path = ?1", params![path])?;
@@ -129,6 +150,67 @@ pub fn batch_delete_files(conn: &Connection, paths: &[String]) -> Result<()> {
     Ok(())
 }
 
+/// Identifiant d'un fichier, dérivé de son chemin
+pub fn file_id(path: &str) -> String {
+    format!("{:x}", path.as_bytes().iter().fold(0u64, |acc, &b| acc.wrapping_mul(31).wrapping_add(b as u64)))
+}
+
+/// Préfixe des chemins sous un dossier ("/a/b" -> "/a/b/")
+fn dir_prefix(dir: &str) -> String {
+    format!("{}{}", dir.trim_end_matches(['/', '\\']), std::path::MAIN_SEPARATOR)
+}
+
+/// Renomme un fichier (chemin, nom, extension et identifiant suivent)
+/// Un fichier déjà présent à la destination est remplacé.
+pub fn rename_file(conn: &Connection, from: &str, to: &str) -> Result<bool> {
+    let path = Path::new(to);
+    let filename = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+    let extension = path.extension().and_then(|ext| ext.to_str()).map(|ext| format!(".{}", ext));
+
+    let tx = conn.unchecked_transaction()?;
+    tx.execute("DELETE FROM files WHERE path = ?1 AND path != ?2", params![to, from])?;
+    let renamed = tx.execute(
+        "UPDATE files SET id = ?1, path = ?2, filename = ?3, extension = ?4 WHERE path = ?5",
+        params![file_id(to), to, filename, extension, from],
+    )?;
+    tx.commit()?;
+    Ok(renamed > 0)
+}
+
+/// Dossier déplacé: réécrit le chemin de tous les fichiers dessous (tous niveaux)
+/// Retourne le nombre de fichiers déplacés.
+pub fn rename_dir(conn: &Connection, from: &str, to: &str) -> Result<usize> {
+    let from_prefix = dir_prefix(from);
+    let to_prefix = dir_prefix(to);
+    let tx = conn.unchecked_transaction()?;
+
+    let paths: Vec<String> = {
+        let mut stmt = tx.prepare("SELECT path FROM files WHERE substr(path, 1, length(?1)) = ?1")?;
+        let rows = stmt.query_map(params![from_prefix], |row| row.get(0))?;
+        rows.collect::<Result<_>>()?
+    };
+    {
+        let mut delete = tx.prepare_cached("DELETE FROM files WHERE path = ?1")?;
+        let mut update = tx.prepare_cached("UPDATE files SET id = ?1, path = ?2 WHERE path = ?3")?;
+        for old in &paths {
+            let new = format!("{}{}", to_prefix, &old[from_prefix.len()..]);
+            delete.execute(params![new])?;
+            update.execute(params![file_id(&new), new, old])?;
+        }
+    }
+
+    tx.commit()?;
+    Ok(paths.len())
+}
+
+/// Supprime tous les fichiers sous un dossier (tous niveaux)
+pub fn delete_files_under(conn: &Connection, dir: &str) -> Result<usize> {
+    conn.execute(
+        "DELETE FROM files WHERE substr(path, 1, length(?1)) = ?1",
+        params![dir_prefix(dir)],
+    )
+}
+
 /// Compte le nombre total de fichiers
 pub fn count_files(conn: &Connection) -> Result<u64> {
     let count: i64 = conn.query_row(
@@ -217,6 +299,215 @@ pub fn get_watched_folders(conn: &Connection) -> Result<Vec<WatchedFolderRecord>
     rows.collect()
 }
 
+// ==================== Scan Reports Operations ====================
+
+/// Bilan du scan d'un dossier indexé
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScanReportRecord {
+    pub root: String,
+    pub files_indexed: u64,
+    /// Nombre total de sous-arbres tronqués (truncated peut en lister moins)
+    pub truncated_total: u64,
+    /// Sous-arbres tronqués: (chemin, raison)
+    pub truncated: Vec<(String, String)>,
+    pub scanned_at: i64,
+}
+
+/// Remplace le bilan précédent du dossier
+pub fn save_scan_report(conn: &Connection, report: &ScanReportRecord) -> Result<()> {
+    let tx = conn.unchecked_transaction()?;
+
+    tx.execute(
+        "INSERT OR REPLACE INTO scan_reports (root, files_indexed, truncated_total, scanned_at)
+         VALUES (?1, ?2, ?3, ?4)",
+        params![
+            report.root,
+            report.files_indexed as i64,
+            report.truncated_total as i64,
+            report.scanned_at,
+        ],
+    )?;
+    tx.execute("DELETE FROM scan_truncations WHERE root = ?1", params![report.root])?;
+    {
+        let mut stmt = tx.prepare_cached(
+            "INSERT OR IGNORE INTO scan_truncations (root, path, reason) VALUES (?1, ?2, ?3)"
+        )?;
+        for (path, reason) in &report.truncated {
+            stmt.execute(params![report.root, path, reason])?;
+        }
+    }
+
+    tx.commit()?;
+    Ok(())
+}
+
+/// Bilans de tous les dossiers scannés (par chemin)
+pub fn get_scan_reports(conn: &Connection) -> Result<Vec<ScanReportRecord>> {
+    let mut stmt = conn.prepare(
+        "SELECT root, files_indexed, truncated_total, scanned_at
+         FROM scan_reports
+         ORDER BY root"
+    )?;
+    let mut reports = stmt
+        .query_map([], |row| {
+            Ok(ScanReportRecord {
+                root: row.get(0)?,
+                files_indexed: row.get::<_, i64>(1)? as u64,
+                truncated_total: row.get::<_, i64>(2)? as u64,
+                truncated: Vec::new(),
+                scanned_at: row.get(3)?,
+            })
+        })?
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut stmt = conn.prepare(
+        "SELECT path, reason FROM scan_truncations WHERE root = ?1 ORDER BY path"
+    )?;
+    for report in &mut reports {
+        report.truncated = stmt
+            .query_map(params![report.root], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>>>()?;
+    }
+
+    Ok(reports)
+}
+
+/// Oublie le bilan d'un dossier (retiré des dossiers indexés)
+pub fn delete_scan_report(conn: &Connection, root: &str) -> Result<()> {
+    conn.execute("DELETE FROM scan_reports WHERE root = ?1", params![root])?;
+    conn.execute("DELETE FROM scan_truncations WHERE root = ?1", params![root])?;
+    Ok(())
+}
+
+// ==================== Indexing Checkpoint Operations ====================
+
+/// Type de l'indexation interrompue
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckpointMode {
+    /// Reconstruction complète (index vidé au départ)
+    Full,
+    /// Synchronisation incrémentale (rafraîchissement)
+    Sync,
+}
+
+impl CheckpointMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CheckpointMode::Full => "full",
+            CheckpointMode::Sync => "sync",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "full" => Some(CheckpointMode::Full),
+            "sync" => Some(CheckpointMode::Sync),
+            _ => None,
+        }
+    }
+}
+
+/// Point de reprise de l'indexation en cours
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckpointRecord {
+    pub mode: CheckpointMode,
+    pub files_done: u64,
+    /// Dernier dossier terminé (et sa racine)
+    pub last_root: Option<String>,
+    pub last_dir: Option<String>,
+    pub paused: bool,
+    pub started_at: i64,
+    pub updated_at: i64,
+}
+
+/// Démarre un nouveau point de reprise (oublie le précédent)
+pub fn begin_checkpoint(conn: &Connection, mode: CheckpointMode, now: i64) -> Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    tx.execute("DELETE FROM indexing_checkpoint_dirs", [])?;
+    tx.execute(
+        "INSERT OR REPLACE INTO indexing_checkpoint (id, mode, files_done, paused, started_at, updated_at)
+         VALUES (1, ?1, 0, 0, ?2, ?2)",
+        params![mode.as_str(), now],
+    )?;
+    tx.commit()?;
+    Ok(())
+}
+
+/// Avance le point de reprise: dossiers (racine, chemin) terminés depuis le précédent
+pub fn save_checkpoint(conn: &Connection, files_done: u64, completed: &[(String, String)], now: i64) -> Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    {
+        let mut stmt = tx.prepare_cached(
+            "INSERT OR IGNORE INTO indexing_checkpoint_dirs (path, root) VALUES (?1, ?2)"
+        )?;
+        for (root, path) in completed {
+            stmt.execute(params![path, root])?;
+        }
+    }
+    let (last_root, last_dir) = match completed.last() {
+        Some((root, path)) => (Some(root), Some(path)),
+        None => (None, None),
+    };
+    tx.execute(
+        "UPDATE indexing_checkpoint SET
+            files_done = ?1,
+            last_root = COALESCE(?2, last_root),
+            last_dir = COALESCE(?3, last_dir),
+            updated_at = ?4
+         WHERE id = 1",
+        params![files_done as i64, last_root, last_dir, now],
+    )?;
+    tx.commit()?;
+    Ok(())
+}
+
+/// Mémorise la pause de l'indexation en cours
+pub fn set_checkpoint_paused(conn: &Connection, paused: bool) -> Result<()> {
+    conn.execute("UPDATE indexing_checkpoint SET paused = ?1 WHERE id = 1", params![paused])?;
+    Ok(())
+}
+
+/// Point de reprise d'une indexation interrompue (None: aucune)
+pub fn get_checkpoint(conn: &Connection) -> Result<Option<CheckpointRecord>> {
+    let mut stmt = conn.prepare(
+        "SELECT mode, files_done, last_root, last_dir, paused, started_at, updated_at
+         FROM indexing_checkpoint WHERE id = 1"
+    )?;
+    let mut rows = stmt.query([])?;
+
+    if let Some(row) = rows.next()? {
+        let mode: String = row.get(0)?;
+        Ok(Some(CheckpointRecord {
+            // Mode inconnu: tout reprendre comme une synchronisation
+            mode: CheckpointMode::parse(&mode).unwrap_or(CheckpointMode::Sync),
+            files_done: row.get::<_, i64>(1)? as u64,
+            last_root: row.get(2)?,
+            last_dir: row.get(3)?,
+            paused: row.get(4)?,
+            started_at: row.get(5)?,
+            updated_at: row.get(6)?,
+        }))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Dossiers terminés du point de reprise
+pub fn get_checkpoint_dirs(conn: &Connection) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare("SELECT path FROM indexing_checkpoint_dirs")?;
+    let rows = stmt.query_map([], |row| row.get(0))?;
+    rows.collect()
+}
+
+/// Indexation terminée: plus rien à reprendre
+pub fn clear_checkpoint(conn: &Connection) -> Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    tx.execute("DELETE FROM indexing_checkpoint_dirs", [])?;
+    tx.execute("DELETE FROM indexing_checkpoint", [])?;
+    tx.commit()?;
+    Ok(())
+}
+
 // ==================== Config Operations ====================
 
 pub fn set_config(conn: &Connection, key: &str, value: &str) -> Result<()> {
@@ -293,6 +584,104 @@ pub fn get_top_searches(conn: &Connection, limit: u32) -> Result<Vec<(String, u3
     rows.collect()
 }
 
+// ==================== Open Events Operations (Frecency) ====================
+
+/// Demi-vie du score de frecency: un événement compte moitié moins après 14 jours
+pub const FRECENCY_HALF_LIFE_DAYS: f64 = 14.0;
+
+/// Au-delà de cette ancienneté, un événement ne compte plus (et peut être purgé)
+pub const FRECENCY_MAX_AGE_DAYS: i64 = 180;
+
+/// Action de l'utilisateur sur un résultat de recherche
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpenAction {
+    Open,
+    Preview,
+    Folder,
+}
+
+impl OpenAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OpenAction::Open => "open",
+            OpenAction::Preview => "preview",
+            OpenAction::Folder => "folder",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "open" => Some(OpenAction::Open),
+            "preview" => Some(OpenAction::Preview),
+            "folder" => Some(OpenAction::Folder),
+            _ => None,
+        }
+    }
+
+    /// Poids dans le score: ouvrir compte plus que prévisualiser
+    pub fn weight(&self) -> f64 {
+        match self {
+            OpenAction::Open => 1.0,
+            OpenAction::Folder => 0.7,
+            OpenAction::Preview => 0.5,
+        }
+    }
+}
+
+pub fn add_open_event(conn: &Connection, path: &str, action: OpenAction, timestamp: i64) -> Result<()> {
+    conn.execute(
+        "INSERT INTO open_events (path, action, timestamp) VALUES (?1, ?2, ?3)",
+        params![path, action.as_str(), timestamp],
+    )?;
+    Ok(())
+}
+
+/// Score de frecency par chemin: somme des poids des événements, chacun
+/// décroissant de moitié tous les FRECENCY_HALF_LIFE_DAYS
+/// Retourne les `limit` chemins les mieux notés (score décroissant)
+pub fn get_frecency_scores(conn: &Connection, now: i64, limit: u32) -> Result<Vec<(String, f64)>> {
+    let mut stmt = conn.prepare(
+        "SELECT path, action, timestamp
+         FROM open_events
+         WHERE timestamp > ?1"
+    )?;
+
+    let since = now - FRECENCY_MAX_AGE_DAYS * 24 * 60 * 60;
+    let half_life_secs = FRECENCY_HALF_LIFE_DAYS * 24.0 * 60.0 * 60.0;
+
+    let mut scores: std::collections::HashMap<String, f64> = std::collections::HashMap::new();
+    let rows = stmt.query_map(params![since], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, i64>(2)?,
+        ))
+    })?;
+
+    for row in rows {
+        let (path, action, timestamp) = row?;
+        let weight = OpenAction::parse(&action).map(|a| a.weight()).unwrap_or(0.0);
+        let age = (now - timestamp).max(0) as f64;
+        *scores.entry(path).or_insert(0.0) += weight * 0.5f64.powf(age / half_life_secs);
+    }
+
+    let mut scores: Vec<(String, f64)> = scores.into_iter().collect();
+    scores.sort_by(|a, b| b.1.total_cmp(&a.1));
+    scores.truncate(limit as usize);
+    Ok(scores)
+}
+
+/// Efface l'historique d'ouverture (retourne le nombre d'événements supprimés)
+pub fn clear_open_events(conn: &Connection) -> Result<usize> {
+    conn.execute("DELETE FROM open_events", [])
+}
+
+/// Supprime les événements trop anciens pour compter dans la frecency
+pub fn cleanup_old_open_events(conn: &Connection, now: i64) -> Result<usize> {
+    let since = now - FRECENCY_MAX_AGE_DAYS * 24 * 60 * 60;
+    conn.execute("DELETE FROM open_events WHERE timestamp <= ?1", params![since])
+}
+
 // ==================== Error Log Operations ====================
 
 #[derive(Debug, Clone)]
@@ -600,6 +989,140 @@ mod tests {
         batch_upsert_files(&conn, &files).unwrap();
         let count = count_files(&conn).unwrap();
         assert_eq!(count, 100);
+
+        let states = get_file_states(&conn).unwrap();
+        assert_eq!(states.len(), 100);
+        assert!(states.contains(&("C:\\file7.txt".to_string(), 1024, now)));
+    }
+
+    #[test]
+    fn test_rename_file_and_dir() {
+        let conn = create_test_db();
+        let record = |path: &str| FileRecord {
+            id: file_id(path),
+            path: path.to_string(),
+            filename: Path::new(path).file_name().unwrap().to_string_lossy().to_string(),
+            extension: Some(".txt".to_string()),
+            size: 10,
+            modified: 1,
+            created: 1,
+            hash: None,
+            indexed_at: 1,
+        };
+        let files: Vec<FileRecord> = ["/data/docs/a.txt", "/data/docs/sub/b.txt", "/data/docs2/c.txt", "/data/old.txt"]
+            .iter()
+            .map(|path| record(path))
+            .collect();
+        batch_upsert_files(&conn, &files).unwrap();
+
+        // Fichier: nom, extension et identifiant suivent
+        assert!(rename_file(&conn, "/data/old.txt", "/data/new.md").unwrap());
+        let renamed = get_file_by_path(&conn, "/data/new.md").unwrap().unwrap();
+        assert_eq!((renamed.filename.as_str(), renamed.extension.as_deref()), ("new.md", Some(".md")));
+        assert_eq!(renamed.id, file_id("/data/new.md"));
+        assert!(!rename_file(&conn, "/data/missing.txt", "/data/x.txt").unwrap());
+
+        // Dossier: tous les niveaux, pas le dossier voisin "docs2"
+        assert_eq!(rename_dir(&conn, "/data/docs", "/archive/docs").unwrap(), 2);
+        assert!(get_file_by_path(&conn, "/archive/docs/sub/b.txt").unwrap().is_some());
+        assert!(get_file_by_path(&conn, "/data/docs/a.txt").unwrap().is_none());
+        assert!(get_file_by_path(&conn, "/data/docs2/c.txt").unwrap().is_some());
+        // Un fichier pourra de nouveau être créé à l'ancien chemin
+        upsert_file(&conn, &record("/data/docs/a.txt")).unwrap();
+
+        let mut states = get_file_states_under(&conn, "/archive/docs").unwrap();
+        states.sort();
+        assert_eq!(states.len(), 2);
+        assert_eq!(states[0].0, "/archive/docs/a.txt");
+
+        assert_eq!(delete_files_under(&conn, "/archive/").unwrap(), 2);
+        assert_eq!(count_files(&conn).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_scan_reports() {
+        let conn = create_test_db();
+
+        let mut report = ScanReportRecord {
+            root: "/data".to_string(),
+            files_indexed: 500,
+            truncated_total: 3,
+            truncated: vec![
+                ("/data/a".to_string(), "file_limit".to_string()),
+                ("/data/deep/x".to_string(), "max_depth".to_string()),
+            ],
+            scanned_at: 1000,
+        };
+        save_scan_report(&conn, &report).unwrap();
+        assert_eq!(get_scan_reports(&conn).unwrap(), vec![report.clone()]);
+
+        // Nouveau scan complet: les anciens sous-arbres disparaissent
+        report.truncated_total = 0;
+        report.truncated.clear();
+        save_scan_report(&conn, &report).unwrap();
+        assert_eq!(get_scan_reports(&conn).unwrap(), vec![report]);
+
+        delete_scan_report(&conn, "/data").unwrap();
+        assert!(get_scan_reports(&conn).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_indexing_checkpoint() {
+        let conn = create_test_db();
+        assert!(get_checkpoint(&conn).unwrap().is_none());
+
+        begin_checkpoint(&conn, CheckpointMode::Full, 100).unwrap();
+        let completed = vec![
+            ("/data".to_string(), "/data/a".to_string()),
+            ("/data".to_string(), "/data/b".to_string()),
+        ];
+        save_checkpoint(&conn, 5000, &completed, 110).unwrap();
+        // Commit sans nouveau dossier terminé: le dernier reste connu
+        save_checkpoint(&conn, 6000, &[], 120).unwrap();
+        set_checkpoint_paused(&conn, true).unwrap();
+
+        let checkpoint = get_checkpoint(&conn).unwrap().unwrap();
+        assert_eq!(checkpoint.mode, CheckpointMode::Full);
+        assert_eq!(checkpoint.files_done, 6000);
+        assert_eq!(checkpoint.last_dir.as_deref(), Some("/data/b"));
+        assert_eq!(checkpoint.last_root.as_deref(), Some("/data"));
+        assert!(checkpoint.paused);
+        assert_eq!(checkpoint.started_at, 100);
+        assert_eq!(get_checkpoint_dirs(&conn).unwrap().len(), 2);
+
+        // Nouvelle indexation: l'ancien point de reprise est oublié
+        begin_checkpoint(&conn, CheckpointMode::Sync, 200).unwrap();
+        assert!(get_checkpoint_dirs(&conn).unwrap().is_empty());
+        assert!(!get_checkpoint(&conn).unwrap().unwrap().paused);
+
+        clear_checkpoint(&conn).unwrap();
+        assert!(get_checkpoint(&conn).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_frecency_scores() {
+        let conn = create_test_db();
+        let now = 1_700_000_000;
+        let day = 24 * 60 * 60;
+
+        // Ouvert deux fois aujourd'hui
+        add_open_event(&conn, "/docs/recent.pdf", OpenAction::Open, now).unwrap();
+        add_open_event(&conn, "/docs/recent.pdf", OpenAction::Preview, now).unwrap();
+        // Ouvert il y a une demi-vie
+        add_open_event(&conn, "/docs/old.pdf", OpenAction::Open, now - 14 * day).unwrap();
+        // Trop ancien pour compter
+        add_open_event(&conn, "/docs/ancient.pdf", OpenAction::Open, now - 365 * day).unwrap();
+
+        let scores = get_frecency_scores(&conn, now, 10).unwrap();
+        assert_eq!(scores.len(), 2);
+        assert_eq!(scores[0].0, "/docs/recent.pdf");
+        assert!((scores[0].1 - 1.5).abs() < 1e-9);
+        assert_eq!(scores[1].0, "/docs/old.pdf");
+        assert!((scores[1].1 - 0.5).abs() < 1e-9);
+
+        assert_eq!(cleanup_old_open_events(&conn, now).unwrap(), 1);
+        assert_eq!(clear_open_events(&conn).unwrap(), 3);
+        assert!(get_frecency_scores(&conn, now, 10).unwrap().is_empty());
     }
 
     #[test]
@@ -62,6 +62,19 @@ CREATE TABLE IF NOT EXISTS search_history (
 CREATE INDEX IF NOT EXISTS idx_search_history_timestamp ON search_history(timestamp);
 CREATE INDEX IF NOT EXISTS idx_search_history_query ON search_history(query);
 
+-- ==================== Open Events Table ====================
+-- Fichiers ouverts depuis les résultats (Ouvrir, Prévisualiser, Dossier)
+-- Sert au score de frecency (fréquence + récence) de la recherche
+CREATE TABLE IF NOT EXISTS open_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    path TEXT NOT NULL,
+    action TEXT NOT NULL,
+    timestamp INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_open_events_path ON open_events(path);
+CREATE INDEX IF NOT EXISTS idx_open_events_timestamp ON open_events(timestamp);
+
 -- ==================== Error Log Table ====================
 -- Logs d'erreurs structurés
 CREATE TABLE IF NOT EXISTS error_log (
@@ -101,6 +114,43 @@ CREATE TABLE IF NOT EXISTS semantic_chunks (
 CREATE INDEX IF NOT EXISTS idx_semantic_chunks_file_id ON semantic_chunks(file_id);
 CREATE INDEX IF NOT EXISTS idx_semantic_chunks_chunk_id ON semantic_chunks(chunk_id);
 
+-- ==================== Scan Reports Tables ====================
+-- Bilan du dernier scan de chaque dossier indexé
+CREATE TABLE IF NOT EXISTS scan_reports (
+    root TEXT PRIMARY KEY,
+    files_indexed INTEGER NOT NULL,
+    truncated_total INTEGER NOT NULL,
+    scanned_at INTEGER NOT NULL
+);
+
+-- Sous-arbres non indexés entièrement (profondeur max, limite de fichiers)
+CREATE TABLE IF NOT EXISTS scan_truncations (
+    root TEXT NOT NULL,
+    path TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    PRIMARY KEY (root, path)
+);
+
+-- ==================== Indexing Checkpoint Tables ====================
+-- Indexation en cours: reprise après fermeture de l'application
+-- (une seule ligne, supprimée quand l'indexation se termine)
+CREATE TABLE IF NOT EXISTS indexing_checkpoint (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    mode TEXT NOT NULL,
+    files_done INTEGER NOT NULL DEFAULT 0,
+    last_root TEXT,
+    last_dir TEXT,
+    paused BOOLEAN NOT NULL DEFAULT 0,
+    started_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+-- Dossiers dont tous les fichiers sont indexés et validés (commit)
+CREATE TABLE IF NOT EXISTS indexing_checkpoint_dirs (
+    path TEXT PRIMARY KEY,
+    root TEXT NOT NULL
+);
+
 -- ==================== Stats View ====================
 -- Vue pour statistiques rapides
 CREATE VIEW IF NOT EXISTS files_stats AS
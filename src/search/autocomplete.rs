@@ -0,0 +1,119 @@
+// src/search/autocomplete.rs
+// Complétion de la requête pendant la saisie (type-ahead)
+//
+// Deux sources, fusionnées par SearchIndex::complete():
+// - l'historique des recherches (get_top_searches): requêtes complètes qui
+//   commencent par la saisie, les plus fréquentes d'abord
+// - le vocabulaire des noms de fichiers (champ filename_words): le dernier
+//   mot en cours de saisie est complété par les mots indexés de même préfixe,
+//   les plus fréquents d'abord
+//
+// Aucune recherche n'est lancée: seul le dictionnaire de termes est parcouru,
+// ce qui reste de l'ordre de la milliseconde même sur un gros index.
+
+// Longueur min du mot en cours pour proposer des mots du vocabulaire
+pub const MIN_COMPLETION_PREFIX: usize = 2;
+
+// Proposition de complétion (requête complète à mettre dans la barre de recherche)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Completion {
+    pub text: String,
+    // Vient de l'historique (sinon du vocabulaire des noms)
+    pub from_history: bool,
+}
+
+// Mot en cours de saisie: (position en octets du mot dans la requête, mot)
+//
+// Les préfixes -, name: et path: sont conservés tels quels, seul le mot qui
+// suit est complété. Rien à compléter si la requête finit par un espace,
+// dans une phrase entre guillemets ou après un autre opérateur (ext:, size:...).
+pub fn current_word(query: &str) -> Option<(usize, &str)> {
+    if query.ends_with(char::is_whitespace) || query.matches('"').count() % 2 == 1 {
+        return None;
+    }
+
+    let token_start = query.rfind(char::is_whitespace).map(|i| i + 1).unwrap_or(0);
+    let mut token = query[token_start..].trim_start_matches(['(', '-']);
+    if let Some((field, rest)) = token.split_once(':') {
+        if !matches!(field.to_lowercase().as_str(), "name" | "path") {
+            return None;
+        }
+        token = rest;
+    }
+    // Le mot est un suffixe de la requête
+    let start = query.len() - token.len();
+
+    let valid = token.chars().count() >= MIN_COMPLETION_PREFIX && token.chars().all(char::is_alphanumeric);
+    valid.then_some((start, token))
+}
+
+// Fusionne les deux sources, sans doublon, dans la limite donnée
+//
+// history: (requête, fréquence) triée par fréquence décroissante
+// words: mots complétant current_word(query), triés par fréquence décroissante
+pub fn merge_completions(query: &str, history: &[(String, u32)], words: &[String], limit: usize) -> Vec<Completion> {
+    let query_lower = query.to_lowercase();
+    let mut completions: Vec<Completion> = Vec::new();
+
+    let mut push = |text: String, from_history: bool| {
+        let duplicate = completions.iter().any(|c| c.text.to_lowercase() == text.to_lowercase());
+        if completions.len() < limit && text.to_lowercase() != query_lower && !duplicate {
+            completions.push(Completion { text, from_history });
+        }
+    };
+
+    for (previous, _) in history {
+        if !query_lower.trim().is_empty() && previous.to_lowercase().starts_with(&query_lower) {
+            push(previous.clone(), true);
+        }
+    }
+
+    if let Some((start, _)) = current_word(query) {
+        for word in words {
+            push(format!("{}{}", &query[..start], word), false);
+        }
+    }
+
+    completions
+}
+
+// Tests TDD
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_current_word() {
+        assert_eq!(current_word("rapport ann"), Some((8, "ann")));
+        assert_eq!(current_word("-brouil"), Some((1, "brouil")));
+        assert_eq!(current_word("ext:pdf name:fact"), Some((13, "fact")));
+        assert_eq!(current_word("(rapport OR fact"), Some((12, "fact")));
+        assert_eq!(current_word("(rap"), Some((1, "rap")));
+
+        // Rien à compléter
+        assert_eq!(current_word("rapport "), None);
+        assert_eq!(current_word("r"), None);
+        assert_eq!(current_word("size:>10"), None);
+        assert_eq!(current_word("\"rapport ann"), None);
+    }
+
+    #[test]
+    fn test_merge_completions() {
+        let history = vec![
+            ("rapport annuel".to_string(), 8),
+            ("facture".to_string(), 5),
+            ("Rapport ANN".to_string(), 2),
+        ];
+        let words = vec!["annuel".to_string(), "annexe".to_string()];
+
+        let completions = merge_completions("rapport ann", &history, &words, 10);
+        let texts: Vec<&str> = completions.iter().map(|c| c.text.as_str()).collect();
+        // Historique d'abord, puis le dernier mot complété (sans doublon ni la saisie elle-même)
+        assert_eq!(texts, vec!["rapport annuel", "rapport annexe"]);
+        assert!(completions[0].from_history);
+        assert!(!completions[1].from_history);
+
+        assert_eq!(merge_completions("rapport ann", &history, &words, 1).len(), 1);
+        assert!(merge_completions("", &history, &[], 10).is_empty());
+    }
+}
@@ -0,0 +1,379 @@
+// src/search/debounce.rs
+// Regroupement des événements du watchdog par chemin
+//
+// Un enregistrement dans un éditeur produit une rafale d'événements
+// (création, écritures, renommages, suppression d'un fichier temporaire).
+// Les événements d'un chemin sont fusionnés tant qu'il reste actif; une fois
+// calme pendant la fenêtre de calme, son état final est publié une seule
+// fois: créé, modifié, supprimé ou renommé (+ modifié).
+//
+// L'état est toujours relatif à l'index avant la rafale:
+// - créé puis supprimé: rien à faire
+// - supprimé puis recréé: modifié (fichier remplacé)
+// - écrit sous un nom temporaire puis renommé: modification du vrai fichier
+// - renommé en sauvegarde (foo -> foo~) puis recréé: modification de foo
+// Les créations et modifications des fichiers temporaires d'éditeur (.swp,
+// ~, .tmp...) ne sont jamais publiées. Leur suppression l'est: un scan a pu
+// les indexer (seul .tmp est exclu par défaut).
+
+use super::file_watcher::FileEvent;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+// Fenêtre de calme par défaut
+pub const DEFAULT_QUIET_WINDOW: Duration = Duration::from_millis(300);
+
+// Un fichier écrit sans arrêt (log...) est publié au plus tard après ce
+// nombre de fenêtres
+const MAX_DELAY_WINDOWS: u32 = 10;
+
+// Fichier temporaire d'éditeur ou d'écriture atomique
+pub fn is_temp_file(path: &Path) -> bool {
+    let Some(name) = path.file_name().map(|name| name.to_string_lossy().to_lowercase()) else {
+        return false;
+    };
+    name.ends_with('~')
+        || name.ends_with(".tmp")
+        || name.ends_with(".swp")
+        || name.ends_with(".swx")
+        || name.ends_with(".swo")
+        || name.starts_with(".#") // Verrou emacs
+        || name == "4913" // Test d'écriture de vim
+}
+
+// Changement en attente d'un chemin, par rapport à l'index
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Pending {
+    Created,
+    Modified,
+    Removed,
+    // Le contenu a été renommé ailleurs (publié par la destination)
+    MovedAway,
+    // Le chemin contient désormais le fichier qui était à `from`
+    RenamedFrom { from: PathBuf, modified: bool },
+}
+
+#[derive(Debug)]
+struct Entry {
+    state: Pending,
+    first: Instant, // Premier événement de la rafale
+    last: Instant,  // Dernier événement
+}
+
+#[derive(Debug)]
+pub struct Debouncer {
+    window: Duration,
+    pending: HashMap<PathBuf, Entry>,
+}
+
+impl Debouncer {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            pending: HashMap::new(),
+        }
+    }
+
+    pub fn set_window(&mut self, window: Duration) {
+        self.window = window;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    // Fusionne un événement avec l'état en attente de son chemin
+    pub fn push(&mut self, event: FileEvent, now: Instant) {
+        match event {
+            FileEvent::Created(path) => {
+                let state = match self.state(&path) {
+                    None => Pending::Created,
+                    // Supprimé (ou renommé ailleurs) puis recréé: fichier remplacé
+                    Some(Pending::Removed) | Some(Pending::MovedAway) => Pending::Modified,
+                    Some(_) => self.modified_state(&path),
+                };
+                self.set(path, state, now);
+            }
+            FileEvent::Modified(path) => {
+                let state = self.modified_state(&path);
+                self.set(path, state, now);
+            }
+            FileEvent::Removed(path) => self.remove(path, now),
+            FileEvent::Renamed { from, to } => self.rename(from, to, now),
+        }
+    }
+
+    // Chemins calmes depuis la fenêtre: leurs changements, renommages en tête
+    pub fn drain_ready(&mut self, now: Instant) -> Vec<FileEvent> {
+        let max_delay = self.window * MAX_DELAY_WINDOWS;
+        let ready_paths: Vec<PathBuf> = self
+            .pending
+            .iter()
+            .filter(|(_, entry)| now.duration_since(entry.last) >= self.window || now.duration_since(entry.first) >= max_delay)
+            .map(|(path, _)| path.clone())
+            .collect();
+        let mut ready: Vec<(PathBuf, Entry)> = ready_paths
+            .into_iter()
+            .filter_map(|path| self.pending.remove(&path).map(|entry| (path, entry)))
+            .collect();
+        ready.sort_by_key(|(_, entry)| (!matches!(entry.state, Pending::RenamedFrom { .. }), entry.first));
+
+        let mut events = Vec::new();
+        for (path, entry) in ready {
+            let temp = is_temp_file(&path);
+            match entry.state {
+                Pending::Created | Pending::Modified | Pending::RenamedFrom { .. } if temp => {}
+                Pending::Created => events.push(FileEvent::Created(path)),
+                Pending::Modified => events.push(FileEvent::Modified(path)),
+                Pending::Removed => events.push(FileEvent::Removed(path)),
+                Pending::MovedAway => {}
+                Pending::RenamedFrom { from, modified } => {
+                    events.push(FileEvent::Renamed { from, to: path.clone() });
+                    if modified {
+                        events.push(FileEvent::Modified(path));
+                    }
+                }
+            }
+        }
+        events
+    }
+
+    fn state(&self, path: &Path) -> Option<&Pending> {
+        self.pending.get(path).map(|entry| &entry.state)
+    }
+
+    fn set(&mut self, path: PathBuf, state: Pending, now: Instant) {
+        let entry = self.pending.entry(path).or_insert(Entry { state: Pending::Modified, first: now, last: now });
+        entry.state = state;
+        entry.last = now;
+    }
+
+    // Le chemin existe avec un nouveau contenu
+    fn modified_state(&self, path: &Path) -> Pending {
+        match self.state(path) {
+            Some(Pending::Created) => Pending::Created,
+            Some(Pending::RenamedFrom { from, .. }) => Pending::RenamedFrom { from: from.clone(), modified: true },
+            _ => Pending::Modified,
+        }
+    }
+
+    fn remove(&mut self, path: PathBuf, now: Instant) {
+        match self.pending.remove(&path).map(|entry| entry.state) {
+            // Apparu puis disparu pendant la rafale: l'index n'a rien vu
+            Some(Pending::Created) => {}
+            // Renommé puis supprimé: c'est l'original qui a disparu
+            Some(Pending::RenamedFrom { from, .. }) => {
+                if self.state(&from) == Some(&Pending::MovedAway) {
+                    self.set(from, Pending::Removed, now);
+                }
+            }
+            _ => self.set(path, Pending::Removed, now),
+        }
+    }
+
+    fn rename(&mut self, from: PathBuf, to: PathBuf, now: Instant) {
+        if from == to {
+            return;
+        }
+        self.move_children(&from, &to);
+        // Sauvegarde d'éditeur (foo -> foo~): pour l'index, foo a disparu
+        if is_temp_file(&to) && !is_temp_file(&from) {
+            self.remove(from, now);
+            self.set(to, Pending::Created, now);
+            return;
+        }
+
+        // Écrit sous un nom temporaire puis renommé: le contenu de `to` change,
+        // et le temporaire disparaît (publié seulement s'il existait avant)
+        if is_temp_file(&from) {
+            self.remove(from, now);
+            let state = self.modified_state(&to);
+            self.set(to, state, now);
+            return;
+        }
+
+        let prior = self.pending.remove(&from).map(|entry| entry.state);
+        // Fichier neuf renommé: le contenu de `to` change
+        if prior == Some(Pending::Created) {
+            let state = self.modified_state(&to);
+            self.set(to, state, now);
+            return;
+        }
+
+        let state = match prior {
+            None => {
+                self.set(from.clone(), Pending::MovedAway, now);
+                Pending::RenamedFrom { from, modified: false }
+            }
+            Some(Pending::Modified) => {
+                self.set(from.clone(), Pending::MovedAway, now);
+                Pending::RenamedFrom { from, modified: true }
+            }
+            // Renommages successifs: seul le premier nom compte
+            Some(Pending::RenamedFrom { from: original, modified }) => {
+                if original == to {
+                    // Revenu à son nom d'origine
+                    if !modified {
+                        self.pending.remove(&to);
+                        return;
+                    }
+                    Pending::Modified
+                } else {
+                    Pending::RenamedFrom { from: original, modified }
+                }
+            }
+            // Incohérent (événement perdu): l'état réel sera relu
+            Some(_) => Pending::Modified,
+        };
+        self.set(to, state, now);
+    }
+
+    // Dossier renommé: les changements en attente de ses fichiers suivent
+    // (publiés après le renommage, sous leur nouveau chemin)
+    fn move_children(&mut self, from: &Path, to: &Path) {
+        let children: Vec<PathBuf> = self
+            .pending
+            .keys()
+            .filter(|path| path.starts_with(from) && path.as_path() != from)
+            .cloned()
+            .collect();
+        for child in children {
+            let (Some(entry), Ok(relative)) = (self.pending.remove(&child), child.strip_prefix(from)) else {
+                continue;
+            };
+            self.pending.insert(to.join(relative), entry);
+        }
+    }
+}
+
+impl Default for Debouncer {
+    fn default() -> Self {
+        Self::new(DEFAULT_QUIET_WINDOW)
+    }
+}
+
+// Tests TDD
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: Duration = Duration::from_millis(100);
+
+    fn p(path: &str) -> PathBuf {
+        PathBuf::from(path)
+    }
+
+    // Rejoue les événements à 1 ms d'intervalle, puis attend la fenêtre
+    fn settle(events: Vec<FileEvent>) -> Vec<FileEvent> {
+        let start = Instant::now();
+        let mut debouncer = Debouncer::new(WINDOW);
+        let count = events.len() as u32;
+        for (i, event) in events.into_iter().enumerate() {
+            debouncer.push(event, start + Duration::from_millis(i as u64));
+        }
+        // Pas encore calme
+        assert!(debouncer.drain_ready(start + Duration::from_millis(count as u64 + 10)).is_empty());
+        let events = debouncer.drain_ready(start + WINDOW + Duration::from_millis(count as u64));
+        assert!(debouncer.is_empty());
+        events
+    }
+
+    #[test]
+    fn test_coalesce_bursts() {
+        use FileEvent::*;
+
+        assert_eq!(
+            settle(vec![Created(p("/d/a.txt")), Modified(p("/d/a.txt")), Modified(p("/d/a.txt"))]),
+            vec![Created(p("/d/a.txt"))]
+        );
+        assert!(settle(vec![Created(p("/d/a.txt")), Modified(p("/d/a.txt")), Removed(p("/d/a.txt"))]).is_empty());
+        assert_eq!(
+            settle(vec![Modified(p("/d/a.txt")), Removed(p("/d/a.txt"))]),
+            vec![Removed(p("/d/a.txt"))]
+        );
+        assert_eq!(
+            settle(vec![Removed(p("/d/a.txt")), Created(p("/d/a.txt"))]),
+            vec![Modified(p("/d/a.txt"))]
+        );
+
+        // Un fichier écrit sans arrêt est publié au bout de MAX_DELAY_WINDOWS fenêtres
+        let start = Instant::now();
+        let mut debouncer = Debouncer::new(WINDOW);
+        for ms in (0..1000).step_by(50) {
+            debouncer.push(Modified(p("/d/app.log")), start + Duration::from_millis(ms));
+            assert!(debouncer.drain_ready(start + Duration::from_millis(ms)).is_empty());
+        }
+        assert_eq!(debouncer.drain_ready(start + WINDOW * MAX_DELAY_WINDOWS).len(), 1);
+    }
+
+    #[test]
+    fn test_editor_save_patterns() {
+        use FileEvent::*;
+        let doc = || p("/d/doc.txt");
+
+        // Écriture atomique: fichier temporaire renommé sur le vrai
+        let tmp = p("/d/doc.txt.tmp");
+        assert_eq!(
+            settle(vec![
+                Created(tmp.clone()),
+                Modified(tmp.clone()),
+                Renamed { from: tmp, to: doc() },
+            ]),
+            vec![Modified(doc())]
+        );
+
+        // vim: sauvegarde doc.txt~, nouveau doc.txt, sauvegarde supprimée; swap ignoré
+        let backup = p("/d/doc.txt~");
+        let swap = p("/d/.doc.txt.swp");
+        assert_eq!(
+            settle(vec![
+                Modified(swap.clone()),
+                Renamed { from: doc(), to: backup.clone() },
+                Created(doc()),
+                Modified(doc()),
+                Removed(backup),
+                Removed(swap.clone()),
+            ]),
+            // Suppression du swap publiée (sans effet s'il n'était pas indexé)
+            vec![Modified(doc()), Removed(swap)]
+        );
+
+        // Renommage d'un vrai fichier, puis modification
+        assert_eq!(
+            settle(vec![
+                Renamed { from: p("/d/a.txt"), to: p("/d/b.txt") },
+                Renamed { from: p("/d/b.txt"), to: p("/d/c.txt") },
+                Modified(p("/d/c.txt")),
+            ]),
+            vec![Renamed { from: p("/d/a.txt"), to: p("/d/c.txt") }, Modified(p("/d/c.txt"))]
+        );
+        // Aller-retour: rien
+        assert!(settle(vec![
+            Renamed { from: p("/d/a.txt"), to: p("/d/b.txt") },
+            Renamed { from: p("/d/b.txt"), to: p("/d/a.txt") },
+        ])
+        .is_empty());
+        // Renommé puis supprimé: l'original a disparu
+        assert_eq!(
+            settle(vec![Renamed { from: p("/d/a.txt"), to: p("/d/b.txt") }, Removed(p("/d/b.txt"))]),
+            vec![Removed(p("/d/a.txt"))]
+        );
+    }
+
+    #[test]
+    fn test_temp_file_removal_is_published() {
+        use FileEvent::*;
+
+        // foo~ indexé par un scan puis supprimé: il doit quitter l'index
+        for name in ["/d/notes~", "/d/.#draft", "/d/x.swp"] {
+            assert_eq!(settle(vec![Removed(p(name))]), vec![Removed(p(name))]);
+            assert!(settle(vec![Modified(p(name))]).is_empty());
+        }
+        // Renommé hors du nom temporaire: le temporaire indexé disparaît aussi
+        let events = settle(vec![Renamed { from: p("/d/notes~"), to: p("/d/notes.txt") }]);
+        assert_eq!(events.len(), 2);
+        assert!(events.contains(&Removed(p("/d/notes~"))));
+        assert!(events.contains(&Modified(p("/d/notes.txt"))));
+    }
+}
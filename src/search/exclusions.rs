@@ -0,0 +1,368 @@
+// src/search/exclusions.rs
+// Règles d'exclusion de la config, compilées une fois (scanner et watcher)
+//
+// - dossiers exclus: préfixe exact, aux limites de dossier
+//   ("/home/moi/proj" n'exclut pas "/home/moi/projet2")
+// - motifs: glob comparé aux noms de dossiers/fichiers du chemin, jamais à
+//   une sous-chaîne: ".git" exclut "repo/.git/config" mais pas "my.github.io".
+//   Un motif avec "/" porte sur plusieurs niveaux ("target/debug"), * et ?
+//   restent dans un nom, ** traverse les dossiers
+// - extensions: ".log" (sans tenir compte de la casse, ".tar.gz" possible)
+// - fichiers et dossiers cachés (nom commençant par "."), réglable par racine
+// - taille max des fichiers
+// Les chemins sont comparés avec "/" et "\" indifféremment (sans tenir compte
+// de la casse sous Windows).
+
+use regex::{RegexSet, RegexSetBuilder};
+use std::fmt;
+use std::path::Path;
+
+use super::ignore_rules::path_glob_to_regex;
+use crate::config::ExclusionsConfig;
+
+// Raison de l'exclusion d'un chemin (affichée dans les paramètres)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExclusionReason {
+    Directory(String), // Dossier exclu (tel que configuré)
+    Pattern(String),   // Motif exclu
+    Extension(String), // Extension exclue
+    Hidden(String),    // Nom caché (fichier ou dossier parent)
+    TooLarge { size: u64, max: u64 },
+}
+
+impl fmt::Display for ExclusionReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExclusionReason::Directory(dir) => write!(f, "dans le dossier exclu {}", dir),
+            ExclusionReason::Pattern(pattern) => write!(f, "correspond au motif \"{}\"", pattern),
+            ExclusionReason::Extension(ext) => write!(f, "extension {} exclue", ext),
+            ExclusionReason::Hidden(name) => write!(f, "\"{}\" est caché", name),
+            ExclusionReason::TooLarge { size, max } => {
+                write!(f, "{} Mo, au-delà de la limite de {} Mo", size / (1024 * 1024), max / (1024 * 1024))
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ExclusionMatcher {
+    dirs: Vec<(String, String)>, // (tel que configuré, normalisé)
+    patterns: Vec<String>,       // Motifs valides, même indice que pattern_set
+    pattern_set: RegexSet,
+    extensions: Vec<String>, // ".ext" en minuscules
+    hidden: bool,
+    max_file_size: Option<u64>, // Octets
+    roots: Vec<MatcherRoot>,    // Leurs propres parents cachés ne comptent pas
+}
+
+#[derive(Debug, Clone)]
+struct MatcherRoot {
+    path: String,         // Normalisé
+    hidden: Option<bool>, // Exclure les cachés sous cette racine (None: réglage global)
+}
+
+impl ExclusionMatcher {
+    // Compile les règles (un motif invalide est ignoré)
+    pub fn new(config: &ExclusionsConfig) -> Self {
+        let mut patterns = Vec::new();
+        let mut regexes = Vec::new();
+        for pattern in &config.patterns {
+            let glob = pattern.trim().replace('\\', "/");
+            let glob = glob.trim_matches('/');
+            if glob.is_empty() {
+                continue;
+            }
+            let regex = format!("(?:^|/){}(?:/|$)", path_glob_to_regex(glob));
+            if regex::Regex::new(&regex).is_ok() {
+                patterns.push(pattern.clone());
+                regexes.push(regex);
+            }
+        }
+        let pattern_set = RegexSetBuilder::new(&regexes)
+            .case_insensitive(cfg!(windows))
+            .build()
+            .unwrap_or_else(|_| RegexSet::empty());
+
+        let extensions = config
+            .extensions
+            .iter()
+            .map(|ext| ext.trim().to_lowercase())
+            .filter(|ext| !ext.is_empty())
+            .map(|ext| if ext.starts_with('.') { ext } else { format!(".{}", ext) })
+            .collect();
+
+        Self {
+            dirs: config
+                .dirs
+                .iter()
+                .filter(|dir| !dir.trim().is_empty())
+                .map(|dir| (dir.clone(), normalize(dir)))
+                .collect(),
+            patterns,
+            pattern_set,
+            extensions,
+            hidden: config.hidden,
+            max_file_size: (config.max_file_size_mb > 0).then(|| config.max_file_size_mb * 1024 * 1024),
+            roots: Vec::new(),
+        }
+    }
+
+    // Racines parcourues: un dossier caché au-dessus d'une racine ne l'exclut pas
+    pub fn with_roots<P: AsRef<Path>>(mut self, roots: &[P]) -> Self {
+        for root in roots {
+            self.add_root(root.as_ref());
+        }
+        self
+    }
+
+    pub fn add_root(&mut self, root: &Path) {
+        let path = normalize(&root.to_string_lossy());
+        if !self.roots.iter().any(|existing| existing.path == path) {
+            self.roots.push(MatcherRoot { path, hidden: None });
+        }
+    }
+
+    // Exclure ou non les cachés sous une racine (None: réglage global)
+    pub fn set_root_hidden(&mut self, root: &Path, hidden: Option<bool>) {
+        self.add_root(root);
+        let path = normalize(&root.to_string_lossy());
+        if let Some(existing) = self.roots.iter_mut().find(|existing| existing.path == path) {
+            existing.hidden = hidden;
+        }
+    }
+
+    pub fn remove_root(&mut self, root: &Path) {
+        let path = normalize(&root.to_string_lossy());
+        self.roots.retain(|existing| existing.path != path);
+    }
+
+    // Nouvelles règles, mêmes racines (et mêmes réglages par racine)
+    pub fn reconfigured(&self, config: &ExclusionsConfig) -> Self {
+        Self {
+            roots: self.roots.clone(),
+            ..Self::new(config)
+        }
+    }
+
+    pub fn is_excluded(&self, path: &Path, is_dir: bool, size: Option<u64>) -> bool {
+        self.explain(path, is_dir, size).is_some()
+    }
+
+    // Le fichier dépasse-t-il la taille max ?
+    pub fn too_large(&self, size: u64) -> bool {
+        self.max_file_size.is_some_and(|max| size > max)
+    }
+
+    // Première règle qui exclut le chemin (None: le chemin est indexé)
+    // size: taille du fichier si connue (sinon la limite n'est pas vérifiée)
+    pub fn explain(&self, path: &Path, is_dir: bool, size: Option<u64>) -> Option<ExclusionReason> {
+        let normalized = normalize(&path.to_string_lossy());
+
+        // 1. Dossiers exclus
+        for (dir, prefix) in &self.dirs {
+            if is_under(&normalized, prefix) {
+                return Some(ExclusionReason::Directory(dir.clone()));
+            }
+        }
+
+        // 2. Motifs
+        if let Some(idx) = self.pattern_set.matches(&normalized).iter().next() {
+            return Some(ExclusionReason::Pattern(self.patterns[idx].clone()));
+        }
+
+        let name = normalized.rsplit('/').next().unwrap_or_default();
+
+        // 3. Extensions (fichiers seulement)
+        if !is_dir {
+            let name_lower = name.to_lowercase();
+            if let Some(ext) = self.extensions.iter().find(|ext| name_lower.ends_with(ext.as_str())) {
+                return Some(ExclusionReason::Extension(ext.clone()));
+            }
+        }
+
+        // 4. Cachés: le nom et ses dossiers parents sous la racine
+        // (une racine n'est jamais exclue parce qu'elle est cachée)
+        let root = self
+            .roots
+            .iter()
+            .filter(|root| is_under(&normalized, &root.path))
+            .max_by_key(|root| root.path.len());
+        if root.and_then(|root| root.hidden).unwrap_or(self.hidden) {
+            let relative = root
+                .map(|root| normalized[root.path.len()..].trim_start_matches('/'))
+                .unwrap_or(name);
+            if let Some(hidden) = relative.split('/').find(|part| is_hidden_name(part)) {
+                return Some(ExclusionReason::Hidden(hidden.to_string()));
+            }
+        }
+
+        // 5. Taille
+        if let (false, Some(size), Some(max)) = (is_dir, size, self.max_file_size) {
+            if size > max {
+                return Some(ExclusionReason::TooLarge { size, max });
+            }
+        }
+
+        None
+    }
+}
+
+// "/" comme séparateur, sans "/" final (sauf racine), en minuscules sous Windows
+fn normalize(path: &str) -> String {
+    let path = path.trim().replace('\\', "/");
+    let trimmed = path.trim_end_matches('/');
+    let path = if trimmed.is_empty() || trimmed.ends_with(':') {
+        // "/" ou "C:/": garder le séparateur de la racine
+        format!("{}/", trimmed)
+    } else {
+        trimmed.to_string()
+    };
+    if cfg!(windows) {
+        path.to_lowercase()
+    } else {
+        path
+    }
+}
+
+// path est prefix ou se trouve dessous (limite de dossier)
+fn is_under(path: &str, prefix: &str) -> bool {
+    match path.strip_prefix(prefix) {
+        Some(rest) => rest.is_empty() || rest.starts_with('/') || prefix.ends_with('/'),
+        None => false,
+    }
+}
+
+fn is_hidden_name(name: &str) -> bool {
+    name.starts_with('.') && name != "." && name != ".."
+}
+
+// Tests TDD
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matcher(patterns: &[&str], extensions: &[&str], dirs: &[&str]) -> ExclusionMatcher {
+        ExclusionMatcher::new(&ExclusionsConfig {
+            extensions: extensions.iter().map(|s| s.to_string()).collect(),
+            patterns: patterns.iter().map(|s| s.to_string()).collect(),
+            dirs: dirs.iter().map(|s| s.to_string()).collect(),
+            ..ExclusionsConfig::none()
+        })
+    }
+
+    fn excluded(m: &ExclusionMatcher, path: &str) -> bool {
+        m.is_excluded(Path::new(path), false, None)
+    }
+
+    #[test]
+    fn test_patterns_match_whole_names() {
+        let m = matcher(&[".git", "node_modules", "*.tmp", "target/debug", "build-??", "**/cache/*.bin"], &[], &[]);
+
+        assert!(excluded(&m, "/home/me/repo/.git/config"));
+        assert!(!excluded(&m, "/home/me/sites/my.github.io/index.html"));
+        assert!(!excluded(&m, "/home/me/repo/.gitignore"));
+        assert!(excluded(&m, "/proj/web/node_modules/react/index.js"));
+        assert!(!excluded(&m, "/proj/node_modules_backup.txt"));
+        assert!(excluded(&m, "/data/report.tmp"));
+        assert!(excluded(&m, "/rust/app/target/debug/app"));
+        assert!(!excluded(&m, "/rust/app/target/release/app"));
+        assert!(excluded(&m, "/ci/build-42/log.txt"));
+        assert!(!excluded(&m, "/ci/build-420/log.txt"));
+        assert!(excluded(&m, "/a/b/cache/blob.bin"));
+        assert!(!excluded(&m, "/a/b/cache/sub/blob.bin"));
+    }
+
+    #[test]
+    fn test_windows_and_unix_separators() {
+        let m = matcher(&["node_modules", "target/debug"], &[], &["C:\\Users\\me\\AppData", "/var/cache/"]);
+
+        assert!(excluded(&m, "C:\\Users\\me\\proj\\node_modules\\x.js"));
+        assert!(excluded(&m, "C:\\rust\\target\\debug\\app.exe"));
+        assert!(excluded(&m, "C:\\Users\\me\\AppData\\Local\\file.db"));
+        assert!(excluded(&m, "C:/Users/me/AppData/Roaming/x"));
+        assert!(excluded(&m, "/var/cache/apt/pkg.deb"));
+        assert!(excluded(&m, "/var/cache"));
+        // Préfixe aux limites de dossier seulement
+        assert!(!excluded(&m, "C:\\Users\\me\\AppDataBackup\\x"));
+        assert!(!excluded(&m, "/var/cache2/x"));
+
+        let root = matcher(&[], &[], &["/"]);
+        assert!(excluded(&root, "/etc/hosts"));
+    }
+
+    #[test]
+    fn test_extensions_hidden_and_size() {
+        let m = matcher(&[], &[".log", "bak", ".tar.gz"], &[]);
+        assert!(excluded(&m, "/x/APP.LOG"));
+        assert!(excluded(&m, "/x/old.bak"));
+        assert!(excluded(&m, "/x/archive.tar.gz"));
+        assert!(!excluded(&m, "/x/catalog"));
+        assert!(!excluded(&m, "/x/backup.gz"));
+        // Les extensions ne s'appliquent pas aux dossiers
+        assert!(!m.is_excluded(Path::new("/x/logs.log"), true, None));
+
+        let mut config = ExclusionsConfig {
+            hidden: true,
+            max_file_size_mb: 10,
+            ..ExclusionsConfig::none()
+        };
+        let m = ExclusionMatcher::new(&config).with_roots(&["/home/me/.config/app"]);
+        assert!(excluded(&m, "/home/me/docs/.env"));
+        assert!(excluded(&m, "/home/me/.ssh"));
+        // La racine elle-même est sous un dossier caché: seul le contenu compte
+        assert!(!excluded(&m, "/home/me/.config/app/settings.toml"));
+        assert!(!m.is_excluded(Path::new("/home/me/.config/app"), true, None));
+        assert!(excluded(&m, "/home/me/.config/app/.cache/x"));
+        assert!(m.is_excluded(Path::new("/x/video.mkv"), false, Some(11 * 1024 * 1024)));
+        assert!(!m.is_excluded(Path::new("/x/video.mkv"), false, Some(9 * 1024 * 1024)));
+        assert!(!m.is_excluded(Path::new("/x/dir"), true, Some(11 * 1024 * 1024)));
+
+        config.hidden = false;
+        assert!(!excluded(&ExclusionMatcher::new(&config), "/home/me/docs/.env"));
+    }
+
+    #[test]
+    fn test_hidden_per_root() {
+        let mut m = ExclusionMatcher::new(&ExclusionsConfig::none()).with_roots(&["/home/me", "/srv/www"]);
+        m.set_root_hidden(Path::new("/home/me/dotfiles"), Some(false));
+        m.set_root_hidden(Path::new("/srv/www"), Some(true));
+
+        // Réglage global (cachés inclus) hors des racines réglées
+        assert!(!excluded(&m, "/home/me/.bashrc"));
+        assert!(excluded(&m, "/srv/www/.htaccess"));
+        // La racine la plus profonde l'emporte
+        assert!(!excluded(&m, "/home/me/dotfiles/.vimrc"));
+
+        // Les réglages par racine survivent à un changement des règles
+        let m = m.reconfigured(&ExclusionsConfig {
+            hidden: true,
+            ..ExclusionsConfig::none()
+        });
+        assert!(excluded(&m, "/home/me/.bashrc"));
+        assert!(!excluded(&m, "/home/me/dotfiles/.vimrc"));
+        assert!(excluded(&m, "/srv/www/.htaccess"));
+
+        let mut m = m;
+        m.set_root_hidden(Path::new("/srv/www"), None);
+        m.remove_root(Path::new("/home/me/dotfiles"));
+        assert!(excluded(&m, "/home/me/dotfiles/.vimrc"));
+        assert!(excluded(&m, "/srv/www/.htaccess"));
+    }
+
+    #[test]
+    fn test_explain() {
+        let m = matcher(&[".git", "["], &[".tmp"], &["/srv/backup"]);
+        let explain = |path: &str| m.explain(Path::new(path), false, None);
+
+        assert_eq!(explain("/srv/backup/a.txt"), Some(ExclusionReason::Directory("/srv/backup".to_string())));
+        assert_eq!(explain("/repo/.git/HEAD"), Some(ExclusionReason::Pattern(".git".to_string())));
+        assert_eq!(explain("/repo/x.TMP"), Some(ExclusionReason::Extension(".tmp".to_string())));
+        assert_eq!(explain("/repo/main.rs"), None);
+        assert_eq!(
+            explain("/repo/.git/x.tmp").unwrap().to_string(),
+            "correspond au motif \".git\""
+        );
+        // Motif "[" (crochet non fermé): littéral
+        assert!(excluded(&m, "/odd/[/file"));
+    }
+}
@@ -1,16 +1,70 @@
 // src/search/file_watcher.rs
 // Surveillance des changements de fichiers en temps réel avec notify
+//
+// Les événements bruts passent par un Debouncer (debounce.rs): une rafale
+// sur un même fichier (enregistrement d'un éditeur) n'en donne qu'un.
+//
+// Renommages: Linux (inotify) envoie Name(From) puis Name(To) avec le même
+// cookie (tracker), suivis de Name(Both) portant les deux chemins; Windows
+// envoie From puis To sans cookie. Les deux moitiés sont appariées
+// (NotifyConverter). Un From resté seul (déplacé hors des dossiers
+// surveillés) devient une suppression, un To seul (arrivé de l'extérieur)
+// une création. Un dossier renommé entraîne la réécriture des chemins de
+// tous ses fichiers, dans l'index et dans la table files.
+//
+// Événements perdus: si la file vers le thread de conversion est pleine
+// (rafale d'un git checkout, d'une décompression...) ou si le noyau signale
+// un débordement (Flag::Rescan), les événements manquants sont notés
+// (LostEvents). Les dossiers surveillés concernés sont alors "sales":
+// take_dirty_roots() les rend pour une resynchronisation complète
+// (incremental::sync_root).
+//
+// Modes de surveillance: sur un partage réseau (NFS, SMB), un FUSE ou un
+// volume de VM/conteneur, les événements natifs n'arrivent pas (ou seulement
+// pour les changements faits par cette machine). Ces dossiers sont repérés
+// par le type de leur système de fichiers (mounts.rs) ou, faute de table des
+// montages, par une sonde: un fichier témoin créé puis supprimé doit produire
+// un événement. Ils sont alors scrutés par un PollWatcher (comparaison des
+// dates de modification à intervalle réglable, voir set_poll_interval).
 
 use anyhow::Result;
-use crossbeam_channel::{bounded, Receiver, Sender};
-use notify::{Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use crossbeam_channel::{bounded, Receiver, RecvTimeoutError, Sender, TrySendError};
+use notify::event::{ModifyKind, RenameMode};
+use notify::{Config, Event, EventKind, PollWatcher, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::{HashSet, VecDeque};
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 use std::thread;
+use std::time::{Duration, Instant};
 
-use super::SearchIndex;
+use super::debounce::{is_temp_file, Debouncer, DEFAULT_QUIET_WINDOW};
+use super::exclusions::ExclusionMatcher;
+use super::ignore_rules::{is_ignore_file, IgnoreTree};
+use super::mounts::{needs_polling, MountTable};
+use crate::config::ExclusionsConfig;
+use super::{FileEntry, SearchIndex};
 
-#[derive(Debug, Clone)]
+// Intervalle de publication des événements regroupés
+const DEBOUNCE_TICK: Duration = Duration::from_millis(25);
+
+// Délai max entre les deux moitiés d'un renommage (From puis To)
+const RENAME_PAIR_TIMEOUT: Duration = Duration::from_millis(200);
+
+// Au-delà de ce nombre de dossiers touchés par des pertes, tout est sale
+const MAX_LOST_DIRS: usize = 1000;
+
+// Événements mis en file (queue_events) appliqués par appel, pour ne pas
+// figer l'UI quand la réconciliation en trouve beaucoup
+const QUEUED_BATCH: usize = 500;
+
+// Intervalle de scrutation par défaut des dossiers sans événements natifs
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+// Attente max de l'événement du fichier témoin (voir probe_native_events)
+const CANARY_TIMEOUT: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum FileEvent {
     Created(PathBuf),
     Modified(PathBuf),
@@ -18,133 +72,353 @@ pub enum FileEvent {
     Renamed { from: PathBuf, to: PathBuf },
 }
 
+// Mode de surveillance d'un dossier
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchMode {
+    Native,  // Événements du système (inotify, ReadDirectoryChangesW, FSEvents)
+    Polling, // Scrutation périodique des dates de modification
+}
+
+impl WatchMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            WatchMode::Native => "natif",
+            WatchMode::Polling => "scrutation",
+        }
+    }
+}
+
+// Dossier surveillé et son mode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchedRoot {
+    pub path: PathBuf,
+    pub mode: WatchMode,
+    pub fs_type: Option<String>, // Type du système de fichiers, s'il est connu
+}
+
 pub struct FileWatcher {
     watcher: RecommendedWatcher,
+    poll_watcher: Option<PollWatcher>, // Créé au premier dossier scruté
+    poll_interval: Duration,
+    notify_tx: Sender<notify::Result<Event>>, // Pour créer le PollWatcher
+    mounts: MountTable,
     event_rx: Receiver<FileEvent>,
+    exclusions: ExclusionMatcher, // Exclusions de la config (voir set_exclusions)
+    ignore: IgnoreTree, // Fichiers .gitignore/.ignore/.xfinderignore des dossiers surveillés
+    quiet_window_ms: Arc<AtomicU64>, // Fenêtre de calme du Debouncer (modifiable à chaud)
+    roots: Vec<WatchedRoot>, // Dossiers surveillés
+    lost: Arc<Mutex<LostEvents>>, // Événements perdus depuis le dernier take_dirty_roots()
+    queued: Mutex<Vec<FileEvent>>, // Différences trouvées au démarrage (voir reconcile.rs)
     _watcher_thread: Option<thread::JoinHandle<()>>,
 }
 
 impl FileWatcher {
     pub fn new() -> Result<Self> {
         let (event_tx, event_rx) = bounded::<FileEvent>(1000);
-        let (notify_tx, notify_rx) = bounded(1000);
+        let (notify_tx, notify_rx) = bounded::<notify::Result<Event>>(1000);
+
+        let quiet_window_ms = Arc::new(AtomicU64::new(DEFAULT_QUIET_WINDOW.as_millis() as u64));
+        let lost = Arc::new(Mutex::new(LostEvents::default()));
 
-        // Thread pour traiter les événements notify et les convertir
+        // Thread pour convertir les événements notify et les regrouper par fichier
         let event_tx_clone = event_tx.clone();
+        let window = quiet_window_ms.clone();
+        let thread_lost = lost.clone();
         let watcher_thread = thread::spawn(move || {
-            while let Ok(result) = notify_rx.recv() {
-                if let Ok(event) = result {
-                    if let Some(file_event) = Self::process_notify_event(event) {
-                        let _ = event_tx_clone.send(file_event);
+            let mut converter = NotifyConverter::default();
+            let mut debouncer = Debouncer::default();
+            let mut last_drain = Instant::now();
+            loop {
+                match notify_rx.recv_timeout(DEBOUNCE_TICK) {
+                    Ok(Ok(event)) => {
+                        // Débordement de la file du noyau: des événements manquent
+                        if event.need_rescan() {
+                            if let Ok(mut lost) = thread_lost.lock() {
+                                lost.record(&event.paths);
+                            }
+                        }
+                        for file_event in converter.process_notify_event(event) {
+                            debouncer.push(file_event, Instant::now());
+                        }
+                    }
+                    Ok(Err(_)) | Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => return, // Watcher fermé
+                }
+                for file_event in converter.expire_unpaired(Instant::now()) {
+                    debouncer.push(file_event, Instant::now());
+                }
+                // Publier les fichiers calmes (au plus une fois par tick)
+                if debouncer.is_empty() || last_drain.elapsed() < DEBOUNCE_TICK {
+                    continue;
+                }
+                last_drain = Instant::now();
+                debouncer.set_window(Duration::from_millis(window.load(Ordering::Relaxed)));
+                for file_event in debouncer.drain_ready(last_drain) {
+                    if event_tx_clone.send(file_event).is_err() {
+                        return;
                     }
                 }
             }
         });
 
-        let watcher = RecommendedWatcher::new(
-            move |res| {
-                let _ = notify_tx.send(res);
-            },
-            Config::default(),
-        )?;
+        let watcher = RecommendedWatcher::new(notify_handler(notify_tx.clone(), lost.clone()), Config::default())?;
 
         Ok(Self {
             watcher,
+            poll_watcher: None,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            notify_tx,
+            mounts: MountTable::load(),
             event_rx,
+            exclusions: ExclusionMatcher::new(&ExclusionsConfig::none()),
+            ignore: IgnoreTree::new(),
+            quiet_window_ms,
+            roots: Vec::new(),
+            lost,
+            queued: Mutex::new(Vec::new()),
             _watcher_thread: Some(watcher_thread),
         })
     }
 
     // Surveiller un dossier
     pub fn watch_path(&mut self, path: &Path) -> Result<()> {
-        self.watcher.watch(path, RecursiveMode::Recursive)?;
+        self.watch_root(path, true)
+    }
+
+    // Surveiller un dossier, en lisant ou non ses .gitignore
+    // Le mode (natif ou scrutation) dépend de son système de fichiers
+    pub fn watch_root(&mut self, path: &Path, respect_vcs_ignore: bool) -> Result<()> {
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let fs_type = self.mounts.fs_type(&canonical).map(str::to_string);
+        let mode = match fs_type.as_deref() {
+            Some(fs_type) if needs_polling(fs_type) => WatchMode::Polling,
+            Some(_) => WatchMode::Native,
+            // Pas de table des montages (hors Linux): tester
+            None if probe_native_events(path) == Some(false) => WatchMode::Polling,
+            None => WatchMode::Native,
+        };
+        self.watch_root_as(path, respect_vcs_ignore, mode, fs_type)
+    }
+
+    fn watch_root_as(&mut self, path: &Path, respect_vcs_ignore: bool, mode: WatchMode, fs_type: Option<String>) -> Result<()> {
+        let mode = match mode {
+            // Watch natif refusé (limite de watches inotify atteinte...): scruter
+            WatchMode::Native if self.watcher.watch(path, RecursiveMode::Recursive).is_ok() => WatchMode::Native,
+            _ => {
+                self.poll_watcher()?.watch(path, RecursiveMode::Recursive)?;
+                WatchMode::Polling
+            }
+        };
+        self.exclusions.add_root(path);
+        self.ignore.add_root(path, respect_vcs_ignore);
+        self.roots.retain(|root| root.path != path);
+        self.roots.push(WatchedRoot { path: path.to_path_buf(), mode, fs_type });
         Ok(())
     }
 
     // Arrêter de surveiller un dossier
     pub fn unwatch_path(&mut self, path: &Path) -> Result<()> {
-        self.watcher.unwatch(path)?;
+        let mode = self.watch_mode(path).unwrap_or(WatchMode::Native);
+        match (mode, self.poll_watcher.as_mut()) {
+            (WatchMode::Polling, Some(poll_watcher)) => poll_watcher.unwatch(path)?,
+            _ => self.watcher.unwatch(path)?,
+        }
+        self.exclusions.remove_root(path);
+        self.ignore.remove_root(path);
+        self.roots.retain(|root| root.path != path);
         Ok(())
     }
 
+    // Dossiers surveillés et leur mode
+    pub fn watched_roots(&self) -> &[WatchedRoot] {
+        &self.roots
+    }
+
+    pub fn watch_mode(&self, path: &Path) -> Option<WatchMode> {
+        self.roots.iter().find(|root| root.path == path).map(|root| root.mode)
+    }
+
+    // Intervalle de scrutation des dossiers sans événements natifs
+    // (le PollWatcher est recréé: son premier parcours ne produit aucun événement)
+    pub fn set_poll_interval(&mut self, interval: Duration) -> Result<()> {
+        if interval == self.poll_interval {
+            return Ok(());
+        }
+        self.poll_interval = interval;
+        if self.poll_watcher.take().is_none() {
+            return Ok(());
+        }
+        let polled: Vec<PathBuf> = self
+            .roots
+            .iter()
+            .filter(|root| root.mode == WatchMode::Polling)
+            .map(|root| root.path.clone())
+            .collect();
+        for path in polled {
+            self.poll_watcher()?.watch(&path, RecursiveMode::Recursive)?;
+        }
+        Ok(())
+    }
+
+    fn poll_watcher(&mut self) -> Result<&mut PollWatcher> {
+        if self.poll_watcher.is_none() {
+            let config = Config::default().with_poll_interval(self.poll_interval);
+            self.poll_watcher = Some(PollWatcher::new(notify_handler(self.notify_tx.clone(), self.lost.clone()), config)?);
+        }
+        Ok(self.poll_watcher.as_mut().expect("PollWatcher créé ci-dessus"))
+    }
+
+    // Règles d'exclusion à appliquer aux événements (mêmes que le scanner)
+    pub fn set_exclusions(&mut self, config: &ExclusionsConfig) {
+        self.exclusions = self.exclusions.reconfigured(config);
+    }
+
+    // Exclut ou non les cachés sous un dossier surveillé (None: règle globale)
+    pub fn set_root_hidden(&mut self, path: &Path, exclude_hidden: Option<bool>) {
+        self.exclusions.set_root_hidden(path, exclude_hidden);
+    }
+
+    // Change la prise en compte des .gitignore d'un dossier déjà surveillé
+    pub fn set_respect_vcs_ignore(&mut self, path: &Path, respect_vcs_ignore: bool) {
+        self.ignore.add_root(path, respect_vcs_ignore);
+    }
+
+    // Délai sans nouvel événement avant de publier les changements d'un fichier
+    pub fn set_quiet_window(&mut self, window: Duration) {
+        self.quiet_window_ms.store(window.as_millis() as u64, Ordering::Relaxed);
+    }
+
+    // Ajoute des changements trouvés hors des événements (réconciliation au
+    // démarrage): appliqués avant les événements en direct
+    pub fn queue_events(&self, events: Vec<FileEvent>) {
+        if let Ok(mut queued) = self.queued.lock() {
+            queued.extend(events);
+        }
+    }
+
     // Récupérer les événements en attente
     pub fn poll_events(&self) -> Vec<FileEvent> {
-        let mut events = Vec::new();
+        let mut events = match self.queued.lock() {
+            Ok(mut queued) => {
+                let count = queued.len().min(QUEUED_BATCH);
+                queued.drain(..count).collect()
+            }
+            Err(_) => Vec::new(),
+        };
         while let Ok(event) = self.event_rx.try_recv() {
             events.push(event);
         }
         events
     }
 
-    // Convertir événement notify en FileEvent
-    fn process_notify_event(event: Event) -> Option<FileEvent> {
-        match event.kind {
-            EventKind::Create(_) => {
-                if let Some(path) = event.paths.first() {
-                    if path.is_file() {
-                        return Some(FileEvent::Created(path.clone()));
-                    }
-                }
-            }
-            EventKind::Modify(_) => {
-                if let Some(path) = event.paths.first() {
-                    if path.is_file() {
-                        return Some(FileEvent::Modified(path.clone()));
-                    }
-                }
-            }
-            EventKind::Remove(_) => {
-                if let Some(path) = event.paths.first() {
-                    return Some(FileEvent::Removed(path.clone()));
-                }
-            }
-            EventKind::Access(_) => {
-                // Rename events sur Windows
-                if event.paths.len() == 2 {
-                    return Some(FileEvent::Renamed {
-                        from: event.paths[0].clone(),
-                        to: event.paths[1].clone(),
-                    });
+    // Dossiers surveillés où des événements ont été perdus depuis le dernier
+    // appel: l'index n'y reflète plus le disque
+    pub fn take_dirty_roots(&self) -> Vec<PathBuf> {
+        let lost = match self.lost.lock() {
+            Ok(mut lost) => std::mem::take(&mut *lost),
+            Err(_) => return Vec::new(),
+        };
+        self.roots
+            .iter()
+            .map(|root| &root.path)
+            .filter(|root| lost.everything || lost.dirs.iter().any(|dir| dir.starts_with(root) || root.starts_with(dir)))
+            .cloned()
+            .collect()
+    }
+
+    // Exclusions de la config ou règles des fichiers ignore
+    fn is_excluded(&self, path: &Path) -> bool {
+        let size = std::fs::metadata(path).ok().map(|metadata| metadata.len());
+        self.exclusions.is_excluded(path, false, size) || self.ignore.is_ignored(path, false)
+    }
+
+    fn is_dir_excluded(&self, path: &Path) -> bool {
+        self.exclusions.is_excluded(path, true, None) || self.ignore.is_ignored(path, true)
+    }
+
+    // Fichiers d'un dossier apparu (créé ou arrivé de l'extérieur), sans
+    // descendre dans les dossiers exclus ni suivre les liens
+    fn files_under(&self, dir: &Path) -> Vec<PathBuf> {
+        let mut files = Vec::new();
+        let mut stack = vec![dir.to_path_buf()];
+        while let Some(dir) = stack.pop() {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let Ok(file_type) = entry.file_type() else {
+                    continue;
+                };
+                let path = entry.path();
+                if file_type.is_dir() && !self.is_dir_excluded(&path) {
+                    stack.push(path);
+                } else if file_type.is_file() {
+                    files.push(path);
                 }
             }
-            _ => {}
         }
-        None
+        files
     }
 
-    // Vérifie si un fichier doit être exclu (similaire au scanner)
-    fn should_exclude(
-        path: &Path,
-        filename: &str,
-        excluded_extensions: &[String],
-        excluded_patterns: &[String],
-        excluded_dirs: &[String],
-    ) -> bool {
-        let path_str = path.to_string_lossy().to_string();
+    // Renommage d'un fichier ou d'un dossier (tous ses fichiers suivent)
+    // Retourne le nombre de fichiers mis à jour
+    fn apply_rename(
+        &self,
+        index: &SearchIndex,
+        database: Option<&std::sync::Arc<crate::database::Database>>,
+        from: &Path,
+        to: &Path,
+    ) -> Result<usize> {
+        let from_str = from.to_string_lossy().to_string();
+        let to_str = to.to_string_lossy().to_string();
 
-        // 1. Vérifier les dossiers exclus
-        for excluded_dir in excluded_dirs {
-            if path_str.starts_with(excluded_dir) || path_str.contains(&format!("\\{}", excluded_dir)) {
-                return true;
+        if to.is_dir() {
+            // Déplacé vers un dossier exclu: ses fichiers sortent de l'index
+            if self.is_dir_excluded(to) {
+                index.delete_dir(&from_str)?;
+                if let Some(db) = database {
+                    let _ = db.delete_files_under(&from_str);
+                }
+                return Ok(1);
             }
+            let moved = index.rename_dir(&from_str, &to_str)?;
+            if let Some(db) = database {
+                let _ = db.rename_dir(&from_str, &to_str);
+            }
+            return Ok(moved);
         }
 
-        // 2. Vérifier les extensions exclues
-        if let Some(ext) = path.extension() {
-            let ext_with_dot = format!(".{}", ext.to_string_lossy());
-            if excluded_extensions.contains(&ext_with_dot) {
-                return true;
+        let Some(filename) = to.file_name() else {
+            return Ok(0);
+        };
+        // Renommé vers un nom exclu: supprimer l'ancien
+        if self.is_excluded(to) {
+            index.delete_file_by_path(&from_str)?;
+            if let Some(db) = database {
+                let _ = db.delete_file(&from_str);
             }
+            return Ok(1);
+        }
+        index.update_file_path(&from_str, &to_str, &filename.to_string_lossy())?;
+        if let Some(db) = database {
+            let _ = db.rename_file(&from_str, &to_str);
         }
+        Ok(1)
+    }
 
-        // 3. Vérifier les patterns
-        for pattern in excluded_patterns {
-            if path_str.contains(pattern) || filename.contains(pattern) {
-                return true;
+    // Un fichier de règles a changé: relire les règles de son dossier
+    fn refresh_ignore_rules(&self, events: &[FileEvent]) {
+        for event in events {
+            let paths: Vec<&PathBuf> = match event {
+                FileEvent::Created(path) | FileEvent::Modified(path) | FileEvent::Removed(path) => vec![path],
+                FileEvent::Renamed { from, to } => vec![from, to],
+            };
+            for path in paths {
+                if path.file_name().is_some_and(|name| is_ignore_file(&name.to_string_lossy())) {
+                    self.ignore.invalidate(path);
+                }
             }
         }
-
-        false
     }
 
     // Appliquer les événements en batch (optimisé)
@@ -152,14 +426,12 @@ impl FileWatcher {
         &self,
         index: &SearchIndex,
         database: Option<&std::sync::Arc<crate::database::Database>>,
-        excluded_extensions: &[String],
-        excluded_patterns: &[String],
-        excluded_dirs: &[String],
     ) -> Result<usize> {
         let events = self.poll_events();
         if events.is_empty() {
             return Ok(0);
         }
+        self.refresh_ignore_rules(&events);
 
         let mut updated_count = 0;
         let mut created_files = Vec::new();
@@ -168,18 +440,25 @@ impl FileWatcher {
         let mut renamed_files = Vec::new();
 
         // 1. Grouper les événements par type
+        // Un dossier apparu (ou sorti d'un dossier exclu) apporte tous ses fichiers
         for event in events {
             match event {
+                FileEvent::Created(path) if path.is_dir() => created_files.extend(self.files_under(&path)),
                 FileEvent::Created(path) => created_files.push(path),
                 FileEvent::Modified(path) => modified_files.push(path),
                 FileEvent::Removed(path) => removed_paths.push(path),
+                FileEvent::Renamed { from, to } if to.is_dir() && self.is_dir_excluded(&from) => {
+                    created_files.extend(self.files_under(&to))
+                }
+                FileEvent::Renamed { from, to } if to.is_file() && self.is_excluded(&from) => created_files.push(to),
                 FileEvent::Renamed { from, to } => renamed_files.push((from, to)),
             }
         }
 
         // 2. Traiter les créations en batch
+        // Le writer partagé valide par lots: pas de commit par événement
         if !created_files.is_empty() {
-            let mut writer = index.create_writer()?;
+            let writer = index.writer()?;
             let mut db_batch = Vec::new();
 
             for path in created_files {
@@ -187,18 +466,19 @@ impl FileWatcher {
                     let path_str = path.to_string_lossy().to_string();
                     let filename_str = filename.to_string_lossy().to_string();
 
-                    if Self::should_exclude(&path, &filename_str, excluded_extensions, excluded_patterns, excluded_dirs) {
+                    if self.is_excluded(&path) {
                         continue;
                     }
 
-                    if index.add_file(&mut writer, &path_str, &filename_str).is_ok() {
+                    // update(): un fichier déjà indexé n'est pas dupliqué
+                    if writer.update(FileEntry::from_path(&path_str, &filename_str)).is_ok() {
                         updated_count += 1;
 
                         if let Some(db) = database {
                             if let Ok(metadata) = std::fs::metadata(&path) {
                                 let now = chrono::Utc::now().timestamp();
                                 let file_record = crate::database::queries::FileRecord {
-                                    id: format!("{:x}", path_str.as_bytes().iter().fold(0u64, |acc, &b| acc.wrapping_mul(31).wrapping_add(b as u64))),
+                                    id: crate::database::queries::file_id(&path_str),
                                     path: path_str.clone(),
                                     filename: filename_str.clone(),
                                     extension: path.extension()
@@ -225,8 +505,6 @@ impl FileWatcher {
                 }
             }
 
-            writer.commit()?;
-
             // Batch insert dans DB
             if !db_batch.is_empty() {
                 if let Some(db) = database {
@@ -241,7 +519,7 @@ impl FileWatcher {
                 let path_str = path.to_string_lossy().to_string();
                 let filename_str = filename.to_string_lossy().to_string();
 
-                if Self::should_exclude(&path, &filename_str, excluded_extensions, excluded_patterns, excluded_dirs) {
+                if self.is_excluded(&path) {
                     let _ = index.delete_file_by_path(&path_str);
                     if let Some(db) = database {
                         let _ = db.delete_file(&path_str);
@@ -266,7 +544,7 @@ impl FileWatcher {
                             }
 
                             let file_record = crate::database::queries::FileRecord {
-                                id: format!("{:x}", path_str.as_bytes().iter().fold(0u64, |acc, &b| acc.wrapping_mul(31).wrapping_add(b as u64))),
+                                id: crate::database::queries::file_id(&path_str),
                                 path: path_str.clone(),
                                 filename: filename_str.clone(),
                                 extension: path.extension()
@@ -294,31 +572,23 @@ impl FileWatcher {
         }
 
         // 4. Traiter les suppressions en batch
+        // Le chemin supprimé peut être un dossier: ses fichiers disparaissent aussi
         for path in removed_paths {
             let path_str = path.to_string_lossy().to_string();
             if index.delete_file_by_path(&path_str).is_ok() {
                 updated_count += 1;
             }
+            let _ = index.delete_dir(&path_str);
             if let Some(db) = database {
                 let _ = db.delete_file(&path_str);
+                let _ = db.delete_files_under(&path_str);
             }
         }
 
-        // 5. Traiter les renommages
+        // 5. Traiter les renommages (fichiers ou dossiers entiers)
         for (from, to) in renamed_files {
-            if let Some(filename) = to.file_name() {
-                let from_str = from.to_string_lossy().to_string();
-                let to_str = to.to_string_lossy().to_string();
-                let filename_str = filename.to_string_lossy().to_string();
-
-                if Self::should_exclude(&to, &filename_str, excluded_extensions, excluded_patterns, excluded_dirs) {
-                    let _ = index.delete_file_by_path(&from_str);
-                    continue;
-                }
-
-                if index.update_file_path(&from_str, &to_str, &filename_str).is_ok() {
-                    updated_count += 1;
-                }
+            if let Ok(count) = self.apply_rename(index, database, &from, &to) {
+                updated_count += count;
             }
         }
 
@@ -330,13 +600,17 @@ impl FileWatcher {
         &self,
         index: &SearchIndex,
         database: Option<&std::sync::Arc<crate::database::Database>>,
-        excluded_extensions: &[String],
-        excluded_patterns: &[String],
-        excluded_dirs: &[String],
     ) -> Result<usize> {
         let events = self.poll_events();
+        self.refresh_ignore_rules(&events);
         let mut updated_count = 0;
 
+        // Un dossier créé apporte tous ses fichiers
+        let events = events.into_iter().flat_map(|event| match event {
+            FileEvent::Created(path) if path.is_dir() => self.files_under(&path).into_iter().map(FileEvent::Created).collect(),
+            event => vec![event],
+        });
+
         for event in events {
             match event {
                 FileEvent::Created(path) => {
@@ -345,13 +619,12 @@ impl FileWatcher {
                         let filename_str = filename.to_string_lossy().to_string();
 
                         // Vérifier si le fichier doit être exclu
-                        if Self::should_exclude(&path, &filename_str, excluded_extensions, excluded_patterns, excluded_dirs) {
+                        if self.is_excluded(&path) {
                             continue; // Skip ce fichier
                         }
 
-                        if let Ok(mut writer) = index.create_writer() {
-                            if index.add_file(&mut writer, &path_str, &filename_str).is_ok() {
-                                let _ = writer.commit();
+                        if let Ok(writer) = index.writer() {
+                            if writer.update(FileEntry::from_path(&path_str, &filename_str)).is_ok() {
                                 updated_count += 1;
 
                                 // Ajouter dans SQLite aussi
@@ -359,7 +632,7 @@ impl FileWatcher {
                                     if let Ok(metadata) = std::fs::metadata(&path) {
                                         let now = chrono::Utc::now().timestamp();
                                         let file_record = crate::database::queries::FileRecord {
-                                            id: format!("{:x}", path_str.as_bytes().iter().fold(0u64, |acc, &b| acc.wrapping_mul(31).wrapping_add(b as u64))),
+                                            id: crate::database::queries::file_id(&path_str),
                                             path: path_str.clone(),
                                             filename: filename_str.clone(),
                                             extension: path.extension()
@@ -392,7 +665,7 @@ impl FileWatcher {
                         let filename_str = filename.to_string_lossy().to_string();
 
                         // Vérifier si le fichier doit être exclu
-                        if Self::should_exclude(&path, &filename_str, excluded_extensions, excluded_patterns, excluded_dirs) {
+                        if self.is_excluded(&path) {
                             // Si le fichier est maintenant exclu, le supprimer de l'index et de la DB
                             let _ = index.delete_file_by_path(&path_str);
                             if let Some(db) = database {
@@ -420,7 +693,7 @@ impl FileWatcher {
                                     }
 
                                     let file_record = crate::database::queries::FileRecord {
-                                        id: format!("{:x}", path_str.as_bytes().iter().fold(0u64, |acc, &b| acc.wrapping_mul(31).wrapping_add(b as u64))),
+                                        id: crate::database::queries::file_id(&path_str),
                                         path: path_str.clone(),
                                         filename: filename_str.clone(),
                                         extension: path.extension()
@@ -457,27 +730,18 @@ impl FileWatcher {
                     if index.delete_file_by_path(&path_str).is_ok() {
                         updated_count += 1;
                     }
+                    // Un dossier supprimé emporte ses fichiers
+                    let _ = index.delete_dir(&path_str);
                     // Supprimer de SQLite aussi
                     if let Some(db) = database {
                         let _ = db.delete_file(&path_str);
+                        let _ = db.delete_files_under(&path_str);
                     }
                 }
                 FileEvent::Renamed { from, to } => {
-                    if let Some(filename) = to.file_name() {
-                        let from_str = from.to_string_lossy().to_string();
-                        let to_str = to.to_string_lossy().to_string();
-                        let filename_str = filename.to_string_lossy().to_string();
-
-                        // Vérifier si le nouveau fichier doit être exclu
-                        if Self::should_exclude(&to, &filename_str, excluded_extensions, excluded_patterns, excluded_dirs) {
-                            // Si renommé vers un nom exclu, supprimer l'ancien
-                            let _ = index.delete_file_by_path(&from_str);
-                            continue;
-                        }
-
-                        if index.update_file_path(&from_str, &to_str, &filename_str).is_ok() {
-                            updated_count += 1;
-                        }
+                    // Fichier ou dossier entier (tous ses fichiers suivent)
+                    if let Ok(count) = self.apply_rename(index, database, &from, &to) {
+                        updated_count += count;
                     }
                 }
             }
@@ -487,6 +751,162 @@ impl FileWatcher {
     }
 }
 
+// Transmet les événements de notify (natif ou scrutation) au thread de
+// conversion. Ne bloque jamais le thread de notify (le noyau déborderait):
+// file pleine, l'événement est perdu et noté.
+fn notify_handler(notify_tx: Sender<notify::Result<Event>>, lost: Arc<Mutex<LostEvents>>) -> impl Fn(notify::Result<Event>) + Send + 'static {
+    move |res| {
+        if let Err(TrySendError::Full(res)) = notify_tx.try_send(res) {
+            if let Ok(mut lost) = lost.lock() {
+                match res {
+                    Ok(event) => lost.record(&event.paths),
+                    Err(error) => lost.record(&error.paths),
+                }
+            }
+        }
+    }
+}
+
+// Sonde: un fichier témoin créé dans root produit-il un événement natif?
+// None si le test est impossible (dossier en lecture seule...)
+fn probe_native_events(root: &Path) -> Option<bool> {
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let mut watcher = RecommendedWatcher::new(
+        move |res| {
+            let _ = tx.send(res);
+        },
+        Config::default(),
+    )
+    .ok()?;
+    watcher.watch(root, RecursiveMode::NonRecursive).ok()?;
+
+    let canary = root.join(format!(".xfinder-canary-{}", std::process::id()));
+    std::fs::write(&canary, b"").ok()?;
+    let deadline = Instant::now() + CANARY_TIMEOUT;
+    let mut seen = false;
+    while let Some(left) = deadline.checked_duration_since(Instant::now()) {
+        match rx.recv_timeout(left) {
+            Ok(Ok(event)) if event.paths.iter().any(|path| path.file_name() == canary.file_name()) => {
+                seen = true;
+                break;
+            }
+            Ok(_) => {}
+            Err(_) => break,
+        }
+    }
+    let _ = std::fs::remove_file(&canary);
+    Some(seen)
+}
+
+// Événements perdus: dossiers des chemins concernés
+// (sans chemin, comme un débordement du noyau: tous les dossiers)
+#[derive(Debug, Default)]
+struct LostEvents {
+    dirs: HashSet<PathBuf>,
+    everything: bool,
+}
+
+impl LostEvents {
+    fn record(&mut self, paths: &[PathBuf]) {
+        if paths.is_empty() || self.dirs.len() >= MAX_LOST_DIRS {
+            self.everything = true;
+            self.dirs.clear();
+            return;
+        }
+        if self.everything {
+            return;
+        }
+        for path in paths {
+            self.dirs.insert(path.parent().unwrap_or(path).to_path_buf());
+        }
+    }
+}
+
+// Convertit les événements notify en FileEvent et apparie les deux moitiés
+// des renommages (voir l'en-tête du fichier)
+#[derive(Debug, Default)]
+struct NotifyConverter {
+    // Moitiés From en attente de leur To: (cookie, chemin, reçu à)
+    pending_from: VecDeque<(Option<usize>, PathBuf, Instant)>,
+    // Dernier renommage apparié, que le Name(Both) qui suit répète
+    last_rename: Option<(PathBuf, PathBuf)>,
+}
+
+impl NotifyConverter {
+    fn process_notify_event(&mut self, event: Event) -> Vec<FileEvent> {
+        let tracker = event.tracker();
+        let Some(path) = event.paths.first().cloned() else {
+            return Vec::new();
+        };
+        match event.kind {
+            // Un dossier créé apporte ses fichiers (voir apply_events_batch)
+            // Un temporaire déjà renommé compte quand même: le debouncer sait
+            // ainsi qu'il est neuf (sa disparition n'a rien à retirer de l'index)
+            EventKind::Create(_) if path.exists() || is_temp_file(&path) => vec![FileEvent::Created(path)],
+            EventKind::Modify(ModifyKind::Name(RenameMode::From)) => {
+                self.pending_from.push_back((tracker, path, Instant::now()));
+                Vec::new()
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::To)) => match self.take_from(tracker) {
+                Some(from) => {
+                    self.last_rename = Some((from.clone(), path.clone()));
+                    vec![FileEvent::Renamed { from, to: path }]
+                }
+                // Arrivé d'un dossier non surveillé
+                None => vec![FileEvent::Created(path)],
+            },
+            EventKind::Modify(ModifyKind::Name(RenameMode::Both)) if event.paths.len() == 2 => {
+                let to = event.paths[1].clone();
+                if self.last_rename.as_ref() == Some(&(path.clone(), to.clone())) {
+                    return Vec::new(); // Déjà publié par From/To
+                }
+                self.pending_from.retain(|(_, from, _)| from != &path);
+                self.last_rename = Some((path.clone(), to.clone()));
+                vec![FileEvent::Renamed { from: path, to }]
+            }
+            // Renommage sans précision (FSEvents...): l'état du disque tranche
+            EventKind::Modify(ModifyKind::Name(_)) => {
+                if path.exists() {
+                    vec![FileEvent::Created(path)]
+                } else {
+                    vec![FileEvent::Removed(path)]
+                }
+            }
+            EventKind::Modify(_) if path.is_file() => vec![FileEvent::Modified(path)],
+            EventKind::Remove(_) => vec![FileEvent::Removed(path)],
+            // Rename events sur Windows
+            EventKind::Access(_) if event.paths.len() == 2 => vec![FileEvent::Renamed {
+                from: path,
+                to: event.paths[1].clone(),
+            }],
+            _ => Vec::new(),
+        }
+    }
+
+    // Moitié From correspondant au cookie (sans cookie: la plus récente sans cookie)
+    fn take_from(&mut self, tracker: Option<usize>) -> Option<PathBuf> {
+        let position = match tracker {
+            Some(_) => self.pending_from.iter().position(|(cookie, _, _)| *cookie == tracker),
+            None => self.pending_from.iter().rposition(|(cookie, _, _)| cookie.is_none()),
+        }?;
+        self.pending_from.remove(position).map(|(_, path, _)| path)
+    }
+
+    // From sans To après le délai: déplacé hors des dossiers surveillés
+    fn expire_unpaired(&mut self, now: Instant) -> Vec<FileEvent> {
+        let mut events = Vec::new();
+        while let Some((_, _, received)) = self.pending_from.front() {
+            if now.duration_since(*received) < RENAME_PAIR_TIMEOUT {
+                break;
+            }
+            if let Some((_, path, _)) = self.pending_from.pop_front() {
+                events.push(FileEvent::Removed(path));
+            }
+        }
+        events
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -531,6 +951,176 @@ mod tests {
         assert!(events.iter().any(|e| matches!(e, FileEvent::Created(_))));
     }
 
+    #[test]
+    fn test_editor_save_is_one_event() {
+        let mut watcher = FileWatcher::new().unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        watcher.set_quiet_window(std::time::Duration::from_millis(150));
+        watcher.watch_path(temp_dir.path()).unwrap();
+
+        // Écriture atomique d'un éditeur: fichier temporaire renommé sur le vrai
+        let doc = temp_dir.path().join("doc.txt");
+        let tmp = temp_dir.path().join("doc.txt.tmp");
+        fs::write(&tmp, "v1").unwrap();
+        fs::write(&tmp, "v1 + v2").unwrap();
+        fs::rename(&tmp, &doc).unwrap();
+
+        std::thread::sleep(std::time::Duration::from_millis(600));
+        let events = watcher.poll_events();
+        assert_eq!(events.len(), 1, "{:?}", events);
+        assert!(matches!(&events[0], FileEvent::Created(path) | FileEvent::Modified(path) if *path == doc));
+    }
+
+    #[test]
+    fn test_lost_events_mark_roots_dirty() {
+        let mut watcher = FileWatcher::new().unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        let (docs, photos) = (temp_dir.path().join("docs"), temp_dir.path().join("photos"));
+        fs::create_dir_all(&docs).unwrap();
+        fs::create_dir_all(&photos).unwrap();
+        watcher.watch_path(&docs).unwrap();
+        watcher.watch_path(&photos).unwrap();
+        assert!(watcher.take_dirty_roots().is_empty());
+
+        // Événement perdu sous docs: seul docs est à resynchroniser (une fois)
+        watcher.lost.lock().unwrap().record(&[docs.join("sub").join("a.txt")]);
+        assert_eq!(watcher.take_dirty_roots(), vec![docs.clone()]);
+        assert!(watcher.take_dirty_roots().is_empty());
+
+        // Débordement du noyau (sans chemin): tous les dossiers
+        watcher.lost.lock().unwrap().record(&[]);
+        watcher.lost.lock().unwrap().record(&[docs.join("b.txt")]);
+        assert_eq!(watcher.take_dirty_roots(), vec![docs.clone(), photos.clone()]);
+
+        watcher.unwatch_path(&photos).unwrap();
+        watcher.lost.lock().unwrap().record(&[]);
+        assert_eq!(watcher.take_dirty_roots(), vec![docs]);
+    }
+
+    #[test]
+    fn test_watch_modes() {
+        let mut watcher = FileWatcher::new().unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        let (local, share) = (temp_dir.path().join("local"), temp_dir.path().join("share"));
+        fs::create_dir_all(&local).unwrap();
+        fs::create_dir_all(&share).unwrap();
+
+        // Disque local: les événements natifs arrivent (le témoin ne reste pas)
+        assert_eq!(probe_native_events(&local), Some(true));
+        assert_eq!(fs::read_dir(&local).unwrap().count(), 0);
+        watcher.watch_path(&local).unwrap();
+        assert_eq!(watcher.watch_mode(&local), Some(WatchMode::Native));
+
+        // Dossier scruté (comme un partage réseau)
+        watcher.set_poll_interval(Duration::from_millis(100)).unwrap();
+        watcher.set_quiet_window(Duration::from_millis(50));
+        watcher.watch_root_as(&share, true, WatchMode::Polling, Some("nfs4".to_string())).unwrap();
+        assert_eq!(watcher.watch_mode(&share), Some(WatchMode::Polling));
+        assert_eq!(watcher.watched_roots()[1].fs_type.as_deref(), Some("nfs4"));
+
+        // Changer l'intervalle recrée le PollWatcher sans perdre le dossier
+        watcher.set_poll_interval(Duration::from_millis(50)).unwrap();
+        fs::write(share.join("remote.txt"), "written elsewhere").unwrap();
+        std::thread::sleep(Duration::from_millis(600));
+        let events = watcher.poll_events();
+        assert!(events.contains(&FileEvent::Created(share.join("remote.txt"))), "{:?}", events);
+
+        watcher.unwatch_path(&share).unwrap();
+        assert_eq!(watcher.watched_roots().len(), 1);
+    }
+
+    #[test]
+    fn test_rename_pairs() {
+        let mut watcher = FileWatcher::new().unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        let watched = temp_dir.path().join("watched");
+        let outside = temp_dir.path().join("outside");
+        fs::create_dir_all(&watched).unwrap();
+        fs::create_dir_all(&outside).unwrap();
+        fs::write(watched.join("a.txt"), "a").unwrap();
+        fs::write(outside.join("in.txt"), "in").unwrap();
+        watcher.set_quiet_window(std::time::Duration::from_millis(100));
+        watcher.watch_path(&watched).unwrap();
+
+        // Renommage dans le dossier surveillé: un seul événement apparié
+        fs::rename(watched.join("a.txt"), watched.join("b.txt")).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(500));
+        assert_eq!(
+            watcher.poll_events(),
+            vec![FileEvent::Renamed { from: watched.join("a.txt"), to: watched.join("b.txt") }]
+        );
+
+        // Sorti des dossiers surveillés: supprimé; arrivé de l'extérieur: créé
+        fs::rename(watched.join("b.txt"), outside.join("b.txt")).unwrap();
+        fs::rename(outside.join("in.txt"), watched.join("in.txt")).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(700));
+        let events = watcher.poll_events();
+        assert_eq!(events.len(), 2, "{:?}", events);
+        assert!(events.contains(&FileEvent::Removed(watched.join("b.txt"))));
+        assert!(events.contains(&FileEvent::Created(watched.join("in.txt"))));
+    }
+
+    #[test]
+    fn test_directory_move_rewrites_children() {
+        let mut watcher = FileWatcher::new().unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        let watched = temp_dir.path().join("watched");
+        let docs = watched.join("docs");
+        fs::create_dir_all(docs.join("sub")).unwrap();
+        let files = [docs.join("a.txt"), docs.join("sub").join("b.txt")];
+        for file in &files {
+            fs::write(file, "content").unwrap();
+        }
+
+        // Index et table files déjà remplis par un scan
+        let index = SearchIndex::new(&temp_dir.path().join("index"), 2, 20).unwrap();
+        let database = Arc::new(crate::database::Database::in_memory().unwrap());
+        let writer = index.writer().unwrap();
+        for file in &files {
+            let path = file.to_string_lossy().to_string();
+            writer.update(FileEntry::from_path(&path, &file.file_name().unwrap().to_string_lossy())).unwrap();
+            database
+                .upsert_file(&crate::database::queries::FileRecord {
+                    id: crate::database::queries::file_id(&path),
+                    path,
+                    filename: file.file_name().unwrap().to_string_lossy().to_string(),
+                    extension: Some(".txt".to_string()),
+                    size: 7,
+                    modified: 0,
+                    created: 0,
+                    hash: None,
+                    indexed_at: 0,
+                })
+                .unwrap();
+        }
+        index.flush().unwrap();
+
+        watcher.set_quiet_window(std::time::Duration::from_millis(100));
+        watcher.watch_path(&watched).unwrap();
+        let archive = watched.join("archive");
+        fs::rename(&docs, &archive).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(500));
+
+        assert_eq!(watcher.apply_events_batch(&index, Some(&database)).unwrap(), 2);
+        index.flush().unwrap();
+
+        let docs_str = docs.to_string_lossy().to_string();
+        let archive_str = archive.to_string_lossy().to_string();
+        assert!(index.paths_under(&docs_str).unwrap().is_empty());
+        let mut moved = index.paths_under(&archive_str).unwrap();
+        moved.sort();
+        assert_eq!(
+            moved,
+            vec![
+                archive.join("a.txt").to_string_lossy().to_string(),
+                archive.join("sub").join("b.txt").to_string_lossy().to_string(),
+            ]
+        );
+        let moved_row = database.get_file_by_path(&archive.join("sub").join("b.txt").to_string_lossy()).unwrap();
+        assert!(moved_row.is_some());
+        assert!(database.get_file_by_path(&files[0].to_string_lossy()).unwrap().is_none());
+    }
+
     #[test]
     #[ignore] // Long test - run with --ignored
     fn test_batch_processing_1000_files() {
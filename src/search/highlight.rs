@@ -0,0 +1,239 @@
+// src/search/highlight.rs
+// Surlignage des fragments qui expliquent un résultat
+//
+// Les n-grams et la recherche floue trouvent des fichiers dont le lien avec
+// la requête n'est pas toujours évident. Plutôt que de demander à Tantivy des
+// snippets (pensés pour du texte long, et muets pour FuzzyTermQuery), on
+// réaligne les mots de la requête sur le nom et le chemin stockés:
+// - recherche normale / exacte: occurrences de la sous-chaîne (sans casse)
+// - recherche floue: meilleure sous-chaîne à distance <= fuzzy_distance
+//
+// Les plages retournées sont des plages d'octets dans le texte d'origine,
+// triées et fusionnées, directement utilisables pour découper la chaîne.
+
+use std::ops::Range;
+
+use super::pattern::FilenamePattern;
+use super::query::{FieldFilter, QueryNode};
+use super::SearchOptions;
+
+// Mots de la requête à surligner dans le nom et dans le chemin
+#[derive(Debug, Clone, Default)]
+pub struct Highlighter {
+    filename_needles: Vec<String>,
+    path_needles: Vec<String>,
+    fuzzy_distance: Option<u8>,
+    // Modes glob / regex: plages trouvées par le motif (nom uniquement)
+    pattern: Option<FilenamePattern>,
+}
+
+impl Highlighter {
+    // Collecte les mots positifs de la requête (les exclusions ne sont pas surlignées)
+    pub fn from_query(ast: &QueryNode, options: &SearchOptions) -> Self {
+        // Mêmes zones que la recherche: les deux si aucune n'est cochée
+        let both = !options.search_in_filename && !options.search_in_path;
+        let mut highlighter = Self {
+            fuzzy_distance: options.fuzzy_search.then_some(options.fuzzy_distance),
+            ..Default::default()
+        };
+        highlighter.collect(ast, options.search_in_filename || both, options.search_in_path || both);
+        highlighter
+    }
+
+    // Surligne ce que le motif glob/regex reconnaît dans le nom
+    pub fn from_pattern(pattern: FilenamePattern) -> Self {
+        Self {
+            pattern: Some(pattern),
+            ..Default::default()
+        }
+    }
+
+    fn collect(&mut self, node: &QueryNode, in_filename: bool, in_path: bool) {
+        match node {
+            QueryNode::Term(text) | QueryNode::Phrase(text) => {
+                if in_filename {
+                    self.filename_needles.push(text.clone());
+                }
+                if in_path {
+                    self.path_needles.push(text.clone());
+                }
+            }
+            QueryNode::Field(FieldFilter::Name(text)) => self.filename_needles.push(text.clone()),
+            QueryNode::Field(FieldFilter::Path(text)) => self.path_needles.push(text.clone()),
+            QueryNode::Field(_) | QueryNode::Not(_) => {}
+            QueryNode::And(children) | QueryNode::Or(children) => {
+                for child in children {
+                    self.collect(child, in_filename, in_path);
+                }
+            }
+        }
+    }
+
+    pub fn filename_ranges(&self, filename: &str) -> Vec<Range<usize>> {
+        match self.pattern {
+            Some(ref pattern) => pattern.match_ranges(filename),
+            None => match_ranges(filename, &self.filename_needles, self.fuzzy_distance),
+        }
+    }
+
+    pub fn path_ranges(&self, path: &str) -> Vec<Range<usize>> {
+        match_ranges(path, &self.path_needles, self.fuzzy_distance)
+    }
+}
+
+// Plages d'octets de text correspondant aux mots donnés (sans tenir compte de la casse)
+// fuzzy_distance: si défini, un mot absent tel quel est aligné de façon approchée
+pub fn match_ranges(text: &str, needles: &[String], fuzzy_distance: Option<u8>) -> Vec<Range<usize>> {
+    // Caractères en minuscules avec leur position en octets dans text
+    let chars: Vec<(usize, char)> = text.char_indices().map(|(i, c)| (i, lowercase_char(c))).collect();
+    let byte_range = |start: usize, end: usize| {
+        let start_byte = chars[start].0;
+        let end_byte = chars.get(end).map(|(i, _)| *i).unwrap_or(text.len());
+        start_byte..end_byte
+    };
+
+    let mut ranges = Vec::new();
+    for needle in needles {
+        let needle: Vec<char> = needle.chars().map(lowercase_char).collect();
+        if needle.is_empty() || chars.is_empty() {
+            continue;
+        }
+
+        let exact = find_all(&chars, &needle);
+        if !exact.is_empty() {
+            ranges.extend(exact.into_iter().map(|(start, end)| byte_range(start, end)));
+        } else if let Some(distance) = fuzzy_distance {
+            if let Some((start, end)) = find_approximate(&chars, &needle, distance as usize) {
+                ranges.push(byte_range(start, end));
+            }
+        }
+    }
+
+    merge_ranges(ranges)
+}
+
+// Minuscule sur un seul caractère: les positions restent alignées sur le texte d'origine
+fn lowercase_char(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+// Toutes les occurrences exactes (plages en caractères)
+fn find_all(chars: &[(usize, char)], needle: &[char]) -> Vec<(usize, usize)> {
+    if needle.len() > chars.len() {
+        return Vec::new();
+    }
+    (0..=chars.len() - needle.len())
+        .filter(|&start| {
+            needle
+                .iter()
+                .enumerate()
+                .all(|(offset, c)| chars[start + offset].1 == *c)
+        })
+        .map(|start| (start, start + needle.len()))
+        .collect()
+}
+
+// Meilleure sous-chaîne à distance de Levenshtein <= max_distance (Sellers)
+// Retourne la plus à gauche parmi les distances minimales (plage en caractères)
+fn find_approximate(chars: &[(usize, char)], needle: &[char], max_distance: usize) -> Option<(usize, usize)> {
+    // Colonne courante: (distance, début de la sous-chaîne) pour chaque préfixe du mot
+    let mut column: Vec<(usize, usize)> = (0..=needle.len()).map(|i| (i, 0)).collect();
+    let mut best: Option<(usize, usize, usize)> = None; // (distance, début, fin)
+
+    for (j, (_, c)) in chars.iter().enumerate() {
+        // La sous-chaîne peut commencer n'importe où: distance 0 avant le caractère j
+        let mut previous = (0, j);
+        column[0] = (0, j + 1);
+        for i in 1..=needle.len() {
+            let substitution = (previous.0 + usize::from(needle[i - 1] != *c), previous.1);
+            let deletion = (column[i].0 + 1, column[i].1);
+            let insertion = (column[i - 1].0 + 1, column[i - 1].1);
+            previous = column[i];
+            column[i] = [substitution, deletion, insertion]
+                .into_iter()
+                .min_by_key(|(distance, _)| *distance)
+                .unwrap_or(substitution);
+        }
+
+        let (distance, start) = column[needle.len()];
+        if distance <= max_distance && start <= j && best.is_none_or(|(d, _, _)| distance < d) {
+            best = Some((distance, start, j + 1));
+        }
+    }
+
+    best.map(|(_, start, end)| (start, end))
+}
+
+// Trie et fusionne les plages qui se chevauchent ou se touchent
+fn merge_ranges(mut ranges: Vec<Range<usize>>) -> Vec<Range<usize>> {
+    ranges.sort_by_key(|r| (r.start, r.end));
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+// Tests TDD
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::query::parse_query;
+    use crate::search::SearchIndex;
+    use tempfile::TempDir;
+
+    fn needles(words: &[&str]) -> Vec<String> {
+        words.iter().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn test_exact_ranges_case_insensitive_and_merged() {
+        let ranges = match_ranges("CHANGELOG_log.md", &needles(&["log", "g_l"]), None);
+        // "LOG" (6..9) + "G_L" (8..11) fusionnés, puis "log" (10..13) aussi
+        assert_eq!(ranges, vec![6..13]);
+
+        // Plages en octets même avec des caractères multi-octets
+        let text = "Étude_été.pdf";
+        let ranges = match_ranges(text, &needles(&["été"]), None);
+        assert_eq!(&text[ranges[0].clone()], "été");
+        assert_eq!(match_ranges(text, &needles(&["ÉTUDE"]), None), vec![0..6]);
+    }
+
+    #[test]
+    fn test_fuzzy_alignment() {
+        // "documnt" (faute de frappe) aligné sur "document"
+        let ranges = match_ranges("my_document.txt", &needles(&["documnt"]), Some(1));
+        assert_eq!(ranges, vec![3..11]);
+
+        // Trop éloigné: rien à surligner
+        assert!(match_ranges("my_document.txt", &needles(&["dcmnt"]), Some(1)).is_empty());
+        // Sans mode fuzzy: seules les occurrences exactes comptent
+        assert!(match_ranges("my_document.txt", &needles(&["documnt"]), None).is_empty());
+    }
+
+    #[test]
+    fn test_highlighter_from_query() {
+        let ast = parse_query("rapport -brouillon name:2024 path:projets ext:pdf").unwrap();
+        let highlighter = Highlighter::from_query(&ast, &SearchOptions::default());
+
+        assert_eq!(highlighter.filename_ranges("rapport_brouillon_2024.pdf"), vec![0..7, 18..22]);
+        assert_eq!(highlighter.path_ranges("/projets/rapport.pdf"), vec![1..8, 9..16]);
+    }
+
+    #[test]
+    fn test_search_results_carry_highlights() {
+        let dir = TempDir::new().unwrap();
+        let index = SearchIndex::new(dir.path(), 2, 20).unwrap();
+        let mut writer = index.create_writer().unwrap();
+        index.add_file(&mut writer, "C:\\test\\notes.txt", "notes.txt").unwrap();
+        writer.commit().unwrap();
+
+        // Fragments surlignés: l'extension dans le nom et dans le chemin
+        let results = index.search("txt", 10, SearchOptions::default()).unwrap();
+        assert_eq!(results[0].filename_matches, vec![6..9]);
+        assert_eq!(results[0].path_matches, vec![14..17]);
+    }
+}
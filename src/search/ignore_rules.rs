@@ -0,0 +1,425 @@
+// src/search/ignore_rules.rs
+// Fichiers .gitignore / .ignore / .xfinderignore (sémantique gitignore)
+//
+// Chaque dossier peut contenir des fichiers de règles; ils s'appliquent au
+// dossier et à tout son contenu:
+// - une règle par ligne, # commentaire, ! ré-inclut (négation)
+// - "/" final: seulement les dossiers ("build/")
+// - "/" au début ou au milieu: ancrée au dossier du fichier ("/todo.txt",
+//   "doc/*.pdf"); sinon le nom est cherché à tous les niveaux ("*.log")
+// - * et ? ne traversent pas "/", ** traverse les dossiers ("**/cache", "a/**/b")
+//
+// Priorité: la dernière règle qui correspond gagne dans un fichier, un fichier
+// d'un sous-dossier l'emporte sur ceux des dossiers parents, et .xfinderignore
+// l'emporte sur .ignore, lui-même sur .gitignore. Comme git, un dossier ignoré
+// n'est pas parcouru: son contenu ne peut pas être ré-inclus.
+//
+// .gitignore (fichier du gestionnaire de versions) peut être désactivé par
+// racine indexée; .ignore et .xfinderignore sont toujours lus.
+
+use regex::{RegexSet, RegexSetBuilder};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+// Fichier de règles du gestionnaire de versions (désactivable par racine)
+pub const VCS_IGNORE_FILE: &str = ".gitignore";
+
+// Fichiers de règles, par priorité croissante
+pub const IGNORE_FILES: [&str; 3] = [VCS_IGNORE_FILE, ".ignore", ".xfinderignore"];
+
+// Le nom est-il un fichier de règles ?
+pub fn is_ignore_file(filename: &str) -> bool {
+    IGNORE_FILES.contains(&filename)
+}
+
+// Fichiers de règles lus pour une racine
+pub fn ignore_files(respect_vcs_ignore: bool) -> Vec<&'static str> {
+    IGNORE_FILES
+        .iter()
+        .copied()
+        .filter(|name| respect_vcs_ignore || *name != VCS_IGNORE_FILE)
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Rule {
+    negated: bool,
+    dir_only: bool,
+}
+
+// Règles des fichiers ignore d'un dossier
+#[derive(Debug)]
+pub struct IgnoreRules {
+    dir: PathBuf,
+    rules: Vec<Rule>,
+    // Une regex par règle, même indice que rules (chemin relatif à dir, séparé par "/")
+    set: RegexSet,
+}
+
+impl IgnoreRules {
+    // Compile le contenu (concaténé) des fichiers de règles de dir
+    // Les lignes invalides sont ignorées, comme le fait git.
+    pub fn parse(dir: &Path, contents: &str) -> Option<Self> {
+        let mut rules = Vec::new();
+        let mut patterns = Vec::new();
+        for line in contents.lines() {
+            if let Some((rule, pattern)) = parse_line(line) {
+                // Valider seule: une règle invalide ne doit pas invalider le fichier
+                if regex::Regex::new(&pattern).is_ok() {
+                    rules.push(rule);
+                    patterns.push(pattern);
+                }
+            }
+        }
+        if rules.is_empty() {
+            return None;
+        }
+
+        let set = RegexSetBuilder::new(&patterns)
+            // Systèmes de fichiers insensibles à la casse sous Windows
+            .case_insensitive(cfg!(windows))
+            .build()
+            .ok()?;
+        Some(Self {
+            dir: dir.to_path_buf(),
+            rules,
+            set,
+        })
+    }
+
+    // Lit les fichiers de règles présents dans dir (None: aucune règle)
+    pub fn load(dir: &Path, files: &[&str]) -> Option<Self> {
+        let contents: Vec<String> = files
+            .iter()
+            .filter_map(|name| std::fs::read_to_string(dir.join(name)).ok())
+            .collect();
+        if contents.is_empty() {
+            return None;
+        }
+        Self::parse(dir, &contents.join("\n"))
+    }
+
+    // Some(true): ignoré, Some(false): ré-inclus par une négation, None: aucune règle
+    pub fn matched(&self, path: &Path, is_dir: bool) -> Option<bool> {
+        let relative = relative_path(&self.dir, path)?;
+        let matches = self.set.matches(&relative);
+        matches
+            .iter()
+            .rev()
+            .map(|idx| self.rules[idx])
+            .find(|rule| is_dir || !rule.dir_only)
+            .map(|rule| !rule.negated)
+    }
+}
+
+// Règles applicables à un dossier: celles de ses parents jusqu'à la racine
+// Partagée entre dossiers frères (clone peu coûteux).
+#[derive(Debug, Clone, Default)]
+pub struct IgnoreStack {
+    levels: Arc<Vec<Arc<IgnoreRules>>>,
+}
+
+impl IgnoreStack {
+    // Pile d'un sous-dossier, avec ses propres règles si il en a
+    pub fn child(&self, rules: Option<Arc<IgnoreRules>>) -> Self {
+        match rules {
+            Some(rules) => {
+                let mut levels = self.levels.as_ref().clone();
+                levels.push(rules);
+                Self { levels: Arc::new(levels) }
+            }
+            None => self.clone(),
+        }
+    }
+
+    // Le dossier le plus profond décide; sans règle, le chemin n'est pas ignoré
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        self.levels
+            .iter()
+            .rev()
+            .find_map(|rules| rules.matched(path, is_dir))
+            .unwrap_or(false)
+    }
+}
+
+// Règles des racines surveillées, lues à la demande et gardées en cache
+// (pour le watcher, qui reçoit des chemins isolés)
+#[derive(Debug, Default)]
+pub struct IgnoreTree {
+    roots: Vec<(PathBuf, bool)>, // (racine, respect_vcs_ignore)
+    cache: Mutex<HashMap<PathBuf, Option<Arc<IgnoreRules>>>>,
+}
+
+impl IgnoreTree {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_root(&mut self, root: &Path, respect_vcs_ignore: bool) {
+        self.remove_root(root);
+        self.roots.push((root.to_path_buf(), respect_vcs_ignore));
+    }
+
+    pub fn remove_root(&mut self, root: &Path) {
+        self.roots.retain(|(path, _)| path != root);
+        self.clear_cache();
+    }
+
+    // Oublie les règles en cache du dossier contenant ce fichier de règles
+    pub fn invalidate(&self, ignore_file: &Path) {
+        if let (Some(dir), Ok(mut cache)) = (ignore_file.parent(), self.cache.lock()) {
+            cache.remove(dir);
+        }
+    }
+
+    pub fn clear_cache(&self) {
+        if let Ok(mut cache) = self.cache.lock() {
+            cache.clear();
+        }
+    }
+
+    // Le chemin (ou un de ses dossiers parents sous la racine) est-il ignoré ?
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        // Racine la plus précise contenant le chemin
+        let Some((root, respect_vcs_ignore)) = self
+            .roots
+            .iter()
+            .filter(|(root, _)| path.starts_with(root))
+            .max_by_key(|(root, _)| root.components().count())
+        else {
+            return false;
+        };
+        let files = ignore_files(*respect_vcs_ignore);
+        let Ok(relative) = path.strip_prefix(root) else {
+            return false;
+        };
+
+        // Descend de la racine vers le chemin, comme le scanner
+        let mut stack = IgnoreStack::default();
+        let mut dir = root.clone();
+        let components: Vec<_> = relative.components().collect();
+        for (idx, component) in components.iter().enumerate() {
+            stack = self.with_rules(&stack, &dir, &files);
+            dir.push(component);
+            let last = idx + 1 == components.len();
+            if stack.is_ignored(&dir, if last { is_dir } else { true }) {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn with_rules(&self, stack: &IgnoreStack, dir: &Path, files: &[&str]) -> IgnoreStack {
+        let Ok(mut cache) = self.cache.lock() else {
+            return stack.clone();
+        };
+        let rules = cache
+            .entry(dir.to_path_buf())
+            .or_insert_with(|| IgnoreRules::load(dir, files).map(Arc::new))
+            .clone();
+        stack.child(rules)
+    }
+}
+
+// Chemin relatif à dir, séparé par "/" (None si hors de dir)
+fn relative_path(dir: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(dir).ok()?;
+    let parts: Vec<String> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+// Ligne de fichier ignore -> (règle, regex sur le chemin relatif)
+fn parse_line(line: &str) -> Option<(Rule, String)> {
+    let line = trim_trailing_spaces(line.trim_end_matches('\r'));
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+
+    let (negated, line) = match line.strip_prefix('!') {
+        Some(rest) => (true, rest),
+        None => (false, line),
+    };
+    let (dir_only, line) = match line.strip_suffix('/') {
+        Some(rest) => (true, rest),
+        None => (false, line),
+    };
+    // "/" restant au début ou au milieu: règle ancrée
+    let anchored = line.contains('/');
+    let line = line.strip_prefix('/').unwrap_or(line);
+    if line.is_empty() {
+        return None;
+    }
+
+    let body = path_glob_to_regex(line);
+    let pattern = if anchored || body.starts_with("(?:.*/)?") {
+        format!("^{}$", body)
+    } else {
+        format!("(?:^|/){}$", body)
+    };
+    Some((Rule { negated, dir_only }, pattern))
+}
+
+// Espaces finaux ignorés, sauf échappés ("a\ ")
+fn trim_trailing_spaces(line: &str) -> &str {
+    let mut end = line.len();
+    while end > 0 && line.as_bytes()[end - 1] == b' ' {
+        if end >= 2 && line.as_bytes()[end - 2] == b'\\' {
+            break;
+        }
+        end -= 1;
+    }
+    &line[..end]
+}
+
+// Glob sur un chemin séparé par "/" -> regex (non ancrée)
+// Partagé avec les motifs d'exclusion (exclusions.rs)
+pub fn path_glob_to_regex(glob: &str) -> String {
+    let chars: Vec<char> = glob.chars().collect();
+    let mut regex = String::with_capacity(glob.len() * 2);
+    let mut i = 0;
+
+    while i < chars.len() {
+        let at_segment_start = i == 0 || chars[i - 1] == '/';
+        match chars[i] {
+            // ** comme segment entier: traverse les dossiers
+            '*' if at_segment_start && chars.get(i + 1) == Some(&'*') && matches!(chars.get(i + 2), None | Some('/')) => {
+                if i + 2 >= chars.len() {
+                    regex.push_str(".*"); // "a/**": tout le contenu
+                } else {
+                    regex.push_str("(?:.*/)?"); // "**/a", "a/**/b": zéro ou plusieurs dossiers
+                    i += 1; // "/" consommé
+                }
+                i += 1;
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            '\\' => {
+                if let Some(&next) = chars.get(i + 1) {
+                    regex.push_str(&regex::escape(&next.to_string()));
+                    i += 1;
+                }
+            }
+            '[' => match chars[i + 1..].iter().position(|&c| c == ']') {
+                Some(len) if len > 0 => {
+                    regex.push('[');
+                    let class = &chars[i + 1..i + 1 + len];
+                    let (negate, class) = match class.first() {
+                        Some('!') | Some('^') => (true, &class[1..]),
+                        _ => (false, class),
+                    };
+                    if negate {
+                        regex.push('^');
+                    }
+                    for &c in class {
+                        if matches!(c, '\\' | '[' | ']' | '^' | '&' | '~') {
+                            regex.push('\\');
+                        }
+                        regex.push(c);
+                    }
+                    regex.push(']');
+                    i += len + 1;
+                }
+                // Crochet non fermé: littéral
+                _ => regex.push_str("\\["),
+            },
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+        i += 1;
+    }
+    regex
+}
+
+// Tests TDD
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(contents: &str) -> IgnoreRules {
+        IgnoreRules::parse(Path::new("/repo"), contents).unwrap()
+    }
+
+    fn ignored(rules: &IgnoreRules, path: &str, is_dir: bool) -> Option<bool> {
+        rules.matched(&Path::new("/repo").join(path), is_dir)
+    }
+
+    #[test]
+    fn test_gitignore_patterns() {
+        let r = rules("# commentaire\n\n*.log\n!keep.log\nbuild/\n/todo.txt\ndoc/*.pdf\n**/cache\nlogs/**\na/**/z\n");
+
+        assert_eq!(ignored(&r, "debug.log", false), Some(true));
+        assert_eq!(ignored(&r, "src/deep/trace.log", false), Some(true));
+        // Négation: la dernière règle gagne
+        assert_eq!(ignored(&r, "src/keep.log", false), Some(false));
+        // build/: dossiers seulement
+        assert_eq!(ignored(&r, "src/build", true), Some(true));
+        assert_eq!(ignored(&r, "src/build", false), None);
+        // Ancrée au dossier du fichier de règles
+        assert_eq!(ignored(&r, "todo.txt", false), Some(true));
+        assert_eq!(ignored(&r, "src/todo.txt", false), None);
+        assert_eq!(ignored(&r, "doc/manual.pdf", false), Some(true));
+        assert_eq!(ignored(&r, "doc/v2/manual.pdf", false), None);
+        // **
+        assert_eq!(ignored(&r, "x/y/cache", true), Some(true));
+        assert_eq!(ignored(&r, "cache", true), Some(true));
+        assert_eq!(ignored(&r, "logs/2024/jan.txt", false), Some(true));
+        assert_eq!(ignored(&r, "a/z", true), Some(true));
+        assert_eq!(ignored(&r, "a/b/c/z", true), Some(true));
+        // * ne traverse pas "/", pas de correspondance partielle de nom
+        assert_eq!(ignored(&r, "my.logs", false), None);
+        assert_eq!(ignored(&r, "readme.md", false), None);
+    }
+
+    #[test]
+    fn test_ignore_line_syntax() {
+        let r = rules("\\#notes\n\\!important\nspace\\ \n[!a]bc\nfile[0-9].txt   \n");
+        assert_eq!(ignored(&r, "#notes", false), Some(true));
+        assert_eq!(ignored(&r, "!important", false), Some(true));
+        assert_eq!(ignored(&r, "space ", false), Some(true));
+        assert_eq!(ignored(&r, "xbc", false), Some(true));
+        assert_eq!(ignored(&r, "abc", false), None);
+        assert_eq!(ignored(&r, "file7.txt", false), Some(true));
+        assert!(IgnoreRules::parse(Path::new("/repo"), "# rien\n\n").is_none());
+    }
+
+    #[test]
+    fn test_ignore_stack_hierarchy() {
+        let root = IgnoreStack::default().child(Some(Arc::new(rules("*.tmp\nsecret/\n"))));
+        // Le sous-dossier ré-inclut ses .tmp
+        let sub = root.child(IgnoreRules::parse(Path::new("/repo/keep"), "!*.tmp\n").map(Arc::new));
+
+        assert!(root.is_ignored(Path::new("/repo/a.tmp"), false));
+        assert!(sub.is_ignored(Path::new("/repo/keep/secret"), true));
+        assert!(!sub.is_ignored(Path::new("/repo/keep/b.tmp"), false));
+        assert!(!root.is_ignored(Path::new("/repo/a.txt"), false));
+    }
+
+    #[test]
+    fn test_ignore_tree_reads_files() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join("app/dist")).unwrap();
+        std::fs::write(root.join(".gitignore"), "dist/\n").unwrap();
+        std::fs::write(root.join("app/.xfinderignore"), "*.bak\n").unwrap();
+
+        let mut tree = IgnoreTree::new();
+        tree.add_root(root, true);
+        assert!(tree.is_ignored(&root.join("app/dist/bundle.js"), false));
+        assert!(tree.is_ignored(&root.join("app/old.bak"), false));
+        assert!(!tree.is_ignored(&root.join("app/main.rs"), false));
+        assert!(!tree.is_ignored(Path::new("/elsewhere/dist/x"), false));
+
+        // .gitignore désactivé pour la racine
+        tree.add_root(root, false);
+        assert!(!tree.is_ignored(&root.join("app/dist/bundle.js"), false));
+        assert!(tree.is_ignored(&root.join("app/old.bak"), false));
+
+        // Fichier de règles modifié: le cache est invalidé
+        std::fs::write(root.join("app/.xfinderignore"), "*.old\n").unwrap();
+        tree.invalidate(&root.join("app/.xfinderignore"));
+        assert!(!tree.is_ignored(&root.join("app/old.bak"), false));
+    }
+}
@@ -0,0 +1,249 @@
+// src/search/incremental.rs
+// Synchronisation incrémentale de l'index avec le disque
+//
+// Un rafraîchissement ne réindexe que ce qui a changé depuis le dernier scan.
+// Les fichiers déjà indexés (table files: chemin, taille, date de
+// modification) sont chargés dans KnownFiles et partagés avec les workers
+// du scan, qui classent chaque fichier trouvé:
+// - Added: chemin inconnu -> ajouté (et hashé)
+// - Modified: taille ou date différente -> remplacé (et re-hashé)
+// - Unchanged: rien à faire, ni hash ni écriture
+// Les fichiers connus que le scan n'a pas vus ont disparu (ou sont désormais
+// exclus): leurs documents et leurs lignes sont supprimés.
+//
+// sync_root() fait la même chose pour un seul dossier, en arrière-plan: le
+// watchdog s'en sert quand des événements ont été perdus.
+
+use super::{FileEntry, FileScanner, ScanConfig, SearchIndex};
+use crate::database::queries::{file_id, FileRecord};
+use crate::database::Database;
+use anyhow::Result;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+// Changement d'un fichier trouvé par rapport au dernier scan
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FileChange {
+    #[default]
+    Added,
+    Modified,
+    Unchanged,
+}
+
+// État d'un fichier au dernier scan
+#[derive(Debug)]
+struct KnownFile {
+    size: u64,
+    modified: i64,
+    seen: AtomicBool, // Retrouvé par le scan en cours
+}
+
+// Fichiers déjà indexés, par chemin
+#[derive(Debug, Default)]
+pub struct KnownFiles {
+    files: HashMap<String, KnownFile>,
+}
+
+impl KnownFiles {
+    // (chemin, taille, date de modification) de chaque fichier indexé
+    pub fn new(states: impl IntoIterator<Item = (String, u64, i64)>) -> Self {
+        let files = states
+            .into_iter()
+            .map(|(path, size, modified)| (path, KnownFile { size, modified, seen: AtomicBool::new(false) }))
+            .collect();
+        Self { files }
+    }
+
+    // Compare un fichier trouvé à son état connu (et le marque comme vu)
+    // Date inconnue: considéré modifié
+    pub fn classify(&self, entry: &FileEntry) -> FileChange {
+        match self.files.get(&entry.path) {
+            None => FileChange::Added,
+            Some(known) => {
+                known.seen.store(true, Ordering::Relaxed);
+                if known.size == entry.size && Some(known.modified) == entry.modified {
+                    FileChange::Unchanged
+                } else {
+                    FileChange::Modified
+                }
+            }
+        }
+    }
+
+    // Fichiers connus sous root que le scan n'a pas retrouvés
+    pub fn vanished_under(&self, root: &Path) -> Vec<String> {
+        self.files
+            .iter()
+            .filter(|(path, known)| !known.seen.load(Ordering::Relaxed) && Path::new(path.as_str()).starts_with(root))
+            .map(|(path, _)| path.clone())
+            .collect()
+    }
+}
+
+// Bilan d'une synchronisation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncSummary {
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+    pub unchanged: usize,
+}
+
+impl SyncSummary {
+    pub fn record(&mut self, change: FileChange) {
+        match change {
+            FileChange::Added => self.added += 1,
+            FileChange::Modified => self.updated += 1,
+            FileChange::Unchanged => self.unchanged += 1,
+        }
+    }
+}
+
+impl std::fmt::Display for SyncSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} ajoutés, {} modifiés, {} supprimés, {} inchangés",
+            self.added, self.updated, self.removed, self.unchanged
+        )
+    }
+}
+
+// Réconcilie l'index et la table files avec le disque sous root
+// (config: règles de parcours du dossier; known et hash sont fournis ici)
+pub fn sync_root(index: &SearchIndex, database: &Database, root: &Path, config: ScanConfig) -> Result<SyncSummary> {
+    let known = Arc::new(KnownFiles::new(database.get_file_states_under(&root.to_string_lossy())?));
+    let writer = index.writer()?;
+    let _bulk = writer.bulk()?;
+    let mut summary = SyncSummary::default();
+    let mut db_batch = Vec::new();
+
+    let mut scan = FileScanner::new().scan(root, ScanConfig {
+        compute_hash: true,
+        known: Some(known.clone()),
+        track_dirs: false,
+        done_dirs: None,
+        ..config
+    });
+    for scanned in scan.by_ref() {
+        summary.record(scanned.change);
+        if scanned.change == FileChange::Unchanged {
+            continue;
+        }
+        let file = scanned.entry;
+        let now = chrono::Utc::now().timestamp();
+        db_batch.push(FileRecord {
+            id: file_id(&file.path),
+            path: file.path.clone(),
+            filename: file.filename.clone(),
+            extension: Path::new(&file.path).extension().and_then(|s| s.to_str()).map(|s| format!(".{}", s)),
+            size: file.size,
+            modified: file.modified.unwrap_or(now),
+            created: file.created.unwrap_or(now),
+            hash: scanned.hash,
+            indexed_at: now,
+        });
+        writer.update(file)?;
+    }
+    database.batch_upsert_files(&db_batch)?;
+
+    // Un scan incomplet laisse des fichiers existants non vus (limite de
+    // fichiers, dossier illisible ou partage démonté)
+    let report = scan.finish();
+    if !report.file_limit_hit {
+        let mut vanished = known.vanished_under(root);
+        vanished.retain(|path| !report.is_unreadable(Path::new(path)));
+        for path in &vanished {
+            writer.delete(path)?;
+        }
+        database.batch_delete_files(&vanished)?;
+        summary.removed = vanished.len();
+    }
+    writer.flush()?;
+    Ok(summary)
+}
+
+// Tests TDD
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(path: &str, size: u64, modified: i64) -> FileEntry {
+        FileEntry {
+            path: path.to_string(),
+            filename: path.rsplit('/').next().unwrap().to_string(),
+            size,
+            modified: Some(modified),
+            created: None,
+        }
+    }
+
+    #[test]
+    fn test_classify_and_vanished() {
+        let known = KnownFiles::new(vec![
+            ("/data/a.txt".to_string(), 10, 100),
+            ("/data/b.txt".to_string(), 10, 100),
+            ("/data/sub/c.txt".to_string(), 10, 100),
+            ("/other/d.txt".to_string(), 10, 100),
+        ]);
+
+        let mut summary = SyncSummary::default();
+        for found in [entry("/data/a.txt", 10, 100), entry("/data/b.txt", 12, 100), entry("/data/new.txt", 1, 1)] {
+            summary.record(known.classify(&found));
+        }
+        assert_eq!(summary, SyncSummary { added: 1, updated: 1, removed: 0, unchanged: 1 });
+
+        // Seuls les fichiers de la racine scannée disparaissent
+        assert_eq!(known.vanished_under(Path::new("/data")), vec!["/data/sub/c.txt".to_string()]);
+        // "/dat" n'est pas un parent de "/data/..."
+        assert!(known.vanished_under(Path::new("/dat")).is_empty());
+        assert_eq!(known.vanished_under(Path::new("/other")).len(), 1);
+    }
+
+    #[test]
+    fn test_sync_root() {
+        use std::fs;
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path().join("root");
+        let other = temp_dir.path().join("other");
+        fs::create_dir_all(root.join("sub")).unwrap();
+        fs::create_dir_all(&other).unwrap();
+        for path in [root.join("keep.txt"), root.join("edit.txt"), root.join("sub").join("gone.txt"), other.join("x.txt")] {
+            fs::write(path, "v1").unwrap();
+        }
+        let index = SearchIndex::new(&temp_dir.path().join("index"), 2, 20).unwrap();
+        let database = Database::in_memory().unwrap();
+
+        // Premier passage: tout est ajouté
+        let first = sync_root(&index, &database, &root, ScanConfig::default()).unwrap();
+        assert_eq!(first, SyncSummary { added: 3, ..Default::default() });
+        sync_root(&index, &database, &other, ScanConfig::default()).unwrap();
+
+        // Le disque a changé sans que le watchdog le voie
+        fs::write(root.join("edit.txt"), "v2 plus long").unwrap();
+        fs::remove_file(root.join("sub").join("gone.txt")).unwrap();
+        fs::write(root.join("sub").join("new.txt"), "v1").unwrap();
+
+        let summary = sync_root(&index, &database, &root, ScanConfig::default()).unwrap();
+        assert_eq!(summary, SyncSummary { added: 1, updated: 1, removed: 1, unchanged: 1 });
+        assert_eq!(database.count_files().unwrap(), 4);
+        let gone = root.join("sub").join("gone.txt").to_string_lossy().to_string();
+        assert!(database.get_file_by_path(&gone).unwrap().is_none());
+        let mut indexed = index.paths_under(&root.to_string_lossy()).unwrap();
+        indexed.sort();
+        assert_eq!(indexed.len(), 3);
+        assert!(!indexed.contains(&gone));
+        // Les autres dossiers ne sont pas touchés
+        assert_eq!(index.paths_under(&other.to_string_lossy()).unwrap().len(), 1);
+
+        // Dossier illisible (ici absent, comme un partage démonté): rien n'est supprimé
+        let away = temp_dir.path().join("away");
+        fs::rename(&root, &away).unwrap();
+        let summary = sync_root(&index, &database, &root, ScanConfig::default()).unwrap();
+        assert_eq!(summary.removed, 0);
+        assert_eq!(database.count_files().unwrap(), 4);
+        assert_eq!(index.paths_under(&root.to_string_lossy()).unwrap().len(), 3);
+    }
+}
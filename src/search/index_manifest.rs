@@ -0,0 +1,177 @@
+// src/search/index_manifest.rs
+// Manifeste de l'index Tantivy: version du schéma et paramètres du tokenizer
+//
+// Le manifeste est écrit à la création de l'index (xfinder_index.toml).
+// À l'ouverture, il est comparé aux paramètres attendus pour détecter:
+// - un schéma obsolète (ancienne version de xfinder, ou index sans manifeste)
+// - des paramètres n-gram modifiés depuis l'indexation
+// Dans les deux cas l'index doit être reconstruit (voir SearchIndex::rebuild).
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+// Version du schéma Tantivy (à incrémenter à chaque changement de champs)
+// - 1: path + filename (index sans manifeste)
+// - 2: path_key, extension, size, modified, created, parent, name_key
+// - 3: filename_words (vocabulaire des suggestions)
+// - 4: filename_words découpé en mots (camelCase, chiffres, sans accents)
+// - 5: name_raw (modes glob / regex)
+// - 6: dirs (facette des dossiers: portée et répartition)
+// - 7: name_sort (nom complet) remplace name_key (8 premiers octets)
+pub const INDEX_SCHEMA_VERSION: u32 = 7;
+
+// Nom du tokenizer n-gram enregistré dans l'index
+pub const NGRAM_TOKENIZER: &str = "ngram3";
+
+// Fichier du manifeste, à côté de meta.json
+pub const MANIFEST_FILE: &str = "xfinder_index.toml";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexManifest {
+    pub schema_version: u32,
+    pub tokenizer: String,
+    pub min_ngram_size: usize,
+    pub max_ngram_size: usize,
+}
+
+// Résultat de la comparaison entre l'index sur disque et les paramètres attendus
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IndexCompatibility {
+    // Aucun index dans le dossier
+    Missing,
+    // Index conforme, utilisable tel quel
+    UpToDate,
+    // Schéma à jour mais tokenizer/n-grams différents: l'index reste
+    // interrogeable avec ses propres paramètres en attendant la reconstruction
+    SettingsChanged { current: IndexManifest },
+    // Schéma incompatible (found = None si aucun manifeste): reconstruction obligatoire
+    SchemaOutdated { found: Option<u32> },
+}
+
+impl IndexManifest {
+    // Manifeste correspondant au schéma actuel et aux n-grams donnés
+    pub fn new(min_ngram_size: usize, max_ngram_size: usize) -> Self {
+        Self {
+            schema_version: INDEX_SCHEMA_VERSION,
+            tokenizer: NGRAM_TOKENIZER.to_string(),
+            min_ngram_size,
+            max_ngram_size,
+        }
+    }
+
+    // Charge le manifeste d'un index (None si absent)
+    pub fn load(index_dir: &Path) -> Result<Option<Self>> {
+        let path = index_dir.join(MANIFEST_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Impossible de lire le manifeste: {:?}", path))?;
+        let manifest = toml::from_str(&content)
+            .with_context(|| format!("Manifeste d'index invalide: {:?}", path))?;
+        Ok(Some(manifest))
+    }
+
+    // Sauvegarde le manifeste (écriture atomique via fichier temporaire)
+    pub fn save(&self, index_dir: &Path) -> Result<()> {
+        let content = toml::to_string_pretty(self)
+            .context("Impossible de sérialiser le manifeste")?;
+
+        let tmp_path = index_dir.join(format!("{}.tmp", MANIFEST_FILE));
+        std::fs::write(&tmp_path, content)
+            .with_context(|| format!("Impossible d'écrire le manifeste: {:?}", tmp_path))?;
+        std::fs::rename(&tmp_path, index_dir.join(MANIFEST_FILE))
+            .context("Impossible de finaliser le manifeste")?;
+        Ok(())
+    }
+
+    // Compare l'index présent dans index_dir avec le manifeste attendu
+    pub fn check(index_dir: &Path, expected: &IndexManifest) -> Result<IndexCompatibility> {
+        if !index_dir.join("meta.json").exists() {
+            return Ok(IndexCompatibility::Missing);
+        }
+
+        let current = match Self::load(index_dir)? {
+            Some(manifest) => manifest,
+            None => return Ok(IndexCompatibility::SchemaOutdated { found: None }),
+        };
+
+        if current.schema_version != expected.schema_version {
+            return Ok(IndexCompatibility::SchemaOutdated {
+                found: Some(current.schema_version),
+            });
+        }
+
+        if current != *expected {
+            return Ok(IndexCompatibility::SettingsChanged { current });
+        }
+
+        Ok(IndexCompatibility::UpToDate)
+    }
+
+    // Description lisible des différences (pour l'UI)
+    pub fn describe_changes(&self, expected: &IndexManifest) -> String {
+        let mut changes = Vec::new();
+        if self.schema_version != expected.schema_version {
+            changes.push(format!("schéma v{} → v{}", self.schema_version, expected.schema_version));
+        }
+        if self.tokenizer != expected.tokenizer {
+            changes.push(format!("tokenizer {} → {}", self.tokenizer, expected.tokenizer));
+        }
+        if self.min_ngram_size != expected.min_ngram_size || self.max_ngram_size != expected.max_ngram_size {
+            changes.push(format!(
+                "n-grams {}-{} → {}-{}",
+                self.min_ngram_size, self.max_ngram_size,
+                expected.min_ngram_size, expected.max_ngram_size
+            ));
+        }
+        changes.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_manifest_roundtrip() {
+        let dir = TempDir::new().unwrap();
+        let manifest = IndexManifest::new(2, 20);
+        manifest.save(dir.path()).unwrap();
+
+        let loaded = IndexManifest::load(dir.path()).unwrap().unwrap();
+        assert_eq!(loaded, manifest);
+    }
+
+    #[test]
+    fn test_check_compatibility() {
+        let dir = TempDir::new().unwrap();
+        let expected = IndexManifest::new(2, 20);
+
+        // Pas d'index
+        assert_eq!(IndexManifest::check(dir.path(), &expected).unwrap(), IndexCompatibility::Missing);
+
+        // Index sans manifeste (ancienne version)
+        std::fs::write(dir.path().join("meta.json"), "{}").unwrap();
+        assert_eq!(
+            IndexManifest::check(dir.path(), &expected).unwrap(),
+            IndexCompatibility::SchemaOutdated { found: None }
+        );
+
+        // N-grams différents
+        let current = IndexManifest::new(3, 10);
+        current.save(dir.path()).unwrap();
+        assert_eq!(
+            IndexManifest::check(dir.path(), &expected).unwrap(),
+            IndexCompatibility::SettingsChanged { current: current.clone() }
+        );
+        assert_eq!(current.describe_changes(&expected), "n-grams 3-10 → 2-20");
+
+        // Conforme
+        expected.save(dir.path()).unwrap();
+        assert_eq!(IndexManifest::check(dir.path(), &expected).unwrap(), IndexCompatibility::UpToDate);
+    }
+}
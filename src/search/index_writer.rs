@@ -0,0 +1,467 @@
+// src/search/index_writer.rs
+// Writer Tantivy unique, possédé par un thread dédié (acteur)
+//
+// Tantivy n'autorise qu'un seul IndexWriter par index (verrou sur disque).
+// Plutôt que de créer un writer (buffer de 200MB + commit) à chaque
+// événement, un thread garde le writer ouvert et reçoit les opérations
+// (ajout, suppression, renommage) par un channel. Les opérations sont
+// regroupées et validées (commit) dès que:
+// - COMMIT_BATCH_SIZE opérations sont en attente, ou
+// - la plus ancienne opération attend depuis COMMIT_INTERVAL (sauf pendant
+//   une session bulk(): indexation complète, seuls le lot et flush() valident,
+//   pour ne pas produire un petit segment par seconde)
+//
+// L'indexation complète (XFinderApp::start_indexing) et le watchdog
+// (FileWatcher) partagent ce writer via SearchIndex::writer(), sans se
+// disputer le verrou. flush() valide immédiatement et attend le commit.
+//
+// DirectWriter: writer direct (SearchIndex::create_writer) pour construire
+// un index qui n'est pas encore en service (reconstruction, tests).
+
+use anyhow::{Context, Result};
+use crossbeam_channel::{bounded, unbounded, Receiver, RecvTimeoutError, Sender};
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use tantivy::{IndexReader, IndexWriter};
+
+use super::{FileEntry, SearchIndex};
+
+// Nombre d'opérations en attente déclenchant un commit
+pub const COMMIT_BATCH_SIZE: usize = 10_000;
+
+// Délai max avant qu'une opération en attente soit validée (trafic du
+// watchdog; pas de délai pendant une session bulk())
+pub const COMMIT_INTERVAL: Duration = Duration::from_secs(1);
+
+// Commandes reçues par le thread du writer
+enum WriterCommand {
+    // Ajout simple (index neuf, pas de doublon possible)
+    Add(FileEntry),
+    // Remplace le document du même chemin (ou l'ajoute)
+    Update(FileEntry),
+    // Supprime le document du chemin donné
+    Delete(String),
+    // Supprime l'ancien chemin et ajoute le nouveau
+    Rename { from: String, entry: FileEntry },
+    // Supprime tous les documents sous un dossier
+    DeleteDir(String),
+    // Valide les opérations en attente et répond une fois le commit fait
+    Flush(Sender<Result<()>>),
+    // Début / fin d'une session d'écriture en masse (voir bulk())
+    BeginBulk,
+    EndBulk,
+    // Valide puis termine le thread
+    Stop,
+}
+
+// Partagé entre toutes les copies du handle: le thread s'arrête (commit
+// final inclus) quand la dernière copie est libérée
+struct WriterShared {
+    tx: Sender<WriterCommand>,
+    thread: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl WriterShared {
+    // Demande l'arrêt du thread et attend sa fin (verrou de l'index libéré)
+    fn stop(&self) {
+        let _ = self.tx.send(WriterCommand::Stop);
+        if let Ok(mut thread) = self.thread.lock() {
+            if let Some(handle) = thread.take() {
+                let _ = handle.join();
+            }
+        }
+    }
+}
+
+impl Drop for WriterShared {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+// Handle clonable vers le writer de l'index
+#[derive(Clone)]
+pub struct IndexWriterHandle {
+    shared: Arc<WriterShared>,
+}
+
+impl IndexWriterHandle {
+    // Démarre le thread du writer
+    // index: copie de SearchIndex sans writer (construction des documents)
+    pub fn start(index: SearchIndex, writer: IndexWriter, batch_size: usize, commit_interval: Duration) -> Self {
+        let (tx, rx) = unbounded::<WriterCommand>();
+
+        let handle = thread::spawn(move || {
+            Self::run_writer_loop(rx, index, writer, batch_size.max(1), commit_interval);
+        });
+
+        Self {
+            shared: Arc::new(WriterShared {
+                tx,
+                thread: Mutex::new(Some(handle)),
+            }),
+        }
+    }
+
+    // Boucle principale: applique les commandes et valide par lots
+    fn run_writer_loop(
+        rx: Receiver<WriterCommand>,
+        index: SearchIndex,
+        mut writer: IndexWriter,
+        batch_size: usize,
+        commit_interval: Duration,
+    ) {
+        let mut pending = 0usize;
+        let mut oldest_pending: Option<Instant> = None;
+        // Sessions bulk() ouvertes: pas de commit au délai
+        let mut bulk_sessions = 0usize;
+        // Première erreur d'une opération ou d'un commit automatique, remontée
+        // au prochain flush() (les documents concernés n'ont pas été écrits)
+        let mut write_error: Option<anyhow::Error> = None;
+
+        loop {
+            // Sans opération en attente (ou en session bulk), attendre indéfiniment
+            let command = match oldest_pending {
+                Some(since) if bulk_sessions == 0 => rx.recv_timeout(commit_interval.saturating_sub(since.elapsed())),
+                _ => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+            };
+
+            match command {
+                Ok(WriterCommand::Flush(ack)) => {
+                    let result = Self::commit(&index, &mut writer);
+                    let result = match write_error.take() {
+                        Some(e) => Err(e),
+                        None => result,
+                    };
+                    pending = 0;
+                    oldest_pending = None;
+                    let _ = ack.send(result);
+                    continue;
+                }
+                Ok(WriterCommand::BeginBulk) => {
+                    bulk_sessions += 1;
+                    continue;
+                }
+                Ok(WriterCommand::EndBulk) => {
+                    // Les opérations en attente reprennent le délai normal
+                    bulk_sessions = bulk_sessions.saturating_sub(1);
+                    continue;
+                }
+                Ok(WriterCommand::Stop) | Err(RecvTimeoutError::Disconnected) => {
+                    if pending > 0 {
+                        if let Err(e) = Self::commit(&index, &mut writer) {
+                            eprintln!("Erreur commit final de l'index: {}", e);
+                        }
+                    }
+                    if let Some(e) = write_error {
+                        eprintln!("Erreur écriture index: {}", e);
+                    }
+                    break;
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    // Délai écoulé: valider le lot en attente
+                    if let Err(e) = Self::commit(&index, &mut writer) {
+                        write_error.get_or_insert(e);
+                    }
+                    pending = 0;
+                    oldest_pending = None;
+                    continue;
+                }
+                Ok(command) => {
+                    if let Err(e) = Self::apply(&index, &mut writer, command) {
+                        write_error.get_or_insert(e);
+                        continue;
+                    }
+                }
+            }
+
+            pending += 1;
+            oldest_pending.get_or_insert_with(Instant::now);
+
+            if pending >= batch_size {
+                if let Err(e) = Self::commit(&index, &mut writer) {
+                    write_error.get_or_insert(e);
+                }
+                pending = 0;
+                oldest_pending = None;
+            }
+        }
+
+        // Attendre la fin des merges pour libérer proprement le verrou
+        let _ = writer.wait_merging_threads();
+    }
+
+    // Applique une opération au writer (sans commit)
+    fn apply(index: &SearchIndex, writer: &mut IndexWriter, command: WriterCommand) -> Result<()> {
+        match command {
+            WriterCommand::Add(entry) => index.add_entry(writer, &entry)?,
+            WriterCommand::Update(entry) => {
+                writer.delete_term(index.path_term(&entry.path));
+                index.add_entry(writer, &entry)?;
+            }
+            WriterCommand::Delete(path) => {
+                writer.delete_term(index.path_term(&path));
+            }
+            WriterCommand::Rename { from, entry } => {
+                // Renommé par-dessus un fichier indexé: pas de doublon
+                writer.delete_term(index.path_term(&from));
+                writer.delete_term(index.path_term(&entry.path));
+                index.add_entry(writer, &entry)?;
+            }
+            WriterCommand::DeleteDir(dir) => {
+                if let Some(term) = index.dir_term(&dir) {
+                    writer.delete_term(term);
+                }
+            }
+            WriterCommand::Flush(_) | WriterCommand::BeginBulk | WriterCommand::EndBulk | WriterCommand::Stop => {}
+        }
+        Ok(())
+    }
+
+    // Valide puis recharge le reader partagé: les recherches voient le
+    // commit sans attendre la détection de meta.json
+    fn commit(index: &SearchIndex, writer: &mut IndexWriter) -> Result<()> {
+        writer.commit().context("Impossible de valider les modifications de l'index")?;
+        index.reload()
+    }
+
+    fn send(&self, command: WriterCommand) -> Result<()> {
+        self.shared
+            .tx
+            .send(command)
+            .map_err(|_| anyhow::anyhow!("Le writer de l'index est arrêté"))
+    }
+
+    // Ajoute un fichier (index neuf: pas de vérification de doublon)
+    pub fn add(&self, entry: FileEntry) -> Result<()> {
+        self.send(WriterCommand::Add(entry))
+    }
+
+    // Ajoute ou remplace le document du même chemin
+    pub fn update(&self, entry: FileEntry) -> Result<()> {
+        self.send(WriterCommand::Update(entry))
+    }
+
+    // Supprime un fichier par son chemin
+    pub fn delete(&self, path: &str) -> Result<()> {
+        self.send(WriterCommand::Delete(path.to_string()))
+    }
+
+    // Remplace le chemin from par le fichier entry (déplacement/renommage)
+    pub fn rename(&self, from: &str, entry: FileEntry) -> Result<()> {
+        self.send(WriterCommand::Rename {
+            from: from.to_string(),
+            entry,
+        })
+    }
+
+    // Supprime les documents de tous les fichiers sous dir
+    pub fn delete_dir(&self, dir: &str) -> Result<()> {
+        self.send(WriterCommand::DeleteDir(dir.to_string()))
+    }
+
+    // Ouvre une session d'écriture en masse (indexation complète): tant
+    // que la session vit, les opérations ne sont validées que par lots de
+    // COMMIT_BATCH_SIZE ou par flush(), jamais au délai COMMIT_INTERVAL
+    pub fn bulk(&self) -> Result<BulkSession> {
+        self.send(WriterCommand::BeginBulk)?;
+        Ok(BulkSession { writer: self.clone() })
+    }
+
+    // Valide immédiatement les opérations en attente et attend le commit
+    // Retourne aussi l'erreur d'une opération ou d'un commit automatique
+    // précédents
+    pub fn flush(&self) -> Result<()> {
+        let (ack_tx, ack_rx) = bounded(1);
+        self.send(WriterCommand::Flush(ack_tx))?;
+        ack_rx
+            .recv()
+            .map_err(|_| anyhow::anyhow!("Le writer de l'index s'est arrêté pendant le commit"))?
+    }
+
+    // Arrête le thread (commit final inclus) et attend sa fin, même si
+    // d'autres copies du handle existent: leurs opérations échoueront
+    // ensuite au lieu d'écrire dans un index fermé
+    pub fn stop(&self) {
+        self.shared.stop();
+    }
+}
+
+// Session d'écriture en masse (voir IndexWriterHandle::bulk), fermée à
+// sa libération
+pub struct BulkSession {
+    writer: IndexWriterHandle,
+}
+
+impl Drop for BulkSession {
+    fn drop(&mut self) {
+        let _ = self.writer.send(WriterCommand::EndBulk);
+    }
+}
+
+// Writer Tantivy direct dont le commit() recharge aussi le reader partagé
+// de l'index: les recherches voient le commit dès son retour
+pub struct DirectWriter {
+    writer: IndexWriter,
+    reader: IndexReader,
+}
+
+impl DirectWriter {
+    pub(crate) fn new(writer: IndexWriter, reader: IndexReader) -> Self {
+        Self { writer, reader }
+    }
+
+    // Valide les documents ajoutés et les rend visibles aux recherches
+    pub fn commit(&mut self) -> tantivy::Result<u64> {
+        let opstamp = self.writer.commit()?;
+        self.reader.reload()?;
+        Ok(opstamp)
+    }
+
+    // Writer Tantivy sous-jacent (pour le confier au thread du writer partagé)
+    pub fn into_inner(self) -> IndexWriter {
+        self.writer
+    }
+}
+
+impl Deref for DirectWriter {
+    type Target = IndexWriter;
+
+    fn deref(&self) -> &IndexWriter {
+        &self.writer
+    }
+}
+
+impl DerefMut for DirectWriter {
+    fn deref_mut(&mut self) -> &mut IndexWriter {
+        &mut self.writer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::SearchOptions;
+    use tempfile::TempDir;
+
+    fn entry(path: &str) -> FileEntry {
+        let filename = path.rsplit('/').next().unwrap_or(path);
+        FileEntry {
+            path: path.to_string(),
+            filename: filename.to_string(),
+            size: 10,
+            modified: Some(1_700_000_000),
+            created: None,
+        }
+    }
+
+    #[test]
+    fn test_writer_shared_and_flush() {
+        let dir = TempDir::new().unwrap();
+        let index = SearchIndex::new(dir.path(), 2, 20).unwrap();
+
+        // Deux handles (indexation + watchdog) sur le même writer
+        let indexer = index.writer().unwrap();
+        let watcher = index.writer().unwrap();
+
+        indexer.add(entry("/data/report.txt")).unwrap();
+        indexer.add(entry("/data/notes.md")).unwrap();
+        watcher.rename("/data/notes.md", entry("/data/todo.md")).unwrap();
+        watcher.update(entry("/data/report.txt")).unwrap();
+        watcher.delete("/data/absent.txt").unwrap();
+        index.flush().unwrap();
+
+        assert_eq!(index.count_documents().unwrap(), 2);
+        assert_eq!(index.search("todo", 10, SearchOptions::default()).unwrap().len(), 1);
+        assert!(index.search("notes", 10, SearchOptions::default()).unwrap().is_empty());
+
+        // Le verrou est libéré quand la dernière copie disparaît
+        drop(indexer);
+        drop(watcher);
+        drop(index);
+        let index = SearchIndex::new(dir.path(), 2, 20).unwrap();
+        assert!(index.create_writer().is_ok());
+    }
+
+    #[test]
+    fn test_writer_commits_on_thresholds() {
+        let dir = TempDir::new().unwrap();
+        let index = SearchIndex::new(dir.path(), 2, 20).unwrap();
+
+        // Seuil de taille: commit dès 2 opérations
+        let writer = IndexWriterHandle::start(
+            index.detached(),
+            index.create_writer().unwrap().into_inner(),
+            2,
+            Duration::from_secs(3600),
+        );
+        writer.add(entry("/data/a.txt")).unwrap();
+        writer.add(entry("/data/b.txt")).unwrap();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while index.count_documents().unwrap() < 2 && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(20));
+        }
+        assert_eq!(index.count_documents().unwrap(), 2);
+        drop(writer);
+
+        // Seuil de temps: commit sans flush ni lot complet
+        let writer = IndexWriterHandle::start(
+            index.detached(),
+            index.create_writer().unwrap().into_inner(),
+            COMMIT_BATCH_SIZE,
+            Duration::from_millis(50),
+        );
+        writer.delete("/data/a.txt").unwrap();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while index.count_documents().unwrap() > 1 && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(20));
+        }
+        assert_eq!(index.count_documents().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_bulk_session_skips_timed_commits() {
+        let dir = TempDir::new().unwrap();
+        let index = SearchIndex::new(dir.path(), 2, 20).unwrap();
+        let writer = IndexWriterHandle::start(
+            index.detached(),
+            index.create_writer().unwrap().into_inner(),
+            COMMIT_BATCH_SIZE,
+            Duration::from_millis(50),
+        );
+
+        // Indexation complète en cours: pas de commit au délai
+        let bulk = writer.bulk().unwrap();
+        writer.add(entry("/data/a.txt")).unwrap();
+        thread::sleep(Duration::from_millis(300));
+        assert_eq!(index.count_documents().unwrap(), 0);
+
+        // Fin de session: le délai normal s'applique de nouveau
+        drop(bulk);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while index.count_documents().unwrap() < 1 && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(20));
+        }
+        assert_eq!(index.count_documents().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_close_writer_stops_all_copies() {
+        let dir = TempDir::new().unwrap();
+        let index = SearchIndex::new(dir.path(), 2, 20).unwrap();
+
+        // Copie gardée par un autre thread (resynchronisation en cours)
+        let straggler = index.writer().unwrap();
+        straggler.add(entry("/data/report.txt")).unwrap();
+
+        // Les opérations en attente sont validées, puis le verrou est libéré
+        index.close_writer().unwrap();
+        assert_eq!(index.count_documents().unwrap(), 1);
+        assert!(index.create_writer().is_ok());
+
+        // Une écriture tardive échoue au lieu d'atterrir dans l'index fermé
+        assert!(straggler.add(entry("/data/late.txt")).is_err());
+    }
+}
@@ -0,0 +1,235 @@
+// src/search/mounts.rs
+// Points de montage du système (Linux: /proc/self/mounts)
+//
+// Sert au scanner à ne pas descendre dans les pseudo-systèmes de fichiers
+// (proc, sysfs, devtmpfs, tmpfs...): un scan de "/" parcourrait sinon des
+// milliers de fichiers virtuels, dont certains bloquent à la lecture.
+// Ailleurs que sous Linux la table est vide et rien n'est ignoré.
+//
+// Sert aussi au watchdog: sur un partage réseau, un FUSE ou un volume de VM,
+// les changements faits ailleurs ne produisent pas d'événement natif
+// (inotify...). Ces dossiers sont scrutés périodiquement (voir file_watcher.rs).
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+// Types de systèmes de fichiers sans vrais fichiers à indexer
+pub const PSEUDO_FILESYSTEMS: &[&str] = &[
+    "proc",
+    "sysfs",
+    "devtmpfs",
+    "tmpfs",
+    "devpts",
+    "cgroup",
+    "cgroup2",
+    "securityfs",
+    "debugfs",
+    "tracefs",
+    "pstore",
+    "bpf",
+    "mqueue",
+    "hugetlbfs",
+    "configfs",
+    "fusectl",
+    "binfmt_misc",
+    "efivarfs",
+    "autofs",
+];
+
+pub fn is_pseudo_filesystem(fs_type: &str) -> bool {
+    PSEUDO_FILESYSTEMS.contains(&fs_type)
+}
+
+// Types de systèmes de fichiers sans événements natifs fiables
+// (plus tous les "fuse.*": sshfs, rclone, gvfs...)
+pub const UNRELIABLE_EVENT_FILESYSTEMS: &[&str] = &[
+    "nfs",
+    "nfs4",
+    "cifs",
+    "smb3",
+    "smbfs",
+    "afs",
+    "ceph",
+    "glusterfs",
+    "9p",
+    "virtiofs",
+    "vboxsf",
+    "vmhgfs",
+    "fuse",
+];
+
+pub fn needs_polling(fs_type: &str) -> bool {
+    UNRELIABLE_EVENT_FILESYSTEMS.contains(&fs_type) || fs_type.starts_with("fuse.")
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MountPoint {
+    pub path: PathBuf,
+    pub fs_type: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MountTable {
+    mounts: Vec<MountPoint>,
+}
+
+impl MountTable {
+    // Table des montages courants (vide si illisible ou hors Linux)
+    pub fn load() -> Self {
+        if cfg!(target_os = "linux") {
+            std::fs::read_to_string("/proc/self/mounts")
+                .map(|contents| Self::parse(&contents))
+                .unwrap_or_default()
+        } else {
+            Self::default()
+        }
+    }
+
+    // Montages déclarés dans /etc/fstab (montés ou non)
+    pub fn load_fstab() -> Self {
+        if cfg!(target_os = "linux") {
+            std::fs::read_to_string("/etc/fstab")
+                .map(|contents| Self::parse(&contents))
+                .unwrap_or_default()
+        } else {
+            Self::default()
+        }
+    }
+
+    // Format de /proc/self/mounts et /etc/fstab: "source chemin type options 0 0"
+    // (les espaces du chemin sont écrits \040, les commentaires de fstab ignorés)
+    pub fn parse(contents: &str) -> Self {
+        let mounts = contents
+            .lines()
+            .filter(|line| !line.trim_start().starts_with('#'))
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                let _source = fields.next()?;
+                let path = fields.next()?;
+                let fs_type = fields.next()?;
+                Some(MountPoint {
+                    path: PathBuf::from(unescape(path)),
+                    fs_type: fs_type.to_string(),
+                })
+            })
+            .collect();
+        Self { mounts }
+    }
+
+    // Montage qui contient path (le plus profond; le dernier monté en cas de doublon)
+    pub fn mount_of(&self, path: &Path) -> Option<&MountPoint> {
+        self.mounts
+            .iter()
+            .filter(|mount| path.starts_with(&mount.path))
+            .max_by_key(|mount| mount.path.components().count())
+    }
+
+    // Type du système de fichiers de path ("ext4", "nfs", "proc"...)
+    pub fn fs_type(&self, path: &Path) -> Option<&str> {
+        self.mount_of(path).map(|mount| mount.fs_type.as_str())
+    }
+
+    // path est sous un montage déclaré (fstab) qui n'est pas monté: le dossier
+    // n'est qu'un point de montage vide (partage réseau absent au démarrage)
+    pub fn is_unmounted(&self, path: &Path, declared: &MountTable) -> bool {
+        if self.mounts.is_empty() {
+            return false; // Table courante inconnue: rien à comparer
+        }
+        match declared.mount_of(path) {
+            Some(expected) => !self.mounts.iter().any(|mount| mount.path == expected.path),
+            None => false,
+        }
+    }
+
+    // Points de montage des pseudo-systèmes de fichiers
+    pub fn pseudo_mount_points(&self) -> HashSet<PathBuf> {
+        self.mounts
+            .iter()
+            .filter(|mount| is_pseudo_filesystem(&mount.fs_type))
+            .map(|mount| mount.path.clone())
+            .collect()
+    }
+}
+
+// "\040" -> " " (octal, comme dans /proc/self/mounts)
+fn unescape(field: &str) -> String {
+    let bytes = field.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\\' && i + 3 < bytes.len() && bytes[i + 1..i + 4].iter().all(|b| (b'0'..=b'7').contains(b)) {
+            let code = (bytes[i + 1] - b'0') * 64 + (bytes[i + 2] - b'0') * 8 + (bytes[i + 3] - b'0');
+            out.push(code);
+            i += 4;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+// Tests TDD
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MOUNTS: &str = "\
+/dev/sda2 / ext4 rw,relatime 0 0
+proc /proc proc rw,nosuid,nodev,noexec,relatime 0 0
+sysfs /sys sysfs rw,nosuid,nodev,noexec,relatime 0 0
+udev /dev devtmpfs rw,nosuid,relatime 0 0
+tmpfs /run tmpfs rw,nosuid,nodev 0 0
+/dev/sdb1 /mnt/My\\040Disk ext4 rw,relatime 0 0
+server:/export /mnt/nas nfs4 rw,relatime 0 0
+me@host:/home /mnt/ssh fuse.sshfs rw,nosuid,nodev 0 0
+";
+
+    #[test]
+    fn test_parse_and_lookup() {
+        let table = MountTable::parse(MOUNTS);
+        assert_eq!(table.fs_type(Path::new("/dev/null")), Some("devtmpfs"));
+        assert_eq!(table.fs_type(Path::new("/mnt/ssh/b")), Some("fuse.sshfs"));
+
+        assert_eq!(table.fs_type(Path::new("/home/me/doc.txt")), Some("ext4"));
+        assert_eq!(table.fs_type(Path::new("/proc/1/status")), Some("proc"));
+        assert_eq!(table.fs_type(Path::new("/mnt/My Disk/photo.jpg")), Some("ext4"));
+        assert_eq!(table.fs_type(Path::new("/mnt/nas/share")), Some("nfs4"));
+        // "/runtime" n'est pas sous "/run"
+        assert_eq!(table.fs_type(Path::new("/runtime")), Some("ext4"));
+
+        let pseudo = table.pseudo_mount_points();
+        assert_eq!(pseudo.len(), 4);
+        assert!(pseudo.contains(Path::new("/sys")));
+        assert!(!pseudo.contains(Path::new("/mnt/nas")));
+
+        // Événements natifs peu fiables: partages réseau et FUSE
+        let polled: Vec<&str> = ["/home/me", "/mnt/nas/a", "/mnt/ssh/b", "/mnt/My Disk"]
+            .iter()
+            .filter(|path| table.fs_type(Path::new(path)).is_some_and(needs_polling))
+            .copied()
+            .collect();
+        assert_eq!(polled, vec!["/mnt/nas/a", "/mnt/ssh/b"]);
+        assert!(!needs_polling("fuseblk")); // ntfs-3g: disque local
+    }
+
+    #[test]
+    fn test_unmounted_share() {
+        let table = MountTable::parse(MOUNTS);
+        let fstab = MountTable::parse(
+            "# /etc/fstab\n\
+             UUID=1234 / ext4 defaults 0 1\n\
+             server:/export /mnt/nas nfs4 defaults 0 0\n\
+             //nas/photos /mnt/photos cifs noauto 0 0\n",
+        );
+        assert_eq!(fstab.fs_type(Path::new("/etc")), Some("ext4"));
+
+        // Déclaré et monté, ou pas déclaré du tout
+        assert!(!table.is_unmounted(Path::new("/mnt/nas/docs"), &fstab));
+        assert!(!table.is_unmounted(Path::new("/home/me"), &fstab));
+        // Déclaré mais pas monté: le dossier vide ne dit rien de son contenu
+        assert!(table.is_unmounted(Path::new("/mnt/photos/2024"), &fstab));
+        // Table courante illisible: on ne conclut rien
+        assert!(!MountTable::default().is_unmounted(Path::new("/mnt/photos"), &fstab));
+    }
+}
@@ -0,0 +1,260 @@
+// src/search/pattern.rs
+// Modes glob et regex sur le nom de fichier
+//
+// En mode glob ou regex, la saisie n'est pas analysée par le langage de
+// requête (query.rs): elle est compilée en motif sur le nom complet.
+// - glob: *.tar.gz, IMG_20??_*.jpg, rapport[0-9].{doc,docx} (nom entier)
+// - regex: syntaxe du crate regex, cherchée n'importe où dans le nom
+//   (^ et $ pour ancrer: ^report-\d{4}\.xlsx$)
+//
+// Le motif est exécuté par Tantivy (RegexQuery, automate sur le dictionnaire
+// du champ brut name_raw) quand l'automate le supporte. Sinon (\b, ancres
+// dans une alternative...), les noms du dictionnaire sont filtrés un par un
+// avec le crate regex: dans les deux cas tri et limite restent dans Tantivy.
+
+use regex::{Regex, RegexBuilder};
+use std::ops::Range;
+
+use super::query::QueryParseError;
+
+// Interprétation de la saisie
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PatternMode {
+    #[default]
+    Text,  // Langage de requête (mots, ext:, OR...)
+    Glob,  // Motif glob sur le nom complet
+    Regex, // Expression régulière sur le nom
+}
+
+impl PatternMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PatternMode::Text => "Texte",
+            PatternMode::Glob => "Glob",
+            PatternMode::Regex => "Regex",
+        }
+    }
+}
+
+// Motif compilé sur le nom de fichier
+#[derive(Debug, Clone)]
+pub struct FilenamePattern {
+    // Recherche dans le nom (glob: déjà ancrée sur le nom complet)
+    regex: Regex,
+    // Motif pour l'automate Tantivy (implicitement ancré), si transposable
+    automaton: Option<String>,
+}
+
+impl FilenamePattern {
+    // Compile la saisie selon le mode (Text n'est pas un motif: erreur)
+    pub fn compile(input: &str, mode: PatternMode, case_sensitive: bool) -> Result<Self, QueryParseError> {
+        let input = input.trim();
+        let (search, automaton_body) = match mode {
+            PatternMode::Glob => {
+                let body = glob_to_regex(input)?;
+                (format!("^(?:{})$", body), Some(body))
+            }
+            PatternMode::Regex => (input.to_string(), automaton_body(input)),
+            PatternMode::Text => {
+                return Err(pattern_error("le mode texte n'utilise pas de motif".to_string()));
+            }
+        };
+
+        let regex = RegexBuilder::new(&search)
+            .case_insensitive(!case_sensitive)
+            .build()
+            .map_err(|e| pattern_error(format!("motif {} invalide: {}", mode.label().to_lowercase(), e)))?;
+
+        let flags = if case_sensitive { "" } else { "(?i)" };
+        Ok(Self {
+            regex,
+            automaton: automaton_body.map(|body| format!("{}{}", flags, body)),
+        })
+    }
+
+    // Motif pour RegexQuery (None: filtrer le dictionnaire avec is_match)
+    pub fn automaton(&self) -> Option<&str> {
+        self.automaton.as_deref()
+    }
+
+    // Le nom correspond-il au motif ?
+    pub fn is_match(&self, filename: &str) -> bool {
+        self.regex.is_match(filename)
+    }
+
+    // Plages d'octets du nom correspondant au motif (surlignage)
+    pub fn match_ranges(&self, filename: &str) -> Vec<Range<usize>> {
+        self.regex
+            .find_iter(filename)
+            .filter(|m| !m.is_empty())
+            .map(|m| m.range())
+            .collect()
+    }
+}
+
+fn pattern_error(message: String) -> QueryParseError {
+    QueryParseError { message, position: 0 }
+}
+
+// Traduit un glob en regex (non ancrée), pour le nom complet
+// * -> .*, ? -> ., [abc] / [!abc] classes, {a,b} alternatives
+pub fn glob_to_regex(glob: &str) -> Result<String, QueryParseError> {
+    let chars: Vec<char> = glob.chars().collect();
+    let mut regex = String::with_capacity(glob.len() * 2);
+    let mut brace_start: Option<usize> = None;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            '[' => {
+                // Classe: "]" juste après l'ouverture (ou "!") est littéral
+                let mut j = i + 1;
+                if chars.get(j) == Some(&'!') {
+                    j += 1;
+                }
+                if chars.get(j) == Some(&']') {
+                    j += 1;
+                }
+                while j < chars.len() && chars[j] != ']' {
+                    j += 1;
+                }
+                if j >= chars.len() {
+                    return Err(QueryParseError {
+                        message: "crochet '[' non fermé dans le motif glob".to_string(),
+                        position: i,
+                    });
+                }
+                regex.push('[');
+                let mut k = i + 1;
+                if chars[k] == '!' {
+                    regex.push('^');
+                    k += 1;
+                }
+                for &class_char in &chars[k..j] {
+                    if matches!(class_char, '\\' | '[' | ']' | '^' | '&' | '~') {
+                        regex.push('\\');
+                    }
+                    regex.push(class_char);
+                }
+                regex.push(']');
+                i = j;
+            }
+            '{' if brace_start.is_none() => {
+                brace_start = Some(i);
+                regex.push_str("(?:");
+            }
+            ',' if brace_start.is_some() => regex.push('|'),
+            '}' if brace_start.is_some() => {
+                brace_start = None;
+                regex.push(')');
+            }
+            _ => regex.push_str(&regex::escape(&c.to_string())),
+        }
+        i += 1;
+    }
+
+    if let Some(position) = brace_start {
+        return Err(QueryParseError {
+            message: "accolade '{' non fermée dans le motif glob".to_string(),
+            position,
+        });
+    }
+    Ok(regex)
+}
+
+// Regex (recherche dans le nom) -> motif pour l'automate Tantivy, qui
+// compare le nom complet et ne supporte pas ^ et $: les ancres de début et
+// de fin sont retirées, sinon .* est ajouté. None si la transposition n'est
+// pas sûre (| au premier niveau avec une ancre).
+fn automaton_body(regex: &str) -> Option<String> {
+    let starts = regex.starts_with('^');
+    let ends = regex.ends_with('$') && !is_escaped(regex, regex.len() - 1);
+    if (starts || ends) && has_top_level_alternation(regex) {
+        return None;
+    }
+
+    let start = usize::from(starts);
+    let end = if ends { regex.len() - 1 } else { regex.len() };
+    let body = regex.get(start..end.max(start))?;
+    Some(format!(
+        "{}(?:{}){}",
+        if starts { "" } else { ".*" },
+        body,
+        if ends { "" } else { ".*" }
+    ))
+}
+
+// Le caractère à l'octet idx est-il précédé d'un nombre impair de '\' ?
+fn is_escaped(text: &str, idx: usize) -> bool {
+    text[..idx].bytes().rev().take_while(|&b| b == b'\\').count() % 2 == 1
+}
+
+// '|' hors parenthèses, classes et échappements
+fn has_top_level_alternation(regex: &str) -> bool {
+    let mut depth = 0i32;
+    let mut in_class = false;
+    let mut escaped = false;
+    for c in regex.chars() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '[' => in_class = true,
+            ']' => in_class = false,
+            _ if in_class => {}
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            '|' if depth == 0 => return true,
+            _ => {}
+        }
+    }
+    false
+}
+
+// Tests TDD
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_to_regex() {
+        let glob = |g: &str| FilenamePattern::compile(g, PatternMode::Glob, false).unwrap();
+
+        assert!(glob("*.tar.gz").is_match("backup.TAR.GZ"));
+        assert!(!glob("*.tar.gz").is_match("backup.tar.gz.old"));
+        assert!(glob("IMG_20??_*.jpg").is_match("IMG_2024_vacances.jpg"));
+        assert!(!glob("IMG_20??_*.jpg").is_match("IMG_202_x.jpg"));
+        assert!(glob("rapport[0-9].{doc,docx}").is_match("rapport7.docx"));
+        assert!(glob("[!.]*").is_match("notes.txt"));
+        assert!(!glob("[!.]*").is_match(".gitignore"));
+        // Les caractères spéciaux regex sont littéraux
+        assert!(glob("a+b (1).txt").is_match("a+b (1).txt"));
+
+        let err = FilenamePattern::compile("photo[12.jpg", PatternMode::Glob, false).unwrap_err();
+        assert_eq!(err.position, 5);
+        assert!(FilenamePattern::compile("{a,b", PatternMode::Glob, false).is_err());
+    }
+
+    #[test]
+    fn test_regex_pattern_and_automaton() {
+        let pattern = FilenamePattern::compile(r"^report-\d{4}\.xlsx$", PatternMode::Regex, false).unwrap();
+        assert!(pattern.is_match("Report-2024.xlsx"));
+        assert!(!pattern.is_match("old_report-2024.xlsx"));
+        assert_eq!(pattern.automaton(), Some(r"(?i)(?:report-\d{4}\.xlsx)"));
+
+        // Non ancrée: cherchée n'importe où dans le nom
+        let pattern = FilenamePattern::compile(r"v\d+", PatternMode::Regex, true).unwrap();
+        assert!(pattern.is_match("design_v12_final.psd"));
+        assert_eq!(pattern.match_ranges("design_v12_final.psd"), vec![7..10]);
+        assert_eq!(pattern.automaton(), Some(r".*(?:v\d+).*"));
+
+        // Ancre dans une alternative: pas d'automate, filtrage du dictionnaire
+        let pattern = FilenamePattern::compile(r"^a|b$", PatternMode::Regex, false).unwrap();
+        assert!(pattern.automaton().is_none());
+        assert!(pattern.is_match("xab"));
+
+        assert!(FilenamePattern::compile(r"report(\d", PatternMode::Regex, false).is_err());
+    }
+}
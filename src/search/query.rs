@@ -0,0 +1,637 @@
+// src/search/query.rs
+// Langage de requête structuré (opérateurs de champ + logique booléenne)
+//
+// Syntaxe supportée:
+// - mots libres:        report invoice        (ET implicite)
+// - phrase exacte:      "rapport annuel"
+// - alternative:        report OR invoice
+// - exclusion:          -draft  ou  NOT draft
+// - groupes:            (pdf OR docx) -old
+// - extension:          ext:pdf  ou  ext:pdf,docx
+// - taille:             size:>10MB  size:<=500ko  size:1MB..2GB
+// - date modification:  modified:<2025-01-01  modified:2024-06  modified:2024..2025
+// - chemin / nom:       path:projects  name:"mon fichier"
+//
+// La requête est parsée en AST typé (QueryNode), ensuite compilé en
+// requête Tantivy par SearchIndex: les filtres et la logique booléenne
+// sont donc appliqués AVANT la limite de résultats.
+
+use chrono::{Datelike, Local, NaiveDate, TimeZone};
+use std::fmt;
+use std::ops::Bound;
+
+// Nœud de l'AST d'une requête
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryNode {
+    Term(String),
+    Phrase(String),
+    Field(FieldFilter),
+    Not(Box<QueryNode>),
+    And(Vec<QueryNode>),
+    Or(Vec<QueryNode>),
+}
+
+// Filtre sur un champ précis (ext:, size:, modified:, path:, name:)
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldFilter {
+    Extension(Vec<String>),
+    Size { min: Bound<u64>, max: Bound<u64> },
+    Modified { min: Bound<i64>, max: Bound<i64> },
+    Path(String),
+    Name(String),
+}
+
+// Erreur de parsing avec position (en caractères) dans la requête
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryParseError {
+    pub message: String,
+    pub position: usize,
+}
+
+impl fmt::Display for QueryParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Requête invalide (position {}): {}", self.position + 1, self.message)
+    }
+}
+
+impl std::error::Error for QueryParseError {}
+
+impl QueryNode {
+    // Vrai si la requête ne contient aucun critère
+    pub fn is_empty(&self) -> bool {
+        match self {
+            QueryNode::And(children) | QueryNode::Or(children) => children.is_empty(),
+            _ => false,
+        }
+    }
+}
+
+// Parse une requête utilisateur en AST
+pub fn parse_query(input: &str) -> Result<QueryNode, QueryParseError> {
+    let tokens = tokenize(input)?;
+    let mut parser = Parser { tokens, pos: 0, input_len: input.chars().count() };
+    let node = parser.parse_sequence()?;
+
+    if let Some(token) = parser.peek() {
+        // Seul un ')' orphelin peut arrêter parse_sequence avant la fin
+        return Err(QueryParseError {
+            message: "parenthèse fermante sans ouverture".to_string(),
+            position: token.position,
+        });
+    }
+
+    Ok(node)
+}
+
+// ==================== Lexer ====================
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    Word(String),
+    Phrase(String),
+    Field { name: String, value: String },
+    Minus,
+    Not,
+    Or,
+    And,
+    LParen,
+    RParen,
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    position: usize,
+}
+
+// Champs reconnus avant ':' (les autres préfixes restent des mots, ex: "C:\Users")
+const KNOWN_FIELDS: &[&str] = &["ext", "extension", "size", "taille", "modified", "modifie", "path", "chemin", "name", "nom"];
+
+fn tokenize(input: &str) -> Result<Vec<Token>, QueryParseError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        match c {
+            '(' => {
+                tokens.push(Token { kind: TokenKind::LParen, position: i });
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token { kind: TokenKind::RParen, position: i });
+                i += 1;
+            }
+            '"' => {
+                let (phrase, next) = read_quoted(&chars, i)?;
+                tokens.push(Token { kind: TokenKind::Phrase(phrase), position: i });
+                i = next;
+            }
+            '-' if i + 1 < chars.len() && !chars[i + 1].is_whitespace() && (i == 0 || chars[i - 1].is_whitespace() || chars[i - 1] == '(') => {
+                tokens.push(Token { kind: TokenKind::Minus, position: i });
+                i += 1;
+            }
+            _ => {
+                let start = i;
+                let mut word = String::new();
+                while i < chars.len() && !chars[i].is_whitespace() && chars[i] != '(' && chars[i] != ')' && chars[i] != '"' {
+                    word.push(chars[i]);
+                    i += 1;
+                }
+
+                // Opérateur de champ: name:value (la valeur peut être entre guillemets)
+                if let Some(colon) = word.find(':') {
+                    let name = word[..colon].to_lowercase();
+                    if KNOWN_FIELDS.contains(&name.as_str()) {
+                        let mut value = word[colon + 1..].to_string();
+                        if value.is_empty() && i < chars.len() && chars[i] == '"' {
+                            let (quoted, next) = read_quoted(&chars, i)?;
+                            value = quoted;
+                            i = next;
+                        }
+                        if value.is_empty() {
+                            return Err(QueryParseError {
+                                message: format!("valeur manquante après '{}:'", name),
+                                position: start,
+                            });
+                        }
+                        tokens.push(Token { kind: TokenKind::Field { name, value }, position: start });
+                        continue;
+                    }
+                }
+
+                let kind = match word.as_str() {
+                    "OR" | "||" => TokenKind::Or,
+                    "AND" | "&&" => TokenKind::And,
+                    "NOT" => TokenKind::Not,
+                    _ => TokenKind::Word(word),
+                };
+                tokens.push(Token { kind, position: start });
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+// Lit une chaîne entre guillemets à partir de `start` (qui pointe sur '"')
+// Retourne le contenu et l'index après le guillemet fermant
+fn read_quoted(chars: &[char], start: usize) -> Result<(String, usize), QueryParseError> {
+    let mut i = start + 1;
+    let mut content = String::new();
+
+    while i < chars.len() {
+        if chars[i] == '"' {
+            return Ok((content, i + 1));
+        }
+        content.push(chars[i]);
+        i += 1;
+    }
+
+    Err(QueryParseError {
+        message: "guillemet non fermé".to_string(),
+        position: start,
+    })
+}
+
+// ==================== Parser ====================
+//
+// Grammaire (OR est prioritaire sur le ET implicite):
+//   sequence := or_expr*
+//   or_expr  := unary (OR unary)*
+//   unary    := ('-' | NOT) unary | primary
+//   primary  := '(' sequence ')' | PHRASE | FIELD | WORD
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    input_len: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn error_at_current(&self, message: &str) -> QueryParseError {
+        QueryParseError {
+            message: message.to_string(),
+            position: self.peek().map(|t| t.position).unwrap_or(self.input_len),
+        }
+    }
+
+    fn parse_sequence(&mut self) -> Result<QueryNode, QueryParseError> {
+        let mut clauses = Vec::new();
+
+        while let Some(token) = self.peek() {
+            match token.kind {
+                TokenKind::RParen => break,
+                TokenKind::And => {
+                    if clauses.is_empty() {
+                        return Err(self.error_at_current("AND sans terme à gauche"));
+                    }
+                    self.pos += 1;
+                    if matches!(self.peek().map(|t| &t.kind), None | Some(TokenKind::RParen)) {
+                        return Err(self.error_at_current("AND sans terme à droite"));
+                    }
+                }
+                TokenKind::Or => return Err(self.error_at_current("OR sans terme à gauche")),
+                _ => clauses.push(self.parse_or()?),
+            }
+        }
+
+        Ok(if clauses.len() == 1 {
+            clauses.pop().unwrap()
+        } else {
+            QueryNode::And(clauses)
+        })
+    }
+
+    fn parse_or(&mut self) -> Result<QueryNode, QueryParseError> {
+        let mut alternatives = vec![self.parse_unary()?];
+
+        while matches!(self.peek().map(|t| &t.kind), Some(TokenKind::Or)) {
+            self.pos += 1;
+            match self.peek().map(|t| &t.kind) {
+                None | Some(TokenKind::RParen) | Some(TokenKind::Or) | Some(TokenKind::And) => {
+                    return Err(self.error_at_current("OR sans terme à droite"));
+                }
+                _ => alternatives.push(self.parse_unary()?),
+            }
+        }
+
+        Ok(if alternatives.len() == 1 {
+            alternatives.pop().unwrap()
+        } else {
+            QueryNode::Or(alternatives)
+        })
+    }
+
+    fn parse_unary(&mut self) -> Result<QueryNode, QueryParseError> {
+        match self.peek().map(|t| &t.kind) {
+            Some(TokenKind::Minus) | Some(TokenKind::Not) => {
+                self.pos += 1;
+                match self.peek().map(|t| &t.kind) {
+                    None | Some(TokenKind::RParen) | Some(TokenKind::Or) | Some(TokenKind::And) => {
+                        Err(self.error_at_current("négation sans terme"))
+                    }
+                    _ => Ok(QueryNode::Not(Box::new(self.parse_unary()?))),
+                }
+            }
+            _ => self.parse_primary(),
+        }
+    }
+
+    fn parse_primary(&mut self) -> Result<QueryNode, QueryParseError> {
+        let token = match self.next() {
+            Some(token) => token,
+            None => return Err(self.error_at_current("terme attendu")),
+        };
+
+        match token.kind {
+            TokenKind::Word(word) => Ok(QueryNode::Term(word)),
+            TokenKind::Phrase(phrase) => {
+                if phrase.trim().is_empty() {
+                    return Err(QueryParseError {
+                        message: "phrase vide".to_string(),
+                        position: token.position,
+                    });
+                }
+                Ok(QueryNode::Phrase(phrase))
+            }
+            TokenKind::Field { name, value } => parse_field(&name, &value, token.position).map(QueryNode::Field),
+            TokenKind::LParen => {
+                let inner = self.parse_sequence()?;
+                match self.next() {
+                    Some(Token { kind: TokenKind::RParen, .. }) => {}
+                    _ => {
+                        return Err(QueryParseError {
+                            message: "parenthèse non fermée".to_string(),
+                            position: token.position,
+                        });
+                    }
+                }
+                if inner.is_empty() {
+                    return Err(QueryParseError {
+                        message: "groupe vide".to_string(),
+                        position: token.position,
+                    });
+                }
+                Ok(inner)
+            }
+            _ => Err(QueryParseError {
+                message: "terme attendu".to_string(),
+                position: token.position,
+            }),
+        }
+    }
+}
+
+// ==================== Valeurs des champs ====================
+
+fn parse_field(name: &str, value: &str, position: usize) -> Result<FieldFilter, QueryParseError> {
+    let err = |message: String| QueryParseError { message, position };
+
+    match name {
+        "ext" | "extension" => {
+            let extensions: Vec<String> = value
+                .split(',')
+                .map(|e| e.trim().trim_start_matches('.').to_lowercase())
+                .filter(|e| !e.is_empty())
+                .collect();
+            if extensions.is_empty() {
+                return Err(err(format!("extension invalide: '{}'", value)));
+            }
+            Ok(FieldFilter::Extension(extensions))
+        }
+        "size" | "taille" => {
+            let (min, max) = parse_range(value, parse_size, |v| v.checked_add(1))
+                .ok_or_else(|| err(format!("taille invalide: '{}' (ex: >10MB, <500ko, 1MB..2GB)", value)))?;
+            Ok(FieldFilter::Size { min, max })
+        }
+        "modified" | "modifie" => {
+            let (min, max) = parse_date_range(value)
+                .ok_or_else(|| err(format!("date invalide: '{}' (ex: <2025-01-01, 2024-06, 2023..2024)", value)))?;
+            Ok(FieldFilter::Modified { min, max })
+        }
+        "path" | "chemin" => Ok(FieldFilter::Path(value.to_string())),
+        "name" | "nom" => Ok(FieldFilter::Name(value.to_string())),
+        _ => Err(err(format!("champ inconnu: '{}'", name))),
+    }
+}
+
+// Parse une taille: "10MB", "1.5go", "500k", "42" (octets)
+// Unités binaires (1 KB = 1024 octets), comme l'affichage de l'UI
+fn parse_size(value: &str) -> Option<u64> {
+    let value = value.trim().to_lowercase();
+    let split = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f64 = number.parse().ok()?;
+
+    let multiplier: u64 = match unit.trim() {
+        "" | "b" | "o" => 1,
+        "k" | "kb" | "ko" | "kib" => 1024,
+        "m" | "mb" | "mo" | "mib" => 1024 * 1024,
+        "g" | "gb" | "go" | "gib" => 1024 * 1024 * 1024,
+        "t" | "tb" | "to" | "tib" => 1024 * 1024 * 1024 * 1024,
+        _ => return None,
+    };
+
+    if number < 0.0 || !number.is_finite() {
+        return None;
+    }
+    Some((number * multiplier as f64) as u64)
+}
+
+// Parse un intervalle générique: ">x", ">=x", "<x", "<=x", "x..y", "x"
+// `successor` donne la plus petite valeur strictement supérieure (pour "x" seul)
+fn parse_range<T: Copy>(
+    value: &str,
+    parse: impl Fn(&str) -> Option<T>,
+    successor: impl Fn(T) -> Option<T>,
+) -> Option<(Bound<T>, Bound<T>)> {
+    let value = value.trim();
+
+    if let Some(rest) = value.strip_prefix(">=") {
+        return Some((Bound::Included(parse(rest)?), Bound::Unbounded));
+    }
+    if let Some(rest) = value.strip_prefix("<=") {
+        return Some((Bound::Unbounded, Bound::Included(parse(rest)?)));
+    }
+    if let Some(rest) = value.strip_prefix('>') {
+        return Some((Bound::Excluded(parse(rest)?), Bound::Unbounded));
+    }
+    if let Some(rest) = value.strip_prefix('<') {
+        return Some((Bound::Unbounded, Bound::Excluded(parse(rest)?)));
+    }
+    if let Some((low, high)) = value.split_once("..") {
+        let min = if low.is_empty() { Bound::Unbounded } else { Bound::Included(parse(low)?) };
+        let max = if high.is_empty() { Bound::Unbounded } else { Bound::Included(parse(high)?) };
+        return Some((min, max));
+    }
+
+    let exact = parse(value)?;
+    Some((Bound::Included(exact), match successor(exact) {
+        Some(next) => Bound::Excluded(next),
+        None => Bound::Unbounded,
+    }))
+}
+
+// Parse une date partielle ("2025-01-01", "2025-01", "2025") en période [début, fin[
+// exprimée en timestamps Unix (heure locale)
+fn parse_period(value: &str) -> Option<(i64, i64)> {
+    let parts: Vec<&str> = value.trim().split('-').collect();
+    let (start, end) = match parts.as_slice() {
+        [year] => {
+            let year: i32 = year.parse().ok()?;
+            (NaiveDate::from_ymd_opt(year, 1, 1)?, NaiveDate::from_ymd_opt(year + 1, 1, 1)?)
+        }
+        [year, month] => {
+            let year: i32 = year.parse().ok()?;
+            let month: u32 = month.parse().ok()?;
+            let start = NaiveDate::from_ymd_opt(year, month, 1)?;
+            let end = if month == 12 {
+                NaiveDate::from_ymd_opt(year + 1, 1, 1)?
+            } else {
+                NaiveDate::from_ymd_opt(year, month + 1, 1)?
+            };
+            (start, end)
+        }
+        [year, month, day] => {
+            let start = NaiveDate::from_ymd_opt(year.parse().ok()?, month.parse().ok()?, day.parse().ok()?)?;
+            (start, start.succ_opt()?)
+        }
+        _ => return None,
+    };
+
+    Some((local_midnight_timestamp(start)?, local_midnight_timestamp(end)?))
+}
+
+fn local_midnight_timestamp(date: NaiveDate) -> Option<i64> {
+    Local
+        .with_ymd_and_hms(date.year(), date.month(), date.day(), 0, 0, 0)
+        .earliest()
+        .map(|dt| dt.timestamp())
+}
+
+// Intervalle de dates: une date désigne toute sa période (jour, mois ou année)
+// - "<d"  : avant le début de d      - ">d"  : après la fin de d
+// - "<=d" : jusqu'à la fin de d      - ">=d" : depuis le début de d
+// - "d1..d2" : du début de d1 à la fin de d2
+fn parse_date_range(value: &str) -> Option<(Bound<i64>, Bound<i64>)> {
+    let value = value.trim();
+
+    if let Some(rest) = value.strip_prefix(">=") {
+        return Some((Bound::Included(parse_period(rest)?.0), Bound::Unbounded));
+    }
+    if let Some(rest) = value.strip_prefix("<=") {
+        return Some((Bound::Unbounded, Bound::Excluded(parse_period(rest)?.1)));
+    }
+    if let Some(rest) = value.strip_prefix('>') {
+        return Some((Bound::Included(parse_period(rest)?.1), Bound::Unbounded));
+    }
+    if let Some(rest) = value.strip_prefix('<') {
+        return Some((Bound::Unbounded, Bound::Excluded(parse_period(rest)?.0)));
+    }
+    if let Some((low, high)) = value.split_once("..") {
+        let min = if low.is_empty() { Bound::Unbounded } else { Bound::Included(parse_period(low)?.0) };
+        let max = if high.is_empty() { Bound::Unbounded } else { Bound::Excluded(parse_period(high)?.1) };
+        return Some((min, max));
+    }
+
+    let (start, end) = parse_period(value)?;
+    Some((Bound::Included(start), Bound::Excluded(end)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn term(s: &str) -> QueryNode {
+        QueryNode::Term(s.to_string())
+    }
+
+    #[test]
+    fn test_implicit_and() {
+        let ast = parse_query("report invoice").unwrap();
+        assert_eq!(ast, QueryNode::And(vec![term("report"), term("invoice")]));
+    }
+
+    #[test]
+    fn test_or_binds_tighter_than_and() {
+        let ast = parse_query("annual report OR invoice").unwrap();
+        assert_eq!(
+            ast,
+            QueryNode::And(vec![
+                term("annual"),
+                QueryNode::Or(vec![term("report"), term("invoice")]),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_negation_and_phrase() {
+        let ast = parse_query("-draft \"exact phrase\"").unwrap();
+        assert_eq!(
+            ast,
+            QueryNode::And(vec![
+                QueryNode::Not(Box::new(term("draft"))),
+                QueryNode::Phrase("exact phrase".to_string()),
+            ])
+        );
+
+        // Un tiret au milieu d'un mot n'est pas une négation
+        assert_eq!(parse_query("my-file").unwrap(), term("my-file"));
+    }
+
+    #[test]
+    fn test_field_operators() {
+        let ast = parse_query("ext:pdf,.DOCX path:projects name:\"mon fichier\"").unwrap();
+        assert_eq!(
+            ast,
+            QueryNode::And(vec![
+                QueryNode::Field(FieldFilter::Extension(vec!["pdf".to_string(), "docx".to_string()])),
+                QueryNode::Field(FieldFilter::Path("projects".to_string())),
+                QueryNode::Field(FieldFilter::Name("mon fichier".to_string())),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_size_filter() {
+        let ast = parse_query("size:>10MB").unwrap();
+        assert_eq!(
+            ast,
+            QueryNode::Field(FieldFilter::Size {
+                min: Bound::Excluded(10 * 1024 * 1024),
+                max: Bound::Unbounded,
+            })
+        );
+
+        let ast = parse_query("size:1k..2ko").unwrap();
+        assert_eq!(
+            ast,
+            QueryNode::Field(FieldFilter::Size {
+                min: Bound::Included(1024),
+                max: Bound::Included(2048),
+            })
+        );
+    }
+
+    #[test]
+    fn test_modified_filter() {
+        let start_2025 = parse_period("2025-01-01").unwrap().0;
+        let ast = parse_query("modified:<2025-01-01").unwrap();
+        assert_eq!(
+            ast,
+            QueryNode::Field(FieldFilter::Modified {
+                min: Bound::Unbounded,
+                max: Bound::Excluded(start_2025),
+            })
+        );
+
+        // Une année entière
+        let (start, end) = parse_period("2024").unwrap();
+        assert_eq!(end - start, 366 * 24 * 3600); // 2024 est bissextile (hors changement d'heure)
+        let ast = parse_query("modified:2024").unwrap();
+        assert!(matches!(ast, QueryNode::Field(FieldFilter::Modified { .. })));
+    }
+
+    #[test]
+    fn test_groups() {
+        let ast = parse_query("(pdf OR docx) -old").unwrap();
+        assert_eq!(
+            ast,
+            QueryNode::And(vec![
+                QueryNode::Or(vec![term("pdf"), term("docx")]),
+                QueryNode::Not(Box::new(term("old"))),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_unknown_field_is_a_word() {
+        // Les chemins Windows ne doivent pas être pris pour des champs
+        assert_eq!(parse_query("C:\\Users").unwrap(), term("C:\\Users"));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = parse_query("\"unterminated").unwrap_err();
+        assert_eq!(err.position, 0);
+        assert!(err.message.contains("guillemet"));
+
+        assert!(parse_query("report OR").is_err());
+        assert!(parse_query("OR report").is_err());
+        assert!(parse_query("(report").is_err());
+        assert!(parse_query("report)").is_err());
+        assert!(parse_query("size:>abc").is_err());
+        assert!(parse_query("modified:2025-13-45").is_err());
+        assert!(parse_query("ext:").is_err());
+        assert!(parse_query("()").is_err());
+
+        // Message lisible pour l'UI
+        let err = parse_query("size:huge").unwrap_err();
+        assert!(err.to_string().contains("taille invalide"));
+    }
+
+    #[test]
+    fn test_empty_query() {
+        assert!(parse_query("   ").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,173 @@
+// src/search/reconcile.rs
+// Réconciliation au démarrage: changements faits pendant que xfinder était fermé
+//
+// Le watchdog ne voit que les événements en direct. Au démarrage, chaque
+// dossier surveillé est parcouru (scanner parallèle, mêmes règles que
+// l'indexation) et comparé à la table files (taille, date de modification,
+// voir incremental::KnownFiles). Seules les différences sont mises en file
+// d'attente du watchdog, sous forme d'événements: créé, modifié, supprimé.
+// La date d'un dossier ne suffit pas à le sauter: modifier un fichier sur
+// place ne la change pas. Chaque fichier est donc comparé (un stat, sans
+// lecture ni hash tant qu'il n'a pas changé).
+//
+// watched_folders.last_scan (date du dernier parcours complet) sert à
+// repérer les fichiers "douteux": modifiés dans la même seconde que le début
+// de ce parcours, ils ont pu changer encore après sans que la date
+// enregistrée (à la seconde) bouge. Ils sont revérifiés (le watchdog compare
+// le hash). Les fichiers modifiés plus tard ont une autre date que celle
+// enregistrée: ils sont déjà vus comme modifiés, sans revérification.
+
+use super::file_watcher::FileEvent;
+use super::incremental::{FileChange, KnownFiles};
+use super::{FileScanner, ScanConfig};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+// Fréquence des rappels de progression (en fichiers vérifiés)
+const PROGRESS_INTERVAL: usize = 500;
+
+// Différences d'un dossier surveillé avec la table files
+#[derive(Debug, Default)]
+pub struct Reconciliation {
+    pub events: Vec<FileEvent>, // À appliquer par le watchdog
+    pub checked: usize,         // Fichiers trouvés sur le disque
+    pub file_count: u64,        // Pour watched_folders
+    pub total_size: u64,
+    pub complete: bool,         // Tout le dossier a été vu (suppressions fiables)
+}
+
+// Compare root à son état connu (known: fichiers de la table files sous root)
+// on_progress reçoit régulièrement le nombre de fichiers vérifiés
+pub fn reconcile_root(
+    root: &Path,
+    known: KnownFiles,
+    last_scan: Option<i64>,
+    config: ScanConfig,
+    mut on_progress: impl FnMut(usize),
+) -> Reconciliation {
+    let known = Arc::new(known);
+    let mut result = Reconciliation::default();
+
+    let mut scan = FileScanner::new().scan(root, ScanConfig {
+        compute_hash: false, // Le watchdog hashe seulement les fichiers modifiés
+        known: Some(known.clone()),
+        track_dirs: false,
+        done_dirs: None,
+        ..config
+    });
+    for scanned in scan.by_ref() {
+        result.checked += 1;
+        result.file_count += 1;
+        result.total_size += scanned.entry.size;
+        if result.checked % PROGRESS_INTERVAL == 0 {
+            on_progress(result.checked);
+        }
+
+        let path = PathBuf::from(&scanned.entry.path);
+        let racy = match (scanned.entry.modified, last_scan) {
+            (Some(modified), Some(last_scan)) => modified == last_scan,
+            _ => false,
+        };
+        match scanned.change {
+            FileChange::Added => result.events.push(FileEvent::Created(path)),
+            FileChange::Modified => result.events.push(FileEvent::Modified(path)),
+            FileChange::Unchanged if racy => result.events.push(FileEvent::Modified(path)),
+            FileChange::Unchanged => {}
+        }
+    }
+    on_progress(result.checked);
+
+    // Fichiers connus absents du disque. Pas de suppression quand le parcours
+    // n'a pas tout vu: limite de fichiers, dossier illisible, ou racine vide
+    // alors que des fichiers y étaient indexés (point de montage d'un partage
+    // absent: le dossier existe mais son contenu n'est pas là)
+    let report = scan.finish();
+    let vanished = known.vanished_under(root);
+    let empty_mount_point = result.checked == 0 && !vanished.is_empty();
+    result.complete = !report.file_limit_hit && report.unreadable.is_empty() && !empty_mount_point;
+    if !report.file_limit_hit && !empty_mount_point {
+        result.events.extend(
+            vanished
+                .into_iter()
+                .filter(|path| !report.is_unreadable(Path::new(path)))
+                .map(|path| FileEvent::Removed(PathBuf::from(path))),
+        );
+    }
+    result
+}
+
+// Tests TDD
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn mtime(path: &Path) -> i64 {
+        fs::metadata(path)
+            .unwrap()
+            .modified()
+            .unwrap()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64
+    }
+
+    #[test]
+    fn test_reconcile_root() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("sub")).unwrap();
+        let (same, edited, gone, added) = (root.join("same.txt"), root.join("sub").join("edited.txt"), root.join("gone.txt"), root.join("sub").join("added.txt"));
+        fs::write(&same, "same").unwrap();
+        fs::write(&edited, "v2, plus long").unwrap();
+        fs::write(&added, "new").unwrap();
+
+        // État enregistré au dernier scan
+        let state = |path: &Path, size: u64, modified: i64| (path.to_string_lossy().to_string(), size, modified);
+        let known = || {
+            KnownFiles::new(vec![
+                state(&same, 4, mtime(&same)),
+                state(&edited, 2, mtime(&edited)),
+                state(&gone, 4, 0),
+            ])
+        };
+
+        let mut calls = 0;
+        let result = reconcile_root(root, known(), Some(mtime(&same)), ScanConfig::default(), |_| calls += 1);
+        assert!(calls >= 1);
+        assert!(result.complete);
+        assert_eq!((result.checked, result.file_count), (3, 3));
+        let mut events = result.events;
+        events.sort_by_key(|event| format!("{:?}", event));
+        assert_eq!(
+            events,
+            vec![
+                FileEvent::Created(added.clone()),
+                // same.txt modifié dans la seconde du dernier scan: revérifié
+                FileEvent::Modified(same.clone()),
+                FileEvent::Modified(edited.clone()),
+                FileEvent::Removed(gone.clone()),
+            ]
+        );
+
+        // Dernier scan une seconde plus tard: same.txt n'est plus douteux
+        let result = reconcile_root(root, known(), Some(mtime(&same) + 1), ScanConfig::default(), |_| {});
+        assert_eq!(result.events.len(), 3);
+        assert!(!result.events.contains(&FileEvent::Modified(same)));
+
+        // Racine vide (partage non monté): aucune suppression
+        let mount_point = root.join("mnt");
+        fs::create_dir_all(&mount_point).unwrap();
+        let share = || KnownFiles::new(vec![state(&mount_point.join("a.txt"), 1, 0), state(&mount_point.join("b.txt"), 1, 0)]);
+        let result = reconcile_root(&mount_point, share(), Some(0), ScanConfig::default(), |_| {});
+        assert!(result.events.is_empty());
+        assert!(!result.complete);
+
+        // Racine illisible (ici absente): aucune suppression non plus
+        fs::remove_dir(&mount_point).unwrap();
+        let result = reconcile_root(&mount_point, share(), Some(0), ScanConfig::default(), |_| {});
+        assert!(result.events.is_empty());
+        assert!(!result.complete);
+    }
+}
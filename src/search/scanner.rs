@@ -1,15 +1,663 @@
 // src/search/scanner.rs
-// Scan de fichiers avec walkdir
+// Scan de fichiers parallèle
+//
+// Pipeline en deux étages, reliés par des channels bornés:
+// 1. parcours: plusieurs threads lisent les dossiers (read_dir). Chaque
+//    thread a sa propre file de sous-dossiers et vole le travail des autres
+//    quand la sienne est vide (work-stealing, crossbeam-deque): un gros
+//    sous-arbre est réparti sur tous les threads au lieu d'en occuper un seul.
+// 2. métadonnées: un pool de workers lit les métadonnées (stat) et, si
+//    demandé, le hash rapide de chaque fichier trouvé.
+// Les fichiers sont transmis au fur et à mesure (ScanHandle): l'indexation
+// commence dès le premier fichier, et les channels bornés ralentissent le
+// parcours si l'indexation ne suit pas (mémoire constante).
+//
+// Les dossiers qui ne sont pas parcourus jusqu'au bout (profondeur max,
+// limite de fichiers, scan arrêté par l'appelant) sont relevés dans un
+// ScanReport: l'index sait ce qui lui manque.
+//
+// Sur demande (track_dirs), le scan suit aussi les dossiers terminés: tous
+// leurs fichiers listés ont été reçus et traités par l'appelant (file_done).
+// L'indexation s'en sert comme point de reprise, et un scan repris saute les
+// fichiers de ces dossiers (done_dirs) tout en parcourant leurs sous-dossiers.
+//
+// Politiques de parcours (par racine):
+// - liens symboliques ignorés, ou suivis (follow_symlinks): chaque dossier
+//   n'est alors parcouru qu'une fois, ce qui coupe les boucles de liens
+// - pseudo-systèmes de fichiers (proc, sysfs, tmpfs...) sautés par défaut,
+//   sauf s'ils sont la racine elle-même (voir mounts.rs)
+// - same_filesystem: pas de descente dans un autre montage que celui de la racine
 
-use anyhow::Result;
-use walkdir::WalkDir;
-use std::path::Path;
+use crossbeam_channel::{bounded, Receiver, Sender};
+use crossbeam_deque::{Injector, Steal, Stealer, Worker};
+use super::exclusions::ExclusionMatcher;
+use super::incremental::{FileChange, KnownFiles};
+use super::ignore_rules::{ignore_files, is_ignore_file, IgnoreRules, IgnoreStack};
+use super::mounts::MountTable;
+use crate::config::ExclusionsConfig;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+#[derive(Debug, Clone)]
 pub struct FileEntry {
     pub path: String,
     pub filename: String,
+    pub size: u64,
+    pub modified: Option<i64>, // Timestamp Unix (secondes)
+    pub created: Option<i64>,  // Timestamp Unix (secondes), absent sur certains FS
 }
 
+impl FileEntry {
+    // Construit une entrée à partir de métadonnées déjà lues (pas de stat supplémentaire)
+    pub fn from_metadata(path: String, filename: String, metadata: &std::fs::Metadata) -> Self {
+        Self {
+            path,
+            filename,
+            size: metadata.len(),
+            modified: to_timestamp(metadata.modified()),
+            created: to_timestamp(metadata.created()),
+        }
+    }
+
+    // Construit une entrée en lisant les métadonnées sur le disque
+    // Si le fichier est inaccessible, taille 0 et dates inconnues
+    pub fn from_path(path: &str, filename: &str) -> Self {
+        match std::fs::metadata(path) {
+            Ok(metadata) => Self::from_metadata(path.to_string(), filename.to_string(), &metadata),
+            Err(_) => Self {
+                path: path.to_string(),
+                filename: filename.to_string(),
+                size: 0,
+                modified: None,
+                created: None,
+            },
+        }
+    }
+}
+
+// Convertit une date système en timestamp Unix
+fn to_timestamp(time: std::io::Result<SystemTime>) -> Option<i64> {
+    time.ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+}
+
+// Paramètres du scan
+#[derive(Debug, Clone)]
+pub struct ScanConfig {
+    pub max_files: usize,
+    pub max_depth: Option<usize>, // Profondeur max sous la racine, fichiers compris (None: illimitée)
+    pub exclusions: ExclusionsConfig,
+    pub respect_vcs_ignore: bool, // Lire les .gitignore (.ignore et .xfinderignore toujours lus)
+    pub follow_symlinks: bool,   // Suivre les liens symboliques (boucles détectées)
+    pub same_filesystem: bool,   // Ne pas traverser les points de montage
+    pub skip_pseudo_fs: bool,    // Sauter proc, sysfs, devtmpfs, tmpfs...
+    pub compute_hash: bool,      // Hash rapide (blake3, 1er Mo) de chaque fichier
+    pub known: Option<Arc<KnownFiles>>, // Fichiers déjà indexés: classés, pas de hash s'ils n'ont pas changé
+    pub track_dirs: bool,        // Suivre les dossiers terminés (voir ScanHandle::file_done)
+    pub done_dirs: Option<Arc<HashSet<PathBuf>>>, // Dossiers déjà traités: fichiers sautés, sous-dossiers parcourus
+    pub walker_threads: usize,   // Threads de parcours des dossiers
+    pub metadata_threads: usize, // Workers métadonnées + hash
+    pub channel_capacity: usize, // Taille des channels entre les étages
+}
+
+impl Default for ScanConfig {
+    fn default() -> Self {
+        let cores = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4);
+        Self {
+            max_files: usize::MAX,
+            max_depth: None,
+            exclusions: ExclusionsConfig::none(),
+            respect_vcs_ignore: true,
+            follow_symlinks: false,
+            same_filesystem: false,
+            skip_pseudo_fs: true,
+            compute_hash: false,
+            known: None,
+            track_dirs: false,
+            done_dirs: None,
+            // Les threads attendent surtout le disque: un peu plus que de coeurs
+            walker_threads: cores.clamp(2, 8),
+            metadata_threads: (cores * 2).clamp(2, 16),
+            channel_capacity: 4096,
+        }
+    }
+}
+
+// Fichier trouvé par le scan, avec ses métadonnées
+#[derive(Debug, Clone)]
+pub struct ScannedFile {
+    pub entry: FileEntry,
+    pub hash: Option<String>, // Si ScanConfig::compute_hash (None si illisible ou inchangé)
+    pub change: FileChange,   // Par rapport à ScanConfig::known (Added sans état connu)
+}
+
+// Dossiers tronqués gardés en détail dans un rapport (les autres sont comptés)
+pub const MAX_REPORTED_TRUNCATIONS: usize = 200;
+
+// Pourquoi un dossier n'a pas été parcouru entièrement
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TruncationReason {
+    MaxDepth,  // Sous-dossier au-delà de la profondeur max
+    FileLimit, // Limite de fichiers atteinte (ou scan arrêté) avant la fin du dossier
+    Unreadable, // Dossier illisible (droits, erreur d'E/S, partage démonté)
+}
+
+impl TruncationReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TruncationReason::MaxDepth => "max_depth",
+            TruncationReason::FileLimit => "file_limit",
+            TruncationReason::Unreadable => "unreadable",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "max_depth" => Some(TruncationReason::MaxDepth),
+            "file_limit" => Some(TruncationReason::FileLimit),
+            "unreadable" => Some(TruncationReason::Unreadable),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TruncationReason::MaxDepth => "profondeur max",
+            TruncationReason::FileLimit => "limite de fichiers",
+            TruncationReason::Unreadable => "illisible",
+        }
+    }
+}
+
+// Dossier dont le contenu n'est pas (entièrement) indexé
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TruncatedDir {
+    pub path: String,
+    pub reason: TruncationReason,
+}
+
+// Bilan d'un scan: sous-arbres non parcourus jusqu'au bout
+#[derive(Debug, Clone, Default)]
+pub struct ScanReport {
+    pub truncated: Vec<TruncatedDir>, // Au plus MAX_REPORTED_TRUNCATIONS
+    pub truncated_total: usize,       // Nombre total de dossiers tronqués
+    pub file_limit_hit: bool,         // Des fichiers n'ont pas été vus (limite ou arrêt)
+    pub unreadable: Vec<PathBuf>,     // Dossiers illisibles (tous, pas seulement les MAX_REPORTED_TRUNCATIONS)
+}
+
+impl ScanReport {
+    // Un fichier connu non revu est-il vraiment supprimé ? Pas s'il est sous
+    // un dossier qui n'a pas pu être lu: son contenu est simplement inconnu
+    pub fn is_unreadable(&self, path: &Path) -> bool {
+        self.unreadable.iter().any(|dir| path.starts_with(dir))
+    }
+}
+
+// Relevé des dossiers tronqués, partagé entre les threads
+#[derive(Debug, Default)]
+struct TruncationLog {
+    inner: Mutex<(HashSet<PathBuf>, ScanReport)>,
+}
+
+impl TruncationLog {
+    fn record(&self, path: &Path, reason: TruncationReason) {
+        let mut inner = self.inner.lock().unwrap();
+        let (seen, report) = &mut *inner;
+        report.file_limit_hit |= reason == TruncationReason::FileLimit;
+        if reason == TruncationReason::Unreadable {
+            report.unreadable.push(path.to_path_buf());
+        }
+        if !seen.insert(path.to_path_buf()) {
+            return;
+        }
+        report.truncated_total += 1;
+        if report.truncated.len() < MAX_REPORTED_TRUNCATIONS {
+            report.truncated.push(TruncatedDir {
+                path: path.to_string_lossy().to_string(),
+                reason,
+            });
+        }
+    }
+
+    fn take(&self) -> ScanReport {
+        let mut inner = self.inner.lock().unwrap();
+        inner.0.clear();
+        std::mem::take(&mut inner.1)
+    }
+}
+
+// Suivi des dossiers terminés (ScanConfig::track_dirs)
+//
+// Un dossier est terminé quand le parcours l'a lu en entier et que chacun de
+// ses fichiers a été traité. Le compte peut devenir négatif: un fichier est
+// souvent traité avant que le parcours ait fini de lister son dossier.
+#[derive(Debug, Default)]
+struct DirTracker {
+    // Dossiers en cours: (fichiers restant à traiter, liste complète)
+    open: Mutex<HashMap<PathBuf, (isize, bool)>>,
+    completed: Mutex<Vec<PathBuf>>,
+}
+
+impl DirTracker {
+    // Le parcours a fini de lister dir, qui contenait `files` fichiers retenus
+    fn listed(&self, dir: &Path, files: usize) {
+        self.update(dir, files as isize, true);
+    }
+
+    // Un fichier de dir a été traité (ou écarté par les workers)
+    fn file_done(&self, dir: &Path) {
+        self.update(dir, -1, false);
+    }
+
+    fn update(&self, dir: &Path, delta: isize, listed: bool) {
+        let mut open = self.open.lock().unwrap();
+        let state = open.entry(dir.to_path_buf()).or_insert((0, false));
+        state.0 += delta;
+        state.1 |= listed;
+        if *state == (0, true) {
+            open.remove(dir);
+            self.completed.lock().unwrap().push(dir.to_path_buf());
+        }
+    }
+
+    fn take_completed(&self) -> Vec<PathBuf> {
+        std::mem::take(&mut *self.completed.lock().unwrap())
+    }
+}
+
+// Compteurs du scan en cours, partagés entre les threads
+#[derive(Debug, Default)]
+struct ScanCounters {
+    files_found: AtomicUsize,     // Fichiers retenus par le parcours
+    files_processed: AtomicUsize, // Fichiers passés par les workers métadonnées
+    walk_done: AtomicBool,
+}
+
+// Progression du scan (instantané des compteurs)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ScanProgress {
+    pub files_found: usize,
+    pub files_processed: usize,
+    pub walk_done: bool, // Parcours terminé: files_found est le total définitif
+}
+
+// Scan en cours: itérer dessus reçoit les fichiers au fur et à mesure
+//
+// Lâcher le handle arrête le scan (les threads voient le channel fermé);
+// finish() l'arrête aussi et retourne le rapport des dossiers tronqués.
+pub struct ScanHandle {
+    rx: Receiver<ScannedFile>,
+    counters: Arc<ScanCounters>,
+    cancelled: Arc<AtomicBool>,
+    // Les fichiers pas encore reçus ne le seront pas (finish)
+    abandoned: Arc<AtomicBool>,
+    truncations: Arc<TruncationLog>,
+    dirs: Option<Arc<DirTracker>>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl ScanHandle {
+    pub fn progress(&self) -> ScanProgress {
+        let c = &self.counters;
+        ScanProgress {
+            files_found: c.files_found.load(Ordering::Relaxed),
+            files_processed: c.files_processed.load(Ordering::Relaxed),
+            walk_done: c.walk_done.load(Ordering::Acquire),
+        }
+    }
+
+    // Arrête le parcours (les fichiers déjà trouvés restent à recevoir)
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    // Signale qu'un fichier reçu a été traité (ScanConfig::track_dirs)
+    pub fn file_done(&self, path: &str) {
+        if let (Some(dirs), Some(parent)) = (&self.dirs, Path::new(path).parent()) {
+            dirs.file_done(parent);
+        }
+    }
+
+    // Dossiers terminés depuis le dernier appel (ScanConfig::track_dirs)
+    pub fn take_completed_dirs(&self) -> Vec<PathBuf> {
+        self.dirs.as_ref().map(|dirs| dirs.take_completed()).unwrap_or_default()
+    }
+
+    // Channel des fichiers, pour attendre plusieurs scans à la fois (Select)
+    // Un scan reçu ainsi se termine par finish().
+    pub fn receiver(&self) -> &Receiver<ScannedFile> {
+        &self.rx
+    }
+
+    // Arrête le scan et retourne son rapport
+    // Scan terminé: seuls les dossiers coupés (profondeur, limite) y figurent.
+    // Sinon les dossiers pas encore parcourus et ceux des fichiers non reçus
+    // y sont ajoutés.
+    pub fn finish(mut self) -> ScanReport {
+        self.abandoned.store(true, Ordering::Relaxed);
+        self.cancel();
+        for file in self.rx.iter() {
+            if let Some(parent) = Path::new(&file.entry.path).parent() {
+                self.truncations.record(parent, TruncationReason::FileLimit);
+            }
+        }
+        for thread in self.threads.drain(..) {
+            let _ = thread.join();
+        }
+        self.truncations.take()
+    }
+}
+
+impl Iterator for ScanHandle {
+    type Item = ScannedFile;
+
+    fn next(&mut self) -> Option<ScannedFile> {
+        match self.rx.recv() {
+            Ok(file) => Some(file),
+            Err(_) => {
+                // Tous les workers ont terminé
+                for thread in self.threads.drain(..) {
+                    let _ = thread.join();
+                }
+                None
+            }
+        }
+    }
+}
+
+impl Drop for ScanHandle {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+// Dossier à lire
+struct DirJob {
+    path: PathBuf,
+    depth: usize, // Profondeur du dossier (racine = 0)
+    ignore: IgnoreStack, // Règles des fichiers ignore des dossiers parents
+}
+
+// Identité d'un dossier, pour ne pas le parcourir deux fois via des liens
+#[cfg(unix)]
+type DirKey = (u64, u64); // (périphérique, inode)
+#[cfg(not(unix))]
+type DirKey = PathBuf; // Chemin canonique
+
+#[cfg(unix)]
+fn dir_key(_path: &Path, metadata: &std::fs::Metadata) -> Option<DirKey> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn dir_key(path: &Path, _metadata: &std::fs::Metadata) -> Option<DirKey> {
+    std::fs::canonicalize(path).ok()
+}
+
+// Périphérique d'un fichier (None: inconnu, same_filesystem sans effet)
+#[cfg(unix)]
+fn device_of(metadata: &std::fs::Metadata) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    Some(metadata.dev())
+}
+
+#[cfg(not(unix))]
+fn device_of(_metadata: &std::fs::Metadata) -> Option<u64> {
+    None
+}
+
+// État partagé par les threads de parcours
+struct Walk {
+    config: ScanConfig,
+    exclusions: Arc<ExclusionMatcher>,
+    pseudo_mounts: HashSet<PathBuf>, // Points de montage à sauter (skip_pseudo_fs)
+    root_device: Option<u64>,        // Périphérique de la racine (same_filesystem)
+    visited: Option<Mutex<HashSet<DirKey>>>, // Dossiers déjà parcourus (follow_symlinks)
+    injector: Injector<DirJob>,
+    stealers: Vec<Stealer<DirJob>>,
+    // Dossiers en file ou en cours de lecture: 0 = parcours terminé
+    pending: AtomicUsize,
+    counters: Arc<ScanCounters>,
+    cancelled: Arc<AtomicBool>,
+    truncations: Arc<TruncationLog>,
+    dirs: Option<Arc<DirTracker>>,
+}
+
+impl Walk {
+    // Prochain dossier: file locale, puis file globale, puis vol chez un autre thread
+    fn find_job(&self, local: &Worker<DirJob>) -> Option<DirJob> {
+        local.pop().or_else(|| {
+            std::iter::repeat_with(|| {
+                self.injector
+                    .steal_batch_and_pop(local)
+                    .or_else(|| self.stealers.iter().map(|s| s.steal()).collect())
+            })
+            .find(|steal| !steal.is_retry())
+            .and_then(|steal| steal.success())
+        })
+    }
+
+    fn stopped(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    // Politiques de parcours d'un sous-dossier (linked: atteint par un lien)
+    fn enter_dir(&self, path: &Path, linked: bool) -> bool {
+        if !self.pseudo_mounts.is_empty() {
+            // Un lien peut mener n'importe où (/proc...): sa cible compte
+            let in_pseudo_fs = if linked {
+                std::fs::canonicalize(path)
+                    .map(|target| self.pseudo_mounts.iter().any(|mount| target.starts_with(mount)))
+                    .unwrap_or(true)
+            } else {
+                self.pseudo_mounts.contains(path)
+            };
+            if in_pseudo_fs {
+                return false;
+            }
+        }
+        if self.root_device.is_none() && self.visited.is_none() {
+            return true;
+        }
+        let Ok(metadata) = std::fs::metadata(path) else {
+            return false;
+        };
+        if self.root_device.is_some() && device_of(&metadata) != self.root_device {
+            return false;
+        }
+        match (&self.visited, dir_key(path, &metadata)) {
+            (Some(visited), Some(key)) => visited.lock().unwrap().insert(key),
+            (Some(_), None) => false, // Identité inconnue: risque de boucle
+            (None, _) => true,
+        }
+    }
+
+    // Réserve une place dans la limite de fichiers (false: limite atteinte)
+    fn reserve_file(&self) -> bool {
+        let found = self.counters.files_found.fetch_add(1, Ordering::Relaxed);
+        if found >= self.config.max_files {
+            self.counters.files_found.fetch_sub(1, Ordering::Relaxed);
+            self.cancelled.store(true, Ordering::Relaxed);
+            return false;
+        }
+        true
+    }
+
+    // Scan arrêté: les dossiers encore en file ne seront pas lus
+    fn abandon_pending(&self, local: &Worker<DirJob>) {
+        let global = std::iter::from_fn(|| loop {
+            match self.injector.steal() {
+                Steal::Success(job) => return Some(job),
+                Steal::Empty => return None,
+                Steal::Retry => {}
+            }
+        });
+        for job in std::iter::from_fn(|| local.pop()).chain(global) {
+            self.truncations.record(&job.path, TruncationReason::FileLimit);
+            self.pending.fetch_sub(1, Ordering::AcqRel);
+        }
+    }
+
+    // Boucle d'un thread de parcours
+    fn run(&self, local: Worker<DirJob>, files_tx: &Sender<PathBuf>) {
+        loop {
+            if self.stopped() {
+                self.abandon_pending(&local);
+                return;
+            }
+            match self.find_job(&local) {
+                Some(job) => {
+                    self.read_dir(job, &local, files_tx);
+                    self.pending.fetch_sub(1, Ordering::AcqRel);
+                }
+                // Plus rien à voler et aucun dossier en cours: terminé
+                None if self.pending.load(Ordering::Acquire) == 0 => return,
+                // D'autres threads lisent encore des dossiers (et vont en publier)
+                None => std::thread::sleep(Duration::from_micros(200)),
+            }
+        }
+    }
+
+    fn read_dir(&self, job: DirJob, local: &Worker<DirJob>, files_tx: &Sender<PathBuf>) {
+        let Ok(entries) = std::fs::read_dir(&job.path) else {
+            // Dossier inaccessible (droits, E/S...): noté pour ne pas prendre
+            // ses fichiers déjà indexés pour des fichiers supprimés
+            self.truncations.record(&job.path, TruncationReason::Unreadable);
+            return;
+        };
+        let child_depth = job.depth + 1;
+
+        // Règles du dossier lues avant de filtrer son contenu
+        let entries: Vec<std::fs::DirEntry> = entries.flatten().collect();
+        let has_rules = entries.iter().any(|entry| is_ignore_file(&entry.file_name().to_string_lossy()));
+        let ignore = if has_rules {
+            let files = ignore_files(self.config.respect_vcs_ignore);
+            job.ignore.child(IgnoreRules::load(&job.path, &files).map(Arc::new))
+        } else {
+            job.ignore
+        };
+        // Reprise: fichiers de ce dossier déjà traités
+        let skip_files = self.config.done_dirs.as_ref().is_some_and(|done| done.contains(&job.path));
+        let mut files_sent = 0;
+
+        for entry in entries {
+            if self.stopped() {
+                self.truncations.record(&job.path, TruncationReason::FileLimit);
+                return;
+            }
+            // Type lu avec l'entrée (pas de stat sur la plupart des FS)
+            let Ok(mut file_type) = entry.file_type() else {
+                continue;
+            };
+            let path = entry.path();
+            let linked = file_type.is_symlink();
+            if linked {
+                if !self.config.follow_symlinks {
+                    continue;
+                }
+                // Type de la cible (lien cassé: ignoré)
+                let Ok(target) = std::fs::metadata(&path) else {
+                    continue;
+                };
+                file_type = target.file_type();
+            }
+
+            if file_type.is_dir() {
+                // Exclure les dossiers avant de les parcourir
+                if self.exclusions.is_excluded(&path, true, None) || ignore.is_ignored(&path, true) {
+                    continue;
+                }
+                if self.config.max_depth.is_some_and(|max| child_depth >= max) {
+                    self.truncations.record(&path, TruncationReason::MaxDepth);
+                    continue;
+                }
+                if !self.enter_dir(&path, linked) {
+                    continue;
+                }
+                self.pending.fetch_add(1, Ordering::AcqRel);
+                local.push(DirJob { path, depth: child_depth, ignore: ignore.clone() });
+            } else if file_type.is_file() {
+                // La taille est vérifiée par les workers (stat)
+                if skip_files || self.exclusions.is_excluded(&path, false, None) || ignore.is_ignored(&path, false) {
+                    continue;
+                }
+                // Limite atteinte: ce dossier a au moins un fichier de trop
+                if !self.reserve_file() {
+                    self.truncations.record(&job.path, TruncationReason::FileLimit);
+                    return;
+                }
+                // Channel fermé: le scan a été abandonné
+                if files_tx.send(path).is_err() {
+                    self.cancelled.store(true, Ordering::Relaxed);
+                    return;
+                }
+                files_sent += 1;
+            }
+        }
+
+        if let Some(dirs) = &self.dirs {
+            dirs.listed(&job.path, files_sent);
+        }
+    }
+}
+
+// État partagé par les workers métadonnées
+struct MetadataShared {
+    compute_hash: bool,
+    known: Option<Arc<KnownFiles>>,
+    exclusions: Arc<ExclusionMatcher>,
+    counters: Arc<ScanCounters>,
+    abandoned: Arc<AtomicBool>,
+    truncations: Arc<TruncationLog>,
+    dirs: Option<Arc<DirTracker>>,
+}
+
+// Worker métadonnées: stat (+ hash) de chaque fichier trouvé
+fn metadata_worker(files_rx: Receiver<PathBuf>, tx: Sender<ScannedFile>, shared: Arc<MetadataShared>) {
+    let MetadataShared { compute_hash, known, exclusions, counters, abandoned, truncations, dirs } = &*shared;
+    for path in files_rx {
+        // Plus personne ne reçoit: relever le dossier sans lire le fichier
+        if abandoned.load(Ordering::Relaxed) {
+            if let Some(parent) = path.parent() {
+                truncations.record(parent, TruncationReason::FileLimit);
+            }
+            continue;
+        }
+        let path_str = path.to_string_lossy().to_string();
+        let filename = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let entry = FileEntry::from_path(&path_str, &filename);
+        // Trop gros: rendu à la limite de fichiers
+        if exclusions.too_large(entry.size) {
+            counters.files_found.fetch_sub(1, Ordering::Relaxed);
+            if let (Some(dirs), Some(parent)) = (dirs, path.parent()) {
+                dirs.file_done(parent);
+            }
+            continue;
+        }
+        let change = known.as_ref().map(|known| known.classify(&entry)).unwrap_or_default();
+        let hash = if *compute_hash && change != FileChange::Unchanged {
+            crate::hash::hash_file_fast(&path).ok()
+        } else {
+            None
+        };
+
+        counters.files_processed.fetch_add(1, Ordering::Relaxed);
+        if tx.send(ScannedFile { entry, hash, change }).is_err() {
+            return; // Handle lâché
+        }
+    }
+}
+
+#[derive(Default)]
 pub struct FileScanner;
 
 impl FileScanner {
@@ -17,45 +665,119 @@ impl FileScanner {
         Self
     }
 
-    // Vérifie si un chemin doit être exclu
-    fn should_exclude(
-        &self,
-        path: &Path,
-        filename: &str,
-        excluded_extensions: &[String],
-        excluded_patterns: &[String],
-        excluded_dirs: &[String],
-    ) -> bool {
-        let path_str = path.to_string_lossy().to_string();
+    // Lance le scan parallèle de root et retourne immédiatement
+    // Les fichiers arrivent dans un ordre quelconque.
+    pub fn scan(&self, root: &Path, config: ScanConfig) -> ScanHandle {
+        let counters = Arc::new(ScanCounters::default());
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let abandoned = Arc::new(AtomicBool::new(false));
+        let truncations = Arc::new(TruncationLog::default());
+        let dirs = config.track_dirs.then(|| Arc::new(DirTracker::default()));
+        let (files_tx, files_rx) = bounded::<PathBuf>(config.channel_capacity);
+        let (tx, rx) = bounded::<ScannedFile>(config.channel_capacity);
+        let mut threads = Vec::new();
+        let exclusions = Arc::new(ExclusionMatcher::new(&config.exclusions).with_roots(&[root]));
 
-        // 1. Vérifier les dossiers exclus (chemins exacts)
-        for excluded_dir in excluded_dirs {
-            if path_str.starts_with(excluded_dir) || path_str.contains(&format!("\\{}", excluded_dir)) {
-                return true;
-            }
+        // La racine est toujours parcourue, même sur un pseudo-FS (/tmp en tmpfs...)
+        let mut pseudo_mounts = if config.skip_pseudo_fs {
+            MountTable::load().pseudo_mount_points()
+        } else {
+            HashSet::new()
+        };
+        let canonical_root = std::fs::canonicalize(root).unwrap_or_else(|_| root.to_path_buf());
+        pseudo_mounts.retain(|mount| !root.starts_with(mount) && !canonical_root.starts_with(mount));
+        let root_metadata = std::fs::metadata(root).ok();
+        let root_device = if config.same_filesystem {
+            root_metadata.as_ref().and_then(device_of)
+        } else {
+            None
+        };
+        let visited = config.follow_symlinks.then(|| {
+            let root_key = root_metadata.as_ref().and_then(|metadata| dir_key(root, metadata));
+            Mutex::new(root_key.into_iter().collect())
+        });
+
+        let shared = Arc::new(MetadataShared {
+            compute_hash: config.compute_hash,
+            known: config.known.clone(),
+            exclusions: exclusions.clone(),
+            counters: counters.clone(),
+            abandoned: abandoned.clone(),
+            truncations: truncations.clone(),
+            dirs: dirs.clone(),
+        });
+        for _ in 0..config.metadata_threads.max(1) {
+            let files_rx = files_rx.clone();
+            let tx = tx.clone();
+            let shared = shared.clone();
+            threads.push(std::thread::spawn(move || metadata_worker(files_rx, tx, shared)));
         }
+        drop(files_rx);
+        drop(tx);
 
-        // 2. Vérifier les extensions exclues
-        if let Some(ext) = path.extension() {
-            let ext_with_dot = format!(".{}", ext.to_string_lossy());
-            if excluded_extensions.contains(&ext_with_dot) {
-                return true;
-            }
+        let locals: Vec<Worker<DirJob>> = (0..config.walker_threads.max(1)).map(|_| Worker::new_fifo()).collect();
+        let walk = Arc::new(Walk {
+            stealers: locals.iter().map(Worker::stealer).collect(),
+            injector: Injector::new(),
+            pending: AtomicUsize::new(0),
+            counters: counters.clone(),
+            cancelled: cancelled.clone(),
+            truncations: truncations.clone(),
+            dirs: dirs.clone(),
+            exclusions,
+            pseudo_mounts,
+            root_device,
+            visited,
+            config,
+        });
+
+        // Racine: un dossier à parcourir, ou un fichier seul
+        if root.is_dir() && !walk.exclusions.is_excluded(root, true, None) {
+            walk.pending.store(1, Ordering::Release);
+            walk.injector.push(DirJob { path: root.to_path_buf(), depth: 0, ignore: IgnoreStack::default() });
+        } else if root.is_file() && !walk.exclusions.is_excluded(root, false, None) && walk.reserve_file() {
+            let _ = files_tx.send(root.to_path_buf());
+        } else if !root.exists() {
+            // Racine introuvable (support débranché, partage démonté...)
+            walk.truncations.record(root, TruncationReason::Unreadable);
         }
 
-        // 3. Vérifier les patterns (noms de fichiers/dossiers)
-        for pattern in excluded_patterns {
-            // Pattern simple: si le chemin contient le pattern
-            if path_str.contains(pattern) || filename.contains(pattern) {
-                return true;
+        let walkers: Vec<JoinHandle<()>> = locals
+            .into_iter()
+            .map(|local| {
+                let walk = walk.clone();
+                let files_tx = files_tx.clone();
+                std::thread::spawn(move || walk.run(local, &files_tx))
+            })
+            .collect();
+        drop(files_tx);
+
+        // Signale la fin du parcours (le total de fichiers est alors connu)
+        threads.push(std::thread::spawn(move || {
+            for walker in walkers {
+                let _ = walker.join();
             }
-        }
+            counters.walk_done.store(true, Ordering::Release);
+        }));
 
-        false
+        ScanHandle {
+            rx,
+            counters: walk.counters.clone(),
+            cancelled,
+            abandoned,
+            truncations,
+            dirs,
+            threads,
+        }
     }
 
     // Scan un dossier récursivement avec exclusions
     // Limite à max_files pour éviter de surcharger
+    //
+    // Attend la fin du scan parallèle (tests seulement, y compris
+    // tests/e2e_phase1.rs: voir scan() pour recevoir les fichiers au fur et
+    // à mesure)
+    #[allow(dead_code)]
     pub fn scan_directory(
         &self,
         root: &Path,
@@ -63,48 +785,18 @@ impl FileScanner {
         excluded_extensions: &[String],
         excluded_patterns: &[String],
         excluded_dirs: &[String],
-    ) -> Result<Vec<FileEntry>> {
-        let mut entries = Vec::new();
-        let mut count = 0;
-
-        for entry in WalkDir::new(root)
-            .max_depth(5) // Limite profondeur
-            .follow_links(false)
-        {
-            if count >= max_files {
-                break;
-            }
-
-            if let Ok(entry) = entry {
-                let path = entry.path();
-
-                // Exclure les dossiers avant de les parcourir
-                if entry.file_type().is_dir() {
-                    if self.should_exclude(path, "", excluded_extensions, excluded_patterns, excluded_dirs) {
-                        continue;
-                    }
-                }
-
-                if entry.file_type().is_file() {
-                    if let Some(filename) = path.file_name() {
-                        let filename_str = filename.to_string_lossy().to_string();
-
-                        // Vérifier si le fichier doit être exclu
-                        if self.should_exclude(path, &filename_str, excluded_extensions, excluded_patterns, excluded_dirs) {
-                            continue;
-                        }
-
-                        entries.push(FileEntry {
-                            path: path.to_string_lossy().to_string(),
-                            filename: filename_str,
-                        });
-                        count += 1;
-                    }
-                }
-            }
-        }
-
-        Ok(entries)
+    ) -> anyhow::Result<Vec<FileEntry>> {
+        let config = ScanConfig {
+            max_files,
+            exclusions: ExclusionsConfig {
+                extensions: excluded_extensions.to_vec(),
+                patterns: excluded_patterns.to_vec(),
+                dirs: excluded_dirs.to_vec(),
+                ..ExclusionsConfig::none()
+            },
+            ..ScanConfig::default()
+        };
+        Ok(self.scan(root, config).map(|file| file.entry).collect())
     }
 }
 
@@ -148,4 +840,283 @@ mod tests {
             assert!(!file.filename.ends_with(".log"));
         }
     }
+
+    #[test]
+    fn test_scan_collects_metadata() {
+        let temp_dir = std::env::temp_dir().join("xfinder_test_scan_metadata");
+        let _ = std::fs::remove_dir_all(&temp_dir);
+        std::fs::create_dir_all(&temp_dir).unwrap();
+        std::fs::write(temp_dir.join("data.bin"), vec![0u8; 1234]).unwrap();
+
+        let scanner = FileScanner::new();
+        let files = scanner.scan_directory(&temp_dir, 10, &[], &[], &[]).unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].size, 1234);
+        assert!(files[0].modified.is_some());
+
+        let _ = std::fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn test_scan_honors_ignore_files() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join("project/target")).unwrap();
+        std::fs::create_dir_all(root.join("project/src")).unwrap();
+        std::fs::write(root.join("project/.gitignore"), "target/\n*.log\n!important.log\n").unwrap();
+        std::fs::write(root.join(".xfinderignore"), "*.iso\n").unwrap();
+        for file in ["project/target/app.bin", "project/src/main.rs", "project/run.log", "project/important.log", "disk.iso"] {
+            std::fs::write(root.join(file), "x").unwrap();
+        }
+
+        let scanner = FileScanner::new();
+        let names = |respect_vcs_ignore: bool| {
+            let mut names: Vec<String> = scanner
+                .scan(root, ScanConfig { respect_vcs_ignore, ..ScanConfig::default() })
+                .map(|f| f.entry.filename)
+                .filter(|name| !name.starts_with('.'))
+                .collect();
+            names.sort();
+            names
+        };
+
+        assert_eq!(names(true), vec!["important.log", "main.rs"]);
+        // .gitignore désactivé: seul .xfinderignore s'applique
+        assert_eq!(names(false), vec!["app.bin", "important.log", "main.rs", "run.log"]);
+    }
+
+    #[test]
+    fn test_parallel_scan_tree() {
+        let temp_dir = std::env::temp_dir().join("xfinder_test_scan_parallel");
+        let _ = std::fs::remove_dir_all(&temp_dir);
+        // 8 dossiers x 3 niveaux x 25 fichiers, plus un dossier exclu
+        for d in 0..8 {
+            let mut dir = temp_dir.clone();
+            for level in 0..3 {
+                dir = dir.join(format!("d{}_{}", d, level));
+                std::fs::create_dir_all(&dir).unwrap();
+                for f in 0..25 {
+                    std::fs::write(dir.join(format!("f{}.txt", f)), format!("{}-{}-{}", d, level, f)).unwrap();
+                }
+            }
+        }
+        std::fs::create_dir_all(temp_dir.join("node_modules")).unwrap();
+        std::fs::write(temp_dir.join("node_modules").join("lib.js"), "x").unwrap();
+
+        let scanner = FileScanner::new();
+        let config = ScanConfig {
+            exclusions: ExclusionsConfig {
+                patterns: vec!["node_modules".to_string()],
+                ..ExclusionsConfig::none()
+            },
+            compute_hash: true,
+            walker_threads: 4,
+            metadata_threads: 4,
+            channel_capacity: 16, // Petits channels: le parcours attend l'indexation
+            ..ScanConfig::default()
+        };
+        let handle = scanner.scan(&temp_dir, config.clone());
+        let files: Vec<ScannedFile> = handle.collect();
+
+        // Chaque fichier exactement une fois, avec métadonnées et hash
+        let mut paths: Vec<&str> = files.iter().map(|f| f.entry.path.as_str()).collect();
+        paths.sort();
+        paths.dedup();
+        assert_eq!(paths.len(), 8 * 3 * 25);
+        assert_eq!(files.len(), 8 * 3 * 25);
+        assert!(files.iter().all(|f| f.hash.is_some() && f.entry.size > 0));
+        assert!(!paths.iter().any(|p| p.contains("node_modules")));
+
+        // Limite de fichiers et progression
+        let mut handle = scanner.scan(&temp_dir, ScanConfig { max_files: 30, ..config });
+        let limited: Vec<ScannedFile> = handle.by_ref().collect();
+        assert_eq!(limited.len(), 30);
+        let progress = handle.progress();
+        assert!(progress.walk_done);
+        assert_eq!(progress.files_found, 30);
+        assert_eq!(progress.files_processed, 30);
+
+        let _ = std::fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn test_scan_report_truncations() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join("a/b/c")).unwrap();
+        for file in ["top.txt", "a/one.txt", "a/b/two.txt", "a/b/c/three.txt"] {
+            std::fs::write(root.join(file), "x").unwrap();
+        }
+        let scanner = FileScanner::new();
+
+        // Profondeur illimitée par défaut: rapport vide
+        let mut handle = scanner.scan(root, ScanConfig::default());
+        assert_eq!(handle.by_ref().count(), 4);
+        assert_eq!(handle.finish().truncated_total, 0);
+
+        // Profondeur 2 (fichiers compris): top.txt et a/one.txt, a/b est coupé
+        let mut handle = scanner.scan(root, ScanConfig { max_depth: Some(2), ..ScanConfig::default() });
+        assert_eq!(handle.by_ref().count(), 2);
+        let report = handle.finish();
+        assert_eq!(report.truncated_total, 1);
+        assert_eq!(report.truncated[0].reason, TruncationReason::MaxDepth);
+        assert_eq!(Path::new(&report.truncated[0].path), root.join("a").join("b"));
+
+        // Scan arrêté par l'appelant avant la fin: le reste est relevé
+        let handle = scanner.scan(root, ScanConfig { walker_threads: 1, channel_capacity: 1, ..ScanConfig::default() });
+        let first = handle.receiver().recv().unwrap();
+        let report = handle.finish();
+        assert!(report.truncated_total > 0);
+        assert!(report.truncated.iter().all(|dir| dir.reason == TruncationReason::FileLimit));
+        assert!(!report.truncated.iter().any(|dir| dir.path == first.entry.path));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scan_report_unreadable_dirs() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let locked = root.join("locked");
+        std::fs::create_dir_all(&locked).unwrap();
+        std::fs::write(root.join("open.txt"), "x").unwrap();
+        std::fs::write(locked.join("hidden.txt"), "x").unwrap();
+        let scanner = FileScanner::new();
+
+        // Racine absente (partage démonté): tout son contenu est inconnu
+        let mut handle = scanner.scan(&root.join("missing"), ScanConfig::default());
+        assert_eq!(handle.by_ref().count(), 0);
+        let report = handle.finish();
+        assert!(report.is_unreadable(&root.join("missing").join("a.txt")));
+        assert_eq!(report.truncated[0].reason, TruncationReason::Unreadable);
+
+        // Dossier en mode 000 (sans effet pour root: test ignoré)
+        std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o000)).unwrap();
+        if std::fs::read_dir(&locked).is_err() {
+            let mut handle = scanner.scan(root, ScanConfig::default());
+            assert_eq!(handle.by_ref().count(), 1);
+            let report = handle.finish();
+            assert_eq!(report.unreadable, vec![locked.clone()]);
+            assert!(report.is_unreadable(&locked.join("hidden.txt")));
+            assert!(!report.is_unreadable(&root.join("open.txt")));
+            assert!(!report.file_limit_hit);
+        }
+        std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[test]
+    fn test_scan_classifies_known_files() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        for file in ["same.txt", "edited.txt", "new.txt"] {
+            std::fs::write(root.join(file), "contenu").unwrap();
+        }
+        let scanner = FileScanner::new();
+        let first: Vec<FileEntry> = scanner.scan(root, ScanConfig::default()).map(|f| f.entry).collect();
+        let state = |name: &str, size_delta: u64| {
+            let entry = first.iter().find(|e| e.filename == name).unwrap();
+            (entry.path.clone(), entry.size + size_delta, entry.modified.unwrap())
+        };
+        let gone = root.join("gone.txt").to_string_lossy().to_string();
+        let known = Arc::new(KnownFiles::new(vec![state("same.txt", 0), state("edited.txt", 1), (gone.clone(), 1, 1)]));
+
+        let config = ScanConfig { known: Some(known.clone()), compute_hash: true, ..ScanConfig::default() };
+        let mut changes: Vec<(String, FileChange, bool)> = scanner
+            .scan(root, config)
+            .map(|f| (f.entry.filename, f.change, f.hash.is_some()))
+            .collect();
+        changes.sort_by(|a, b| a.0.cmp(&b.0));
+
+        // Fichier inchangé: pas de hash
+        assert_eq!(changes, vec![
+            ("edited.txt".to_string(), FileChange::Modified, true),
+            ("new.txt".to_string(), FileChange::Added, true),
+            ("same.txt".to_string(), FileChange::Unchanged, false),
+        ]);
+        assert_eq!(known.vanished_under(root), vec![gone]);
+    }
+
+    #[test]
+    fn test_scan_tracks_and_skips_done_dirs() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join("a/b")).unwrap();
+        std::fs::create_dir_all(root.join("empty")).unwrap();
+        for file in ["top.txt", "a/one.txt", "a/two.txt", "a/b/three.txt"] {
+            std::fs::write(root.join(file), "x").unwrap();
+        }
+        let scanner = FileScanner::new();
+        let config = ScanConfig { track_dirs: true, ..ScanConfig::default() };
+
+        // Un dossier n'est terminé qu'une fois tous ses fichiers traités
+        let handle = scanner.scan(root, config.clone());
+        let mut received = Vec::new();
+        for file in handle.receiver().iter() {
+            if file.entry.filename != "two.txt" {
+                handle.file_done(&file.entry.path);
+            }
+            received.push(file);
+        }
+        let mut done: Vec<PathBuf> = handle.take_completed_dirs();
+        done.sort();
+        assert_eq!(done, vec![root.to_path_buf(), root.join("a/b"), root.join("empty")]);
+        let two = received.iter().find(|f| f.entry.filename == "two.txt").unwrap();
+        handle.file_done(&two.entry.path);
+        assert_eq!(handle.take_completed_dirs(), vec![root.join("a")]);
+
+        // Reprise: les fichiers des dossiers terminés sont sautés, pas leurs sous-dossiers
+        let done_dirs: HashSet<PathBuf> = [root.to_path_buf(), root.join("a")].into_iter().collect();
+        let mut names: Vec<String> = scanner
+            .scan(root, ScanConfig { done_dirs: Some(Arc::new(done_dirs)), ..config })
+            .map(|f| f.entry.filename)
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["three.txt"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scan_symlink_policy() {
+        use std::os::unix::fs::symlink;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path().join("root");
+        let outside = temp_dir.path().join("outside");
+        std::fs::create_dir_all(root.join("a")).unwrap();
+        std::fs::create_dir_all(&outside).unwrap();
+        std::fs::write(root.join("a/one.txt"), "x").unwrap();
+        std::fs::write(outside.join("two.txt"), "x").unwrap();
+        symlink(&root, root.join("a/loop")).unwrap(); // Boucle vers la racine
+        symlink(&outside, root.join("docs")).unwrap();
+        symlink(root.join("a/one.txt"), root.join("alias.txt")).unwrap();
+        symlink(root.join("missing"), root.join("broken")).unwrap();
+        // Lien vers /proc, seulement si la table des montages le connaît
+        let proc_known = MountTable::load().pseudo_mount_points().contains(Path::new("/proc"));
+        if proc_known {
+            symlink("/proc", root.join("proc")).unwrap();
+        }
+
+        let scanner = FileScanner::new();
+        let paths = |follow_symlinks: bool| {
+            let mut paths: Vec<PathBuf> = scanner
+                .scan(&root, ScanConfig { follow_symlinks, ..ScanConfig::default() })
+                .map(|f| PathBuf::from(f.entry.path))
+                .collect();
+            paths.sort();
+            paths
+        };
+
+        // Par défaut les liens sont ignorés
+        assert_eq!(paths(false), vec![root.join("a/one.txt")]);
+        // Suivis: chaque dossier une seule fois (la boucle s'arrête), /proc sauté
+        let followed = paths(true);
+        assert_eq!(followed, vec![root.join("a/one.txt"), root.join("alias.txt"), root.join("docs/two.txt")]);
+
+        // Même système de fichiers: le dossier lui-même reste parcouru
+        let same_fs: Vec<ScannedFile> = scanner
+            .scan(&root, ScanConfig { same_filesystem: true, ..ScanConfig::default() })
+            .collect();
+        assert_eq!(same_fs.len(), 1);
+    }
 }
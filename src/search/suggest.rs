@@ -0,0 +1,207 @@
+// src/search/suggest.rs
+// Suggestions "Vouliez-vous dire" à partir du vocabulaire des noms de fichiers
+//
+// L'index contient un champ filename_words (mots entiers des noms, voir
+// SearchIndex::suggest) dont le dictionnaire de termes sert de vocabulaire.
+// Pour chaque mot libre de la requête absent de l'index, on cherche les mots
+// du vocabulaire à faible distance d'édition, classés par:
+// 1. distance (Damerau-Levenshtein: une inversion de lettres compte 1)
+// 2. nombre de fichiers contenant le mot (les plus fréquents d'abord)
+//
+// Indépendant de la recherche floue: fonctionne même si fuzzy_search est désactivé.
+
+// Longueur min d'un mot pour proposer une correction (en caractères)
+pub const MIN_SUGGEST_LEN: usize = 3;
+
+// Mot du vocabulaire proche d'un mot de la requête
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidate {
+    pub word: String,
+    pub distance: u32,
+    pub doc_freq: u64,
+}
+
+// Distance max tolérée: 1 faute pour les mots courts, 2 au-delà
+pub fn max_distance(word_len: usize) -> u32 {
+    if word_len <= 4 {
+        1
+    } else {
+        2
+    }
+}
+
+// Distance de Damerau-Levenshtein (variante "optimal string alignment")
+pub fn edit_distance(a: &[char], b: &[char]) -> u32 {
+    let (n, m) = (a.len(), b.len());
+    let mut d = vec![vec![0u32; m + 1]; n + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i as u32;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j as u32;
+    }
+
+    for i in 1..=n {
+        for j in 1..=m {
+            let cost = u32::from(a[i - 1] != b[j - 1]);
+            let mut best = (d[i - 1][j] + 1).min(d[i][j - 1] + 1).min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(d[i - 2][j - 2] + 1);
+            }
+            d[i][j] = best;
+        }
+    }
+    d[n][m]
+}
+
+// Meilleurs candidats pour word parmi (mot, fréquence) du vocabulaire
+pub fn rank_candidates(word: &str, vocabulary: impl IntoIterator<Item = (String, u64)>, limit: usize) -> Vec<Candidate> {
+    let word_chars: Vec<char> = word.chars().collect();
+    let max = max_distance(word_chars.len());
+
+    let mut candidates: Vec<Candidate> = vocabulary
+        .into_iter()
+        .filter_map(|(term, doc_freq)| {
+            let term_chars: Vec<char> = term.chars().collect();
+            // Filtre rapide: la différence de longueur minore la distance
+            if term_chars.len().abs_diff(word_chars.len()) > max as usize {
+                return None;
+            }
+            let distance = edit_distance(&word_chars, &term_chars);
+            (distance > 0 && distance <= max).then_some(Candidate {
+                word: term,
+                distance,
+                doc_freq,
+            })
+        })
+        .collect();
+
+    candidates.sort_by(|a, b| {
+        a.distance
+            .cmp(&b.distance)
+            .then(b.doc_freq.cmp(&a.doc_freq))
+            .then_with(|| a.word.cmp(&b.word))
+    });
+    candidates.truncate(limit);
+    candidates
+}
+
+// Requêtes corrigées: chaque mot de la requête est remplacé par son candidat
+//
+// La première suggestion prend le meilleur candidat de chaque mot, les
+// suivantes varient un mot à la fois. Les mots sont remplacés tels quels dans
+// la requête d'origine (opérateurs, filtres et phrases sont conservés).
+pub fn build_corrections(query: &str, corrections: &[(String, Vec<Candidate>)], limit: usize) -> Vec<String> {
+    let corrections: Vec<&(String, Vec<Candidate>)> =
+        corrections.iter().filter(|(_, candidates)| !candidates.is_empty()).collect();
+    if corrections.is_empty() || limit == 0 {
+        return Vec::new();
+    }
+
+    // Variante: indice du candidat retenu pour chaque mot
+    let base: Vec<usize> = vec![0; corrections.len()];
+    let mut variants = vec![base.clone()];
+    for (word_idx, (_, candidates)) in corrections.iter().enumerate() {
+        for candidate_idx in 1..candidates.len() {
+            let mut variant = base.clone();
+            variant[word_idx] = candidate_idx;
+            variants.push(variant);
+        }
+    }
+
+    let cost = |variant: &[usize]| {
+        variant.iter().zip(&corrections).fold((0u32, 0u64), |(distance, freq), (&idx, (_, candidates))| {
+            (distance + candidates[idx].distance, freq + candidates[idx].doc_freq)
+        })
+    };
+    variants.sort_by(|a, b| {
+        let (da, fa) = cost(a);
+        let (db, fb) = cost(b);
+        da.cmp(&db).then(fb.cmp(&fa))
+    });
+
+    let mut suggestions = Vec::new();
+    for variant in variants {
+        let replacements: Vec<(&str, &str)> = variant
+            .iter()
+            .zip(&corrections)
+            .map(|(&idx, (word, candidates))| (word.as_str(), candidates[idx].word.as_str()))
+            .collect();
+        let suggestion = replace_words(query, &replacements);
+        if suggestion != query && !suggestions.contains(&suggestion) {
+            suggestions.push(suggestion);
+        }
+        if suggestions.len() >= limit {
+            break;
+        }
+    }
+    suggestions
+}
+
+// Remplace les mots entiers de la requête (sans tenir compte de la casse)
+fn replace_words(query: &str, replacements: &[(&str, &str)]) -> String {
+    query
+        .split_whitespace()
+        .map(|token| {
+            replacements
+                .iter()
+                .find(|(word, _)| token.to_lowercase() == word.to_lowercase())
+                .map(|(_, replacement)| replacement.to_string())
+                .unwrap_or_else(|| token.to_string())
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Tests TDD
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chars(s: &str) -> Vec<char> {
+        s.chars().collect()
+    }
+
+    fn vocabulary(words: &[(&str, u64)]) -> Vec<(String, u64)> {
+        words.iter().map(|(w, f)| (w.to_string(), *f)).collect()
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance(&chars("rapport"), &chars("rapport")), 0);
+        assert_eq!(edit_distance(&chars("rapprot"), &chars("rapport")), 1); // inversion
+        assert_eq!(edit_distance(&chars("factue"), &chars("facture")), 1);
+        assert_eq!(edit_distance(&chars("été"), &chars("ete")), 2);
+    }
+
+    #[test]
+    fn test_rank_candidates() {
+        let vocab = vocabulary(&[("rapport", 5), ("rapports", 9), ("support", 50), ("report", 3)]);
+        let candidates = rank_candidates("rapprot", vocab, 10);
+        let words: Vec<&str> = candidates.iter().map(|c| c.word.as_str()).collect();
+        // Distance d'abord, puis fréquence
+        assert_eq!(words, vec!["rapport", "rapports"]);
+
+        // Mot court: une seule faute tolérée
+        assert_eq!(rank_candidates("cv", vocabulary(&[("cvs", 1)]), 10).len(), 1);
+        assert!(rank_candidates("pdf", vocabulary(&[("pxx", 1)]), 10).is_empty());
+    }
+
+    #[test]
+    fn test_build_corrections() {
+        let candidates = |words: &[(&str, u32, u64)]| {
+            words
+                .iter()
+                .map(|(w, d, f)| Candidate { word: w.to_string(), distance: *d, doc_freq: *f })
+                .collect::<Vec<_>>()
+        };
+        let corrections = vec![
+            ("Rapprot".to_string(), candidates(&[("rapport", 1, 5), ("rapports", 2, 9)])),
+            ("anuel".to_string(), candidates(&[("annuel", 1, 2)])),
+        ];
+
+        let suggestions = build_corrections("Rapprot anuel ext:pdf", &corrections, 3);
+        assert_eq!(suggestions, vec!["rapport annuel ext:pdf", "rapports annuel ext:pdf"]);
+        assert!(build_corrections("test", &[], 3).is_empty());
+    }
+}
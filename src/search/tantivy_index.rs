@@ -5,16 +5,55 @@
 // - Création et ouverture de l'index
 // - Ajout de documents (fichiers) à l'index
 // - Recherche dans l'index avec scoring
+// - Compilation du langage de requête (voir query.rs) en requête Tantivy
 
 use anyhow::{Context, Result};
+use std::collections::{BinaryHeap, HashMap};
+use std::ops::Bound;
 use std::path::Path;
-use tantivy::collector::TopDocs;
-use tantivy::query::{QueryParser, TermQuery};
+use std::sync::{Arc, Mutex};
+use tantivy::collector::{Collector, Count, DocSetCollector, FacetCollector, SegmentCollector, TopDocs};
+use tantivy::columnar::StrColumn;
+use tantivy::query::{AllQuery, BooleanQuery, BoostQuery, ConstScoreQuery, EmptyQuery, FuzzyTermQuery, Occur, PhraseQuery, Query, RangeQuery, RegexQuery, TermQuery, TermSetQuery};
 use tantivy::schema::*;
-use tantivy::tokenizer::{NgramTokenizer, LowerCaser, TextAnalyzer};
-use tantivy::{doc, Index, IndexWriter, TantivyDocument, Term};
+use tantivy::tokenizer::{NgramTokenizer, LowerCaser, TextAnalyzer, TokenStream};
+use tantivy::{DocAddress, DocId, Index, IndexReader, IndexWriter, Order, ReloadPolicy, Score, SegmentOrdinal, SegmentReader, TantivyDocument, Term};
+
+use super::index_manifest::{IndexCompatibility, IndexManifest, INDEX_SCHEMA_VERSION, NGRAM_TOKENIZER};
+use super::index_writer::{DirectWriter, IndexWriterHandle, COMMIT_BATCH_SIZE, COMMIT_INTERVAL};
+use super::autocomplete::{current_word, merge_completions, Completion};
+use super::highlight::Highlighter;
+use super::pattern::{FilenamePattern, PatternMode};
+use super::suggest::{build_corrections, max_distance, rank_candidates, MIN_SUGGEST_LEN};
+use super::word_tokenizer::{word_analyzer, WORD_TOKENIZER};
+use super::query::{parse_query, FieldFilter, QueryNode};
+use super::{FileEntry, FolderCount, SearchPage, SearchResult};
+
+// Ordre de tri des résultats (appliqué par le collector Tantivy)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortBy {
+    Relevance,    // Score Tantivy (défaut)
+    NameAsc,      // A→Z
+    NameDesc,     // Z→A
+    DateAsc,      // Ancien → Récent
+    DateDesc,     // Récent → Ancien
+    SizeAsc,      // Petit → Grand
+    SizeDesc,     // Grand → Petit
+}
 
-use super::SearchResult;
+impl SortBy {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SortBy::Relevance => "Pertinence",
+            SortBy::NameAsc => "Nom (A→Z)",
+            SortBy::NameDesc => "Nom (Z→A)",
+            SortBy::DateAsc => "Date (ancien→récent)",
+            SortBy::DateDesc => "Date (récent→ancien)",
+            SortBy::SizeAsc => "Taille (petit→grand)",
+            SortBy::SizeDesc => "Taille (grand→petit)",
+        }
+    }
+}
 
 // Options de recherche avancée
 #[derive(Debug, Clone)]
@@ -25,6 +64,19 @@ pub struct SearchOptions {
     pub search_in_path: bool,
     pub fuzzy_search: bool,        // Recherche floue (tolère les fautes de frappe)
     pub fuzzy_distance: u8,        // Distance Levenshtein (0-2, défaut 1)
+    // Filtres appliqués dans Tantivy (avant la limite)
+    pub extensions: Vec<String>,          // Extensions autorisées, sans point (vide = toutes)
+    pub excluded_extensions: Vec<String>, // Extensions à écarter, sans point
+    pub size_min: Option<u64>,            // Taille min en octets (incluse)
+    pub size_max: Option<u64>,            // Taille max en octets (incluse)
+    pub modified_after: Option<i64>,      // Timestamp Unix min de modification (inclus)
+    pub sort_by: SortBy,
+    // Interprétation de la requête: langage de requête, glob ou regex sur le nom
+    pub pattern_mode: PatternMode,
+    // Dossiers dans lesquels chercher (sous-dossiers inclus), vide = partout
+    pub scope: Vec<String>,
+    // Scores de frecency par chemin (fichiers souvent ouverts), vide = désactivé
+    pub frecency: Vec<(String, f64)>,
 }
 
 impl Default for SearchOptions {
@@ -36,92 +88,379 @@ impl Default for SearchOptions {
             search_in_path: true,
             fuzzy_search: false,
             fuzzy_distance: 1,
+            extensions: Vec::new(),
+            excluded_extensions: Vec::new(),
+            size_min: None,
+            size_max: None,
+            modified_after: None,
+            sort_by: SortBy::Relevance,
+            pattern_mode: PatternMode::Text,
+            scope: Vec::new(),
+            frecency: Vec::new(),
         }
     }
 }
 
+// Les copies partagent le même index et le même writer (voir writer())
+#[derive(Clone)]
 pub struct SearchIndex {
     index: Index,
     schema: Schema,
+    manifest: IndexManifest,
     path_field: Field,
     filename_field: Field,
+    path_key_field: Field,
+    extension_field: Field,
+    size_field: Field,
+    modified_field: Field,
+    created_field: Field,
+    parent_field: Field,
+    name_sort_field: Field,
+    filename_words_field: Field,
+    name_raw_field: Field,
+    dirs_field: Field,
+    // Reader partagé par toutes les copies (voir reload())
+    reader: IndexReader,
+    // Writer partagé, démarré au premier besoin
+    writer: Arc<Mutex<Option<IndexWriterHandle>>>,
 }
 
 impl SearchIndex {
     // Initialise un nouvel index Tantivy ou ouvre un index existant
     //
     // L'index sera créé dans le dossier spécifié. Si un index existe déjà
-    // à cet emplacement, il sera ouvert pour être réutilisé.
+    // à cet emplacement, il sera ouvert pour être réutilisé, à condition que
+    // son manifeste (xfinder_index.toml) corresponde au schéma actuel et aux
+    // n-grams demandés. Sinon une erreur explicite est retournée: utiliser
+    // IndexManifest::check() en amont pour choisir entre open_existing()
+    // et une reconstruction (rebuild).
     //
-    // Le schéma initial contient deux champs:
+    // Le schéma contient les champs:
     // - path: chemin complet du fichier (TEXT | STORED)
     // - filename: nom du fichier uniquement (TEXT | STORED)
+    // - path_key: chemin brut non tokenisé (STRING, clé de suppression/mise à jour)
+    // - extension: extension en minuscules sans le point (STRING | FAST | STORED)
+    // - size: taille en octets (u64 INDEXED | FAST | STORED)
+    // - modified / created: timestamps Unix (i64 INDEXED | FAST | STORED)
+    // - parent: dossier parent (STRING | FAST | STORED)
+    // - name_sort: nom complet en minuscules (STRING | FAST), clé du tri par nom
+    //   (voir NameOrder)
+    // - filename_words: mots entiers du nom (camelCase, snake_case, chiffres, sans
+    //   accents; TEXT non stocké, voir word_tokenizer.rs)
+    // - name_raw: nom brut non tokenisé (STRING, modes glob/regex, voir pattern.rs)
+    // - dirs: dossier parent en facette hiérarchique (/C:/Users/...): portée
+    //   de recherche (SearchOptions::scope) et répartition par dossier
+    //
+    // Les métadonnées sont stockées dans l'index: les résultats n'ont pas
+    // besoin de stat() et restent affichables si le disque est démonté.
     //
     // min_ngram_size: taille min des n-grams (généralement 2)
     // max_ngram_size: taille max des n-grams (min_ngram_size-max_ngram_size)
     // - Plus petit = indexation rapide mais recherches limitées
     // - Plus grand = indexation lente mais recherches flexibles
     pub fn new(index_dir: &Path, min_ngram_size: usize, max_ngram_size: usize) -> Result<Self> {
+        let expected = IndexManifest::new(min_ngram_size, max_ngram_size);
+
+        match IndexManifest::check(index_dir, &expected)? {
+            IndexCompatibility::Missing => Self::create(index_dir, &expected),
+            IndexCompatibility::UpToDate => Self::open(index_dir, &expected),
+            IndexCompatibility::SettingsChanged { current } => anyhow::bail!(
+                "Index construit avec d'autres paramètres ({}): reconstruction nécessaire",
+                current.describe_changes(&expected)
+            ),
+            IndexCompatibility::SchemaOutdated { found } => anyhow::bail!(
+                "Schéma d'index obsolète (v{} → v{}): reconstruction nécessaire",
+                found.unwrap_or(1),
+                INDEX_SCHEMA_VERSION
+            ),
+        }
+    }
+
+    // Ouvre un index existant avec SES paramètres (ceux du manifeste), même
+    // s'ils diffèrent de la configuration actuelle. Permet de continuer à
+    // servir les recherches pendant une reconstruction en arrière-plan.
+    pub fn open_existing(index_dir: &Path) -> Result<Self> {
+        let manifest = IndexManifest::load(index_dir)?
+            .context("Manifeste d'index introuvable")?;
+        if manifest.schema_version != INDEX_SCHEMA_VERSION {
+            anyhow::bail!(
+                "Schéma d'index obsolète (v{} → v{}): reconstruction nécessaire",
+                manifest.schema_version,
+                INDEX_SCHEMA_VERSION
+            );
+        }
+        Self::open(index_dir, &manifest)
+    }
+
+    // Crée un index vide avec le schéma actuel et écrit son manifeste
+    fn create(index_dir: &Path, manifest: &IndexManifest) -> Result<Self> {
         std::fs::create_dir_all(index_dir)
             .context("Impossible de créer le dossier d'index")?;
 
-        // Essayer d'ouvrir un index existant d'abord
-        let (index, schema, path_field, filename_field) = if index_dir.join("meta.json").exists() {
-            // Index existe - ouvrir et récupérer son schéma
-            let index = Index::open_in_dir(index_dir)
-                .context("Impossible d'ouvrir l'index existant")?;
-            let schema = index.schema();
-            let path_field = schema.get_field("path")
-                .context("Champ 'path' introuvable dans le schéma")?;
-            let filename_field = schema.get_field("filename")
-                .context("Champ 'filename' introuvable dans le schéma")?;
-            (index, schema, path_field, filename_field)
-        } else {
-            // Créer un nouvel index avec schéma n-gram
-            let mut schema_builder = Schema::builder();
+        // Créer un nouvel index avec schéma n-gram
+        let mut schema_builder = Schema::builder();
+
+        let text_opts = TextOptions::default()
+            .set_indexing_options(
+                TextFieldIndexing::default()
+                    .set_tokenizer(NGRAM_TOKENIZER)
+                    .set_index_option(IndexRecordOption::WithFreqsAndPositions)
+            )
+            .set_stored();
+
+        schema_builder.add_text_field("path", text_opts.clone());
+        schema_builder.add_text_field("filename", text_opts);
+        schema_builder.add_text_field("path_key", STRING);
+        schema_builder.add_text_field("extension", STRING | FAST | STORED);
+        schema_builder.add_u64_field("size", INDEXED | FAST | STORED);
+        schema_builder.add_i64_field("modified", INDEXED | FAST | STORED);
+        schema_builder.add_i64_field("created", INDEXED | FAST | STORED);
+        schema_builder.add_text_field("parent", STRING | FAST | STORED);
+        schema_builder.add_text_field("name_sort", STRING | FAST);
+        // Mots entiers (voir word_tokenizer.rs): bonus de score pour les
+        // mots complets et dictionnaire des suggestions/complétions
+        schema_builder.add_text_field(
+            "filename_words",
+            TextOptions::default().set_indexing_options(
+                TextFieldIndexing::default()
+                    .set_tokenizer(WORD_TOKENIZER)
+                    .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+            ),
+        );
+        schema_builder.add_text_field("name_raw", STRING);
+        schema_builder.add_facet_field("dirs", FacetOptions::default());
+        let schema = schema_builder.build();
 
-            let text_opts = TextOptions::default()
-                .set_indexing_options(
-                    TextFieldIndexing::default()
-                        .set_tokenizer("ngram3")
-                        .set_index_option(IndexRecordOption::WithFreqsAndPositions)
-                )
-                .set_stored();
+        let index = Index::create_in_dir(index_dir, schema)
+            .context("Impossible de créer l'index")?;
+        manifest.save(index_dir)?;
 
-            let path_field = schema_builder.add_text_field("path", text_opts.clone());
-            let filename_field = schema_builder.add_text_field("filename", text_opts);
-            let schema = schema_builder.build();
+        Self::from_index(index, manifest)
+    }
 
-            let index = Index::create_in_dir(index_dir, schema.clone())
-                .context("Impossible de créer l'index")?;
+    // Ouvre un index existant (le manifeste a déjà été vérifié)
+    fn open(index_dir: &Path, manifest: &IndexManifest) -> Result<Self> {
+        let index = Index::open_in_dir(index_dir)
+            .context("Impossible d'ouvrir l'index existant")?;
+        Self::from_index(index, manifest)
+    }
 
-            (index, schema, path_field, filename_field)
-        };
+    fn from_index(index: Index, manifest: &IndexManifest) -> Result<Self> {
+        let schema = index.schema();
+        let path_field = schema.get_field("path")
+            .context("Champ 'path' introuvable dans le schéma")?;
+        let filename_field = schema.get_field("filename")
+            .context("Champ 'filename' introuvable dans le schéma")?;
+        let path_key_field = Self::required_field(&schema, "path_key", false)?;
+        let extension_field = Self::required_field(&schema, "extension", true)?;
+        let size_field = Self::required_field(&schema, "size", true)?;
+        let modified_field = Self::required_field(&schema, "modified", true)?;
+        let created_field = Self::required_field(&schema, "created", true)?;
+        let parent_field = Self::required_field(&schema, "parent", true)?;
+        let name_sort_field = Self::required_field(&schema, "name_sort", true)?;
+        let filename_words_field = Self::required_field(&schema, "filename_words", false)?;
+        let name_raw_field = Self::required_field(&schema, "name_raw", false)?;
+        let dirs_field = Self::required_field(&schema, "dirs", false)?;
 
         // CRITIQUE: Enregistrer le tokenizer n-gram À CHAQUE FOIS
         // Même si on ouvre un index existant, le tokenizer doit être enregistré
-        // car il n'est pas persisté sur disque
+        // car il n'est pas persisté sur disque: ses paramètres viennent du
+        // manifeste, pour tokeniser les requêtes comme à l'indexation
         //
         // N-grams min-max: configurable via l'UI
         // - Recommandation: 2-20 pour bon équilibre vitesse/flexibilité
         // - Pour fragments ultra-courts: commencer à 1
         // - Pour noms complets longs: augmenter max ou utiliser "Match exact"
         let ngram_tokenizer = TextAnalyzer::builder(
-            NgramTokenizer::new(min_ngram_size, max_ngram_size, false).unwrap()
+            NgramTokenizer::new(manifest.min_ngram_size, manifest.max_ngram_size, false)
+                .context("Paramètres n-gram invalides")?
         )
         .filter(LowerCaser)
         .build();
 
-        index.tokenizers().register("ngram3", ngram_tokenizer);
+        index.tokenizers().register(NGRAM_TOKENIZER, ngram_tokenizer);
+        index.tokenizers().register(WORD_TOKENIZER, word_analyzer());
+
+        // Un seul reader pour toute la durée de vie de l'index
+        // - OnCommitWithDelay: suit les commits d'autres processus (meta.json)
+        // - les commits du writer partagé rechargent immédiatement (reload())
+        // Le reader sert des Searcher (snapshots immuables, thread-safe):
+        // l'UI, une CLI ou un serveur IPC peuvent chercher en parallèle
+        // sur des copies de SearchIndex sans rien reconstruire
+        // Pas de pool de searchers à gérer: depuis tantivy 0.22 le reader
+        // garde un seul snapshot par commit (ArcSwap), que chaque requête
+        // récupère sans verrou, quel que soit le nombre de threads
+        let reader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::OnCommitWithDelay)
+            .try_into()
+            .context("Impossible de créer le reader")?;
 
         Ok(Self {
             index,
             schema,
+            manifest: manifest.clone(),
             path_field,
             filename_field,
+            path_key_field,
+            extension_field,
+            size_field,
+            modified_field,
+            created_field,
+            parent_field,
+            name_sort_field,
+            filename_words_field,
+            name_raw_field,
+            dirs_field,
+            reader,
+            writer: Arc::new(Mutex::new(None)),
         })
     }
 
+    // Reconstruit un index complet dans target_dir à partir des documents
+    // stockés dans source_dir, avec le schéma actuel et les n-grams donnés.
+    //
+    // L'index source peut être d'une ancienne version: seuls path et filename
+    // sont indispensables. Les métadonnées absentes sont relues sur le disque.
+    // Aucun rescan des dossiers n'est nécessaire, et l'index source reste
+    // utilisable pendant la reconstruction (il est seulement lu).
+    //
+    // progress(traités, total) est appelé régulièrement.
+    pub fn rebuild(
+        source_dir: &Path,
+        target_dir: &Path,
+        min_ngram_size: usize,
+        max_ngram_size: usize,
+        mut progress: impl FnMut(usize, usize),
+    ) -> Result<usize> {
+        let source = Index::open_in_dir(source_dir)
+            .context("Impossible d'ouvrir l'index à reconstruire")?;
+        let source_schema = source.schema();
+        let source_path = source_schema.get_field("path")
+            .context("Champ 'path' introuvable dans l'index à reconstruire")?;
+        let source_filename = source_schema.get_field("filename")
+            .context("Champ 'filename' introuvable dans l'index à reconstruire")?;
+        let stored_field = |name: &str| {
+            source_schema
+                .get_field(name)
+                .ok()
+                .filter(|field| source_schema.get_field_entry(*field).is_stored())
+        };
+        let source_size = stored_field("size");
+        let source_modified = stored_field("modified");
+        let source_created = stored_field("created");
+
+        let reader = source.reader().context("Impossible de lire l'index à reconstruire")?;
+        let searcher = reader.searcher();
+        let total = searcher.num_docs() as usize;
+
+        // Repartir d'un dossier propre (reconstruction précédente interrompue)
+        if target_dir.exists() {
+            std::fs::remove_dir_all(target_dir)
+                .context("Impossible de nettoyer le dossier de reconstruction")?;
+        }
+        let target = Self::create(target_dir, &IndexManifest::new(min_ngram_size, max_ngram_size))?;
+        let mut writer = target.create_writer()?;
+
+        let mut count = 0;
+        let mut seen_paths = std::collections::HashSet::new();
+
+        for segment_reader in searcher.segment_readers() {
+            let store = segment_reader.get_store_reader(1)?;
+            for doc in store.iter::<TantivyDocument>(segment_reader.alive_bitset()) {
+                let doc = doc?;
+                let text = |field: Field| doc.get_first(field).and_then(|v| v.as_str()).unwrap_or("").to_string();
+                let path = text(source_path);
+                let filename = text(source_filename);
+
+                // Les anciens index pouvaient contenir des doublons
+                if path.is_empty() || !seen_paths.insert(path.clone()) {
+                    continue;
+                }
+
+                let entry = match source_size {
+                    Some(size_field) => FileEntry {
+                        size: doc.get_first(size_field).and_then(|v| v.as_u64()).unwrap_or(0),
+                        modified: source_modified.and_then(|f| doc.get_first(f)).and_then(|v| v.as_i64()),
+                        created: source_created.and_then(|f| doc.get_first(f)).and_then(|v| v.as_i64()),
+                        path,
+                        filename,
+                    },
+                    None => FileEntry::from_path(&path, &filename),
+                };
+                target.add_entry(&mut writer, &entry)?;
+
+                count += 1;
+                if count % 1000 == 0 {
+                    progress(count, total);
+                }
+            }
+        }
+
+        writer.commit().context("Impossible de valider l'index reconstruit")?;
+        progress(count, total);
+        Ok(count)
+    }
+
+    // Remplace l'index de index_dir par celui reconstruit dans rebuilt_dir
+    // Toutes les instances ouvertes sur index_dir doivent avoir été fermées
+    // (verrous de fichiers sous Windows)
+    pub fn replace_with_rebuilt(index_dir: &Path, rebuilt_dir: &Path) -> Result<()> {
+        let backup_dir = index_dir.with_extension("old");
+        if backup_dir.exists() {
+            std::fs::remove_dir_all(&backup_dir)?;
+        }
+        if index_dir.exists() {
+            std::fs::rename(index_dir, &backup_dir)
+                .context("Impossible de déplacer l'ancien index")?;
+        }
+        if let Err(e) = std::fs::rename(rebuilt_dir, index_dir) {
+            // Restaurer l'ancien index pour ne pas rester sans index
+            let _ = std::fs::rename(&backup_dir, index_dir);
+            return Err(e).context("Impossible d'installer l'index reconstruit");
+        }
+        let _ = std::fs::remove_dir_all(&backup_dir);
+        Ok(())
+    }
+
+    // Supprime les fichiers Tantivy d'un index situé dans un dossier partagé
+    // (anciennes versions: index directement dans ~/.xfinder_index, à côté de
+    // la base SQLite et de la config). Les autres fichiers sont conservés.
+    pub fn remove_index_files(dir: &Path) -> Result<()> {
+        let segment_files: Vec<std::path::PathBuf> = {
+            let index = Index::open_in_dir(dir)?;
+            index
+                .searchable_segment_metas()?
+                .iter()
+                .flat_map(|meta| meta.list_files())
+                .collect()
+        };
+
+        for file in segment_files {
+            let _ = std::fs::remove_file(dir.join(file));
+        }
+        for file in ["meta.json", ".managed.json", ".tantivy-meta.lock", ".tantivy-writer.lock"] {
+            let _ = std::fs::remove_file(dir.join(file));
+        }
+        Ok(())
+    }
+
+    // Paramètres avec lesquels l'index a été construit
+    pub fn manifest(&self) -> &IndexManifest {
+        &self.manifest
+    }
+
+    // Récupère un champ du schéma, en vérifiant qu'il est fast si nécessaire
+    fn required_field(schema: &Schema, name: &str, fast: bool) -> Result<Field> {
+        let field = schema.get_field(name).with_context(|| {
+            format!("Schéma d'index obsolète (champ '{}' manquant): relancez une indexation complète", name)
+        })?;
+        if fast && !schema.get_field_entry(field).is_fast() {
+            anyhow::bail!("Schéma d'index obsolète (champ '{}' non fast): relancez une indexation complète", name);
+        }
+        Ok(field)
+    }
+
     // Ajoute un fichier à l'index via le writer fourni
     //
     // Cette méthode crée un document Tantivy avec les informations du fichier
@@ -132,11 +471,43 @@ impl SearchIndex {
     // - writer: Le IndexWriter actif pour cette session d'indexation
     // - path: Chemin complet du fichier (ex: C:\Users\...\document.pdf)
     // - filename: Nom du fichier uniquement (ex: document.pdf)
+    //
+    // Les métadonnées (taille, dates) sont lues sur le disque. Pendant une
+    // indexation complète, préférer add_entry() avec celles du scanner.
     pub fn add_file(&self, writer: &mut IndexWriter, path: &str, filename: &str) -> Result<()> {
-        let doc = doc!(
-            self.path_field => path,
-            self.filename_field => filename,
-        );
+        self.add_entry(writer, &FileEntry::from_path(path, filename))
+    }
+
+    // Ajoute un fichier dont les métadonnées sont déjà connues (pas de stat)
+    pub fn add_entry(&self, writer: &mut IndexWriter, entry: &FileEntry) -> Result<()> {
+        let mut doc = TantivyDocument::default();
+        doc.add_text(self.path_field, &entry.path);
+        doc.add_text(self.filename_field, &entry.filename);
+        doc.add_text(self.path_key_field, &entry.path);
+
+        if let Some(ext) = Path::new(&entry.filename).extension().and_then(|e| e.to_str()) {
+            doc.add_text(self.extension_field, ext.to_lowercase());
+        }
+        if let Some(parent) = Path::new(&entry.path).parent() {
+            doc.add_text(self.parent_field, parent.to_string_lossy());
+        }
+
+        doc.add_u64(self.size_field, entry.size);
+        if let Some(modified) = entry.modified {
+            doc.add_i64(self.modified_field, modified);
+        }
+        if let Some(created) = entry.created {
+            doc.add_i64(self.created_field, created);
+        }
+        doc.add_text(self.name_sort_field, entry.filename.to_lowercase());
+        doc.add_text(self.filename_words_field, &entry.filename);
+        doc.add_text(self.name_raw_field, &entry.filename);
+        // Facette du dossier parent: ses ancêtres sont indexés avec lui
+        let components = path_components(&entry.path);
+        if components.len() > 1 {
+            doc.add_facet(self.dirs_field, Facet::from_path(&components[..components.len() - 1]));
+        }
+
         writer.add_document(doc)?;
         Ok(())
     }
@@ -146,25 +517,81 @@ impl SearchIndex {
     // Le writer alloue 200MB de RAM pour le buffer d'indexation (optimisé).
     // Plus de RAM = moins de commits intermédiaires = meilleure performance
     // N'oublie pas d'appeler writer.commit() pour persister les changements!
-    pub fn create_writer(&self) -> Result<IndexWriter> {
+    // (le commit recharge aussi le reader: les recherches le voient aussitôt)
+    //
+    // Tantivy n'autorise qu'un writer à la fois: sur un index en service,
+    // passer par writer() (partagé) plutôt que par cette méthode.
+    pub fn create_writer(&self) -> Result<DirectWriter> {
         let writer = self
             .index
             .writer(200_000_000) // 200MB pour optimiser les performances
             .context("Impossible de créer le writer")?;
-        Ok(writer)
+        Ok(DirectWriter::new(writer, self.reader.clone()))
     }
 
-    // Efface tous les documents de l'index
-    // Utile pour réinitialiser complètement avant une nouvelle indexation
-    pub fn clear(&self) -> Result<()> {
-        let mut writer = self.create_writer()?;
-        writer.delete_all_documents()?;
-        writer.commit()?;
-        Ok(())
+    // Writer partagé de l'index (voir index_writer.rs)
+    //
+    // Démarré au premier appel puis réutilisé par toutes les copies de
+    // SearchIndex: indexation complète et watchdog écrivent sans se disputer
+    // le verrou. Les opérations sont validées par lots, flush() force le commit.
+    pub fn writer(&self) -> Result<IndexWriterHandle> {
+        let mut slot = self.writer.lock().map_err(|_| anyhow::anyhow!("Writer de l'index indisponible"))?;
+        if let Some(ref handle) = *slot {
+            return Ok(handle.clone());
+        }
+
+        let handle = IndexWriterHandle::start(self.detached(), self.create_writer()?.into_inner(), COMMIT_BATCH_SIZE, COMMIT_INTERVAL);
+        *slot = Some(handle.clone());
+        Ok(handle)
+    }
+
+    // Valide les opérations en attente du writer partagé et attend le commit
+    pub fn flush(&self) -> Result<()> {
+        let handle = self.writer.lock().ok().and_then(|slot| slot.clone());
+        match handle {
+            Some(handle) => handle.flush(),
+            None => Ok(()), // Rien n'a été écrit
+        }
+    }
+
+    // Valide les opérations en attente puis arrête le writer partagé de
+    // toutes les copies de SearchIndex: plus aucune écriture ni fichier
+    // ouvert par le writer (avant de remplacer les fichiers de l'index)
+    pub fn close_writer(&self) -> Result<()> {
+        let handle = self.writer.lock().ok().and_then(|mut slot| slot.take());
+        match handle {
+            Some(handle) => {
+                let result = handle.flush();
+                handle.stop();
+                result
+            }
+            None => Ok(()),
+        }
     }
 
-    // Supprime COMPLÈTEMENT l'index (dossier + schéma + tout)
-    // Nécessaire pour changer le tokenizer
+    // Copie sans writer partagé, pour le thread du writer lui-même
+    // (sinon le writer se garderait en vie)
+    pub(crate) fn detached(&self) -> Self {
+        Self {
+            writer: Arc::new(Mutex::new(None)),
+            ..self.clone()
+        }
+    }
+
+    // Terme identifiant un document par son chemin (suppression/mise à jour)
+    pub(crate) fn path_term(&self, path: &str) -> Term {
+        Term::from_field_text(self.path_key_field, path)
+    }
+
+    // Terme de la facette d'un dossier: tous les fichiers dessous, tous niveaux
+    // (None pour la racine, qui désignerait tout l'index)
+    pub(crate) fn dir_term(&self, dir: &str) -> Option<Term> {
+        let components = path_components(dir);
+        (!components.is_empty()).then(|| Term::from_facet(self.dirs_field, &Facet::from_path(components)))
+    }
+
+    // Supprime COMPLÈTEMENT l'index (dossier + schéma + manifeste)
+    // Le dossier doit être dédié à l'index Tantivy
     pub fn delete_completely(index_dir: &std::path::Path) -> Result<()> {
         if index_dir.exists() {
             std::fs::remove_dir_all(index_dir)?;
@@ -174,50 +601,76 @@ impl SearchIndex {
 
     // Compte le nombre de documents dans l'index
     pub fn count_documents(&self) -> Result<usize> {
-        let reader = self.index.reader()?;
-        let searcher = reader.searcher();
-        Ok(searcher.num_docs() as usize)
+        Ok(self.reader.searcher().num_docs() as usize)
+    }
+
+    // Recharge le reader pour voir le dernier commit
+    //
+    // Automatique après les commits du writer partagé et de create_writer().
+    // Les commits d'un autre processus sont vus sans appel, dès la détection
+    // de la modification de meta.json (quelques centaines de ms).
+    pub fn reload(&self) -> Result<()> {
+        self.reader.reload().context("Impossible de recharger le reader")?;
+        Ok(())
     }
 
     // Supprime un fichier de l'index par son chemin
+    // L'opération passe par le writer partagé: validée au prochain lot (ou flush())
     pub fn delete_file_by_path(&self, file_path: &str) -> Result<()> {
-        let mut writer = self.create_writer()?;
-        let term = Term::from_field_text(self.path_field, file_path);
-        writer.delete_term(term);
-        writer.commit()?;
-        Ok(())
+        self.writer()?.delete(file_path)
     }
 
     // Met à jour le chemin d'un fichier (pour les déplacements)
     // Supprime l'ancien chemin et ajoute le nouveau
     pub fn update_file_path(&self, old_path: &str, new_path: &str, filename: &str) -> Result<()> {
-        let mut writer = self.create_writer()?;
-
-        // Supprimer l'ancien
-        let term = Term::from_field_text(self.path_field, old_path);
-        writer.delete_term(term);
-
-        // Ajouter le nouveau
-        self.add_file(&mut writer, new_path, filename)?;
-
-        writer.commit()?;
-        Ok(())
+        self.writer()?.rename(old_path, FileEntry::from_path(new_path, filename))
     }
 
     // Met à jour un fichier existant (pour les modifications)
     // Garde le même path mais rafraîchit les métadonnées
     pub fn update_file(&self, path: &str, filename: &str) -> Result<()> {
-        let mut writer = self.create_writer()?;
+        self.writer()?.update(FileEntry::from_path(path, filename))
+    }
 
-        // Supprimer l'ancien
-        let term = Term::from_field_text(self.path_field, path);
-        writer.delete_term(term);
+    // Chemins indexés sous un dossier, tous niveaux (opérations validées seulement)
+    pub fn paths_under(&self, dir: &str) -> Result<Vec<String>> {
+        let Some(term) = self.dir_term(dir) else {
+            return Ok(Vec::new());
+        };
+        let searcher = self.reader.searcher();
+        let docs = searcher.search(&TermQuery::new(term, IndexRecordOption::Basic), &DocSetCollector)?;
+        let mut paths = Vec::with_capacity(docs.len());
+        for doc_address in docs {
+            let doc: TantivyDocument = searcher.doc(doc_address)?;
+            if let Some(path) = doc.get_first(self.path_field).and_then(|v| v.as_str()) {
+                paths.push(path.to_string());
+            }
+        }
+        Ok(paths)
+    }
 
-        // Ré-ajouter avec nouvelles métadonnées
-        self.add_file(&mut writer, path, filename)?;
+    // Dossier déplacé ou renommé: réécrit le chemin de tous ses fichiers
+    // Les opérations en attente sont validées avant, pour n'en oublier aucun.
+    // Retourne le nombre de fichiers déplacés.
+    pub fn rename_dir(&self, from: &str, to: &str) -> Result<usize> {
+        self.flush()?;
+        let writer = self.writer()?;
+        let mut moved = 0;
+        for old in self.paths_under(from)? {
+            let Ok(relative) = Path::new(&old).strip_prefix(from) else {
+                continue;
+            };
+            let new = Path::new(to).join(relative);
+            let filename = new.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+            writer.rename(&old, FileEntry::from_path(&new.to_string_lossy(), &filename))?;
+            moved += 1;
+        }
+        Ok(moved)
+    }
 
-        writer.commit()?;
-        Ok(())
+    // Supprime tous les fichiers sous un dossier (tous niveaux)
+    pub fn delete_dir(&self, dir: &str) -> Result<()> {
+        self.writer()?.delete_dir(dir)
     }
 
     // Recherche ultra-flexible: marche avec n'importe quel fragment
@@ -225,26 +678,187 @@ impl SearchIndex {
     // Les n-grams (min-max) sont configurables via l'UI avant l'indexation
     // Pour les noms complets très longs: utiliser l'option "Match exact"
     //
-    // Options disponibles:
+    // La requête suit le langage de query.rs (ext:, size:, modified:, path:,
+    // name:, -exclusion, "phrase", OR, parenthèses). Elle est compilée en une
+    // seule requête Tantivy: filtres et logique booléenne sont appliqués
+    // AVANT la limite. Une requête mal formée retourne une erreur explicite.
+    //
+    // Options disponibles (appliquées aux mots libres et phrases):
     // - exact_match: recherche exacte sans n-grams
     // - case_sensitive: respecter la casse
     // - search_in_filename/search_in_path: limiter la zone de recherche
+    // - pattern_mode: glob ou regex sur le nom (voir pattern.rs), à la place
+    //   du langage de requête; un motif invalide retourne une QueryParseError
+    //
+    // Retourne les limit premiers résultats (première page de search_page)
     pub fn search(&self, query_str: &str, limit: usize, options: SearchOptions) -> Result<Vec<SearchResult>> {
-        let reader = self
-            .index
-            .reader()
-            .context("Impossible de créer le reader")?;
+        Ok(self.search_page(query_str, 0, limit, options)?.results)
+    }
 
-        let searcher = reader.searcher();
+    // Une page de résultats: page_size résultats à partir du rang offset,
+    // et le nombre total de correspondances (collector Count, même passe)
+    //
+    // Tantivy ne garde que offset + page_size documents pendant la collecte:
+    // mémoire et latence ne dépendent pas du nombre total de correspondances.
+    pub fn search_page(&self, query_str: &str, offset: usize, page_size: usize, options: SearchOptions) -> Result<SearchPage> {
+        // Snapshot du dernier commit chargé (pas de reader à reconstruire)
+        let searcher = self.reader.searcher();
+
+        let Some((query, highlighter)) = self.build_query(&searcher, query_str, &options)? else {
+            return Ok(SearchPage::default());
+        };
+        let query = self.apply_filters(query, &options);
+        let query = self.apply_frecency(query, &options);
+
+        // Lance la recherche et récupère la page demandée + le total
+        // Tri par pertinence (score) ou directement sur les fast fields
+        let page = TopDocs::with_limit(page_size.max(1)).and_offset(offset);
+        let (total, top_docs): (usize, Vec<(f32, DocAddress)>) = match options.sort_by {
+            SortBy::Relevance => searcher
+                .search(&query, &(Count, page))
+                .context("Erreur lors de la recherche")?,
+            SortBy::NameAsc | SortBy::NameDesc => {
+                let collector = NameOrder {
+                    field: self.schema.get_field_name(self.name_sort_field).to_string(),
+                    offset,
+                    keep: offset + page_size.max(1),
+                    ascending: options.sort_by == SortBy::NameAsc,
+                };
+                let (total, docs) = searcher
+                    .search(&query, &(Count, collector))
+                    .context("Erreur lors de la recherche")?;
+                (total, docs.into_iter().map(|(_, address)| (0.0, address)).collect())
+            }
+            SortBy::DateAsc | SortBy::DateDesc => {
+                self.search_sorted::<i64>(&searcher, &query, page, self.modified_field, options.sort_by == SortBy::DateAsc)?
+            }
+            SortBy::SizeAsc | SortBy::SizeDesc => {
+                self.search_sorted::<u64>(&searcher, &query, page, self.size_field, options.sort_by == SortBy::SizeAsc)?
+            }
+        };
 
-        // Préparer la requête selon les options
-        let clean_query = if options.case_sensitive {
-            query_str.trim().to_string()
-        } else {
-            query_str.trim().to_lowercase()
+        // Convertir les résultats Tantivy en SearchResult (champs stockés, sans stat)
+        // On déduplique par chemin pour éviter les doublons: la page peut alors
+        // être plus courte que page_size, la suivante commence à offset + consumed
+        // Les fragments correspondant à la requête sont repérés pour le surlignage
+        let mut results = Vec::new();
+        let mut seen_paths = std::collections::HashSet::new();
+        let top_docs: Vec<(f32, DocAddress)> = top_docs.into_iter().take(page_size).collect();
+        let consumed = top_docs.len();
+
+        for (score, doc_address) in top_docs {
+            let retrieved_doc: TantivyDocument = searcher.doc(doc_address)?;
+            let path = retrieved_doc
+                .get_first(self.path_field)
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string();
+            let filename = retrieved_doc
+                .get_first(self.filename_field)
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string();
+
+            // Skip si déjà vu
+            if !seen_paths.insert(path.clone()) {
+                continue;
+            }
+
+            let size = retrieved_doc.get_first(self.size_field).and_then(|v| v.as_u64()).unwrap_or(0);
+            let modified = retrieved_doc.get_first(self.modified_field).and_then(|v| v.as_i64());
+            let created = retrieved_doc.get_first(self.created_field).and_then(|v| v.as_i64());
+
+            let mut result = SearchResult::from_index(path, filename, score, size, modified, created);
+            result.filename_matches = highlighter.filename_ranges(&result.filename);
+            result.path_matches = highlighter.path_ranges(&result.path);
+            results.push(result);
+        }
+
+        Ok(SearchPage { results, total, consumed })
+    }
+
+    // Requête Tantivy (sans filtres) et surlignage correspondant à la saisie
+    // None si la saisie ne contient aucun critère
+    fn build_query(
+        &self,
+        searcher: &tantivy::Searcher,
+        query_str: &str,
+        options: &SearchOptions,
+    ) -> Result<Option<(Box<dyn Query>, Highlighter)>> {
+        // Modes glob / regex: motif sur le nom, sans langage de requête
+        Ok(Some(match options.pattern_mode {
+            PatternMode::Text => {
+                // Parser la requête en AST
+                let ast = parse_query(query_str)?;
+                if ast.is_empty() {
+                    return Ok(None);
+                }
+                let query = self.compile_node(&ast, &self.search_fields(options), options)?;
+                (query, Highlighter::from_query(&ast, options))
+            }
+            mode => {
+                if query_str.trim().is_empty() {
+                    return Ok(None);
+                }
+                let pattern = FilenamePattern::compile(query_str, mode, options.case_sensitive)?;
+                (self.pattern_query(searcher, &pattern)?, Highlighter::from_pattern(pattern))
+            }
+        }))
+    }
+
+    // Répartition des résultats par dossier (au plus limit dossiers, les plus
+    // fournis d'abord)
+    //
+    // Les dossiers listés sont les sous-dossiers du premier niveau où les
+    // résultats se séparent: si tout est sous /home/user/work, on descend
+    // directement jusque-là. Avec un seul dossier dans options.scope, la
+    // répartition part de ce dossier.
+    pub fn folder_counts(&self, query_str: &str, limit: usize, options: &SearchOptions) -> Result<Vec<FolderCount>> {
+        let searcher = self.reader.searcher();
+        let Some((query, _)) = self.build_query(&searcher, query_str, options)? else {
+            return Ok(Vec::new());
         };
+        let query = self.apply_filters(query, options);
+        let field_name = self.schema.get_field_name(self.dirs_field);
+
+        let mut parent = match options.scope.as_slice() {
+            [dir] => Facet::from_path(path_components(dir)),
+            _ => Facet::root(),
+        };
+        let mut total = searcher.search(&query, &Count)? as u64;
+
+        loop {
+            let mut collector = FacetCollector::for_field(field_name);
+            collector.add_facet(parent.clone());
+            let counts = searcher.search(&query, &collector)?;
+            let mut children: Vec<(Facet, u64)> = counts
+                .get(parent.clone())
+                .map(|(facet, count)| (facet.clone(), count))
+                .collect();
+
+            // Un seul sous-dossier contient tous les résultats: descendre
+            if let [(child, count)] = children.as_slice() {
+                if *count == total {
+                    parent = child.clone();
+                    total = *count;
+                    continue;
+                }
+            }
+
+            children.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            return Ok(children
+                .into_iter()
+                .take(limit)
+                .map(|(facet, count)| FolderCount {
+                    path: facet_to_path(&facet),
+                    count,
+                })
+                .collect());
+        }
+    }
 
-        // Déterminer les champs à rechercher
+    // Champs des mots libres selon les options (les deux si aucun n'est coché)
+    fn search_fields(&self, options: &SearchOptions) -> Vec<Field> {
         let mut search_fields = Vec::new();
         if options.search_in_filename {
             search_fields.push(self.filename_field);
@@ -252,83 +866,551 @@ impl SearchIndex {
         if options.search_in_path {
             search_fields.push(self.path_field);
         }
-
-        // Si aucun champ sélectionné, chercher dans les deux par défaut
         if search_fields.is_empty() {
             search_fields.push(self.filename_field);
             search_fields.push(self.path_field);
         }
+        search_fields
+    }
 
-        // Construire la requête selon le mode (exact, fuzzy ou flexible)
-        let query: Box<dyn tantivy::query::Query> = if options.fuzzy_search {
-            // Mode fuzzy: utiliser FuzzyTermQuery pour tolérer les fautes de frappe
-            use tantivy::query::{BooleanQuery, Occur, FuzzyTermQuery};
+    // Requête pour un motif glob/regex sur le nom brut
+    //
+    // Automate Tantivy sur le dictionnaire de name_raw si le motif s'y prête,
+    // sinon les noms du dictionnaire sont testés avec le crate regex et les
+    // correspondances regroupées en TermSetQuery (filtres, tri et limite
+    // restent appliqués par Tantivy)
+    fn pattern_query(&self, searcher: &tantivy::Searcher, pattern: &FilenamePattern) -> Result<Box<dyn Query>> {
+        if let Some(automaton) = pattern.automaton() {
+            if let Ok(query) = RegexQuery::from_pattern(automaton, self.name_raw_field) {
+                return Ok(Box::new(query));
+            }
+        }
 
-            let fuzzy_queries: Vec<(Occur, Box<dyn tantivy::query::Query>)> = search_fields
-                .iter()
-                .map(|field| {
-                    let term = Term::from_field_text(*field, &clean_query);
-                    let fuzzy_query = FuzzyTermQuery::new(term, options.fuzzy_distance, true);
-                    (Occur::Should, Box::new(fuzzy_query) as Box<dyn tantivy::query::Query>)
-                })
-                .collect();
+        let mut terms = Vec::new();
+        for segment_reader in searcher.segment_readers() {
+            let inverted_index = segment_reader.inverted_index(self.name_raw_field)?;
+            let mut stream = inverted_index.terms().stream()?;
+            while stream.advance() {
+                if let Ok(name) = std::str::from_utf8(stream.key()) {
+                    if pattern.is_match(name) {
+                        terms.push(Term::from_field_text(self.name_raw_field, name));
+                    }
+                }
+            }
+        }
+        Ok(Box::new(TermSetQuery::new(terms)))
+    }
+
+    // Suggestions "Vouliez-vous dire" pour une requête sans résultat
+    //
+    // Chaque mot libre de la requête qui ne correspond à aucun nom ni chemin
+    // est comparé au vocabulaire des noms de fichiers (champ filename_words,
+    // voir suggest.rs). Retourne jusqu'à limit requêtes corrigées, prêtes à
+    // être relancées telles quelles. Indépendant de options.fuzzy_search.
+    pub fn suggest(&self, query_str: &str, limit: usize) -> Result<Vec<String>> {
+        let ast = parse_query(query_str)?;
+        let mut words = Vec::new();
+        collect_free_words(&ast, &mut words);
+
+        let searcher = self.reader.searcher();
+        let mut corrections = Vec::new();
+        for word in words {
+            let lower = word.to_lowercase();
+            if lower.chars().count() < MIN_SUGGEST_LEN || self.word_matches(&searcher, &lower)? {
+                continue;
+            }
+            let Some(normalized) = self.normalize_word(&word)? else {
+                continue;
+            };
+            let candidates = rank_candidates(&normalized, self.filename_vocabulary(&searcher, &normalized)?, limit);
+            corrections.push((word, candidates));
+        }
+
+        Ok(build_corrections(query_str, &corrections, limit))
+    }
+
+    // Complétions de la requête en cours de saisie (voir autocomplete.rs)
+    //
+    // history: requêtes précédentes (Database::get_top_searches), les plus
+    // fréquentes d'abord. Le dernier mot est complété à partir des mots des
+    // noms de fichiers: seul le dictionnaire de termes est lu, pas de recherche.
+    pub fn complete(&self, query: &str, history: &[(String, u32)], limit: usize) -> Result<Vec<Completion>> {
+        let words = match current_word(query) {
+            Some((_, prefix)) => match self.normalize_word(prefix)? {
+                Some(prefix) => self.complete_word(&prefix, limit)?,
+                None => Vec::new(),
+            },
+            None => Vec::new(),
+        };
+        Ok(merge_completions(query, history, &words, limit))
+    }
+
+    // Mots des noms de fichiers commençant par prefix, les plus fréquents d'abord
+    fn complete_word(&self, prefix: &str, limit: usize) -> Result<Vec<String>> {
+        let searcher = self.reader.searcher();
+        let mut words: HashMap<String, u64> = HashMap::new();
+
+        for segment_reader in searcher.segment_readers() {
+            let inverted_index = segment_reader.inverted_index(self.filename_words_field)?;
+            // Le dictionnaire est trié: parcourir à partir du préfixe
+            let mut stream = inverted_index.terms().range().ge(prefix.as_bytes()).into_stream()?;
+            while stream.advance() {
+                if !stream.key().starts_with(prefix.as_bytes()) {
+                    break;
+                }
+                if let Ok(word) = std::str::from_utf8(stream.key()) {
+                    *words.entry(word.to_string()).or_default() += u64::from(stream.value().doc_freq);
+                }
+            }
+        }
+
+        let mut words: Vec<(String, u64)> = words.into_iter().collect();
+        words.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        Ok(words.into_iter().take(limit).map(|(word, _)| word).collect())
+    }
+
+    // Le mot trouve-t-il déjà des fichiers (nom, chemin ou mot entier) ?
+    fn word_matches(&self, searcher: &tantivy::Searcher, word: &str) -> Result<bool> {
+        let queries = [
+            self.ngram_query(self.filename_field, word)?,
+            self.ngram_query(self.path_field, word)?,
+            self.word_query(word)?,
+        ];
+        for query in queries {
+            if searcher.search(&query, &Count)? > 0 {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    // Forme indexée d'un mot (minuscules, sans accents), si c'est un seul mot
+    fn normalize_word(&self, word: &str) -> Result<Option<String>> {
+        let mut tokens = self.word_tokens(word)?;
+        Ok(match tokens.len() {
+            1 => tokens.pop().map(|(_, token)| token),
+            _ => None,
+        })
+    }
+
+    // Mots du texte selon le tokenizer de filename_words (avec positions)
+    fn word_tokens(&self, text: &str) -> Result<Vec<(usize, String)>> {
+        let mut tokenizer = self
+            .index
+            .tokenizer_for_field(self.filename_words_field)
+            .context("Tokenizer introuvable pour le champ")?;
+        let mut tokens = Vec::new();
+        let mut stream = tokenizer.token_stream(text);
+        stream.process(&mut |token| tokens.push((token.position, token.text.clone())));
+        Ok(tokens)
+    }
+
+    // Requête sur les mots entiers du nom: un mot -> TermQuery,
+    // plusieurs ("DataOps", "rapport annuel") -> mots consécutifs
+    fn word_query(&self, text: &str) -> Result<Box<dyn Query>> {
+        let mut terms: Vec<(usize, Term)> = self
+            .word_tokens(text)?
+            .into_iter()
+            .map(|(position, word)| (position, Term::from_field_text(self.filename_words_field, &word)))
+            .collect();
+
+        Ok(match terms.len() {
+            0 => Box::new(EmptyQuery),
+            1 => {
+                let (_, term) = terms.pop().unwrap();
+                Box::new(TermQuery::new(term, IndexRecordOption::WithFreqs))
+            }
+            _ => Box::new(PhraseQuery::new_with_offset(terms)),
+        })
+    }
 
-            Box::new(BooleanQuery::new(fuzzy_queries))
-        } else if options.exact_match {
-            // Mode exact: utiliser TermQuery pour chaque champ
-            use tantivy::query::BooleanQuery;
-            use tantivy::query::Occur;
+    // Mots du vocabulaire de longueur proche, avec leur nombre de fichiers
+    // (cumulé sur tous les segments)
+    fn filename_vocabulary(&self, searcher: &tantivy::Searcher, word: &str) -> Result<HashMap<String, u64>> {
+        let word_len = word.chars().count();
+        let max = max_distance(word_len) as usize;
+        let mut vocabulary: HashMap<String, u64> = HashMap::new();
+
+        for segment_reader in searcher.segment_readers() {
+            let inverted_index = segment_reader.inverted_index(self.filename_words_field)?;
+            let mut stream = inverted_index.terms().stream()?;
+            while stream.advance() {
+                let Ok(term) = std::str::from_utf8(stream.key()) else {
+                    continue;
+                };
+                if term.chars().count().abs_diff(word_len) > max {
+                    continue;
+                }
+                *vocabulary.entry(term.to_string()).or_default() += u64::from(stream.value().doc_freq);
+            }
+        }
 
-            let term_queries: Vec<(Occur, Box<dyn tantivy::query::Query>)> = search_fields
+        Ok(vocabulary)
+    }
+
+    // Recherche triée par un fast field (le score n'est pas calculé: 0.0)
+    // Retourne aussi le nombre total de correspondances
+    fn search_sorted<T: tantivy::fastfield::FastValue>(
+        &self,
+        searcher: &tantivy::Searcher,
+        query: &dyn Query,
+        page: TopDocs,
+        field: Field,
+        ascending: bool,
+    ) -> Result<(usize, Vec<(f32, DocAddress)>)> {
+        let order = if ascending { Order::Asc } else { Order::Desc };
+        let collector = page.order_by_fast_field::<T>(self.schema.get_field_name(field), order);
+        let (total, docs) = searcher
+            .search(query, &(Count, collector))
+            .context("Erreur lors de la recherche")?;
+        Ok((total, docs.into_iter().map(|(_, address)| (0.0, address)).collect()))
+    }
+
+    // Ajoute les filtres de SearchOptions (type, taille, date) à la requête
+    fn apply_filters(&self, query: Box<dyn Query>, options: &SearchOptions) -> Box<dyn Query> {
+        let mut clauses: Vec<(Occur, Box<dyn Query>)> = Vec::new();
+
+        if !options.extensions.is_empty() {
+            let filter = self.field_query_extensions(&options.extensions);
+            clauses.push((Occur::Must, filter));
+        }
+        if !options.excluded_extensions.is_empty() {
+            let filter = self.field_query_extensions(&options.excluded_extensions);
+            clauses.push((Occur::MustNot, filter));
+        }
+        if options.size_min.is_some() || options.size_max.is_some() {
+            clauses.push((Occur::Must, Box::new(RangeQuery::new_u64_bounds(
+                self.schema.get_field_name(self.size_field).to_string(),
+                options.size_min.map_or(Bound::Unbounded, Bound::Included),
+                options.size_max.map_or(Bound::Unbounded, Bound::Included),
+            ))));
+        }
+        // Portée: le fichier est dans l'un des dossiers ou leurs sous-dossiers
+        // (les ancêtres d'une facette sont indexés). Une racine vide = partout
+        let scopes: Vec<Facet> = options
+            .scope
+            .iter()
+            .map(|dir| Facet::from_path(path_components(dir)))
+            .collect();
+        if !scopes.is_empty() && !scopes.iter().any(Facet::is_root) {
+            let scope_clauses = scopes
                 .iter()
-                .map(|field| {
-                    let term = Term::from_field_text(*field, &clean_query);
-                    (Occur::Should, Box::new(TermQuery::new(term, IndexRecordOption::Basic)) as Box<dyn tantivy::query::Query>)
+                .map(|facet| {
+                    let term = Term::from_facet(self.dirs_field, facet);
+                    (Occur::Should, Box::new(TermQuery::new(term, IndexRecordOption::Basic)) as Box<dyn Query>)
                 })
                 .collect();
+            clauses.push((Occur::Must, Box::new(BooleanQuery::new(scope_clauses))));
+        }
+        if let Some(after) = options.modified_after {
+            clauses.push((Occur::Must, Box::new(RangeQuery::new_i64_bounds(
+                self.schema.get_field_name(self.modified_field).to_string(),
+                Bound::Included(after),
+                Bound::Unbounded,
+            ))));
+        }
+
+        if clauses.is_empty() {
+            return query;
+        }
+        clauses.insert(0, (Occur::Must, query));
+        Box::new(BooleanQuery::new(clauses))
+    }
+
+    // Mélange la frecency au score Tantivy (tri par pertinence uniquement)
+    //
+    // Chaque fichier souvent ouvert reçoit un bonus constant s'il correspond
+    // déjà à la requête (clause Should: n'élargit pas les résultats). Le bonus
+    // est appliqué avant la limite: pour une requête courte, où les scores BM25
+    // sont proches, les fichiers habituels remontent en tête.
+    fn apply_frecency(&self, query: Box<dyn Query>, options: &SearchOptions) -> Box<dyn Query> {
+        if options.sort_by != SortBy::Relevance || options.frecency.is_empty() {
+            return query;
+        }
+
+        let mut clauses: Vec<(Occur, Box<dyn Query>)> = Vec::with_capacity(options.frecency.len() + 1);
+        clauses.push((Occur::Must, query));
+        for (path, frecency) in &options.frecency {
+            // Croissance logarithmique: 1 ouverture récente ≈ +1.4, 10 ≈ +4.8
+            let bonus = (FRECENCY_BOOST_WEIGHT * (1.0 + frecency.max(0.0)).ln()) as f32;
+            let term_query = TermQuery::new(self.path_term(path), IndexRecordOption::Basic);
+            clauses.push((Occur::Should, Box::new(ConstScoreQuery::new(Box::new(term_query), bonus))));
+        }
+        Box::new(BooleanQuery::new(clauses))
+    }
+
+    // Une des extensions données (minuscules, sans point)
+    fn field_query_extensions(&self, extensions: &[String]) -> Box<dyn Query> {
+        let clauses = extensions
+            .iter()
+            .map(|ext| {
+                let term = Term::from_field_text(self.extension_field, &ext.trim_start_matches('.').to_lowercase());
+                (Occur::Should, Box::new(TermQuery::new(term, IndexRecordOption::Basic)) as Box<dyn Query>)
+            })
+            .collect();
+        Box::new(BooleanQuery::new(clauses))
+    }
+
+    // Compile un nœud de l'AST en requête Tantivy
+    fn compile_node(&self, node: &QueryNode, fields: &[Field], options: &SearchOptions) -> Result<Box<dyn Query>> {
+        let query: Box<dyn Query> = match node {
+            QueryNode::Term(text) | QueryNode::Phrase(text) => self.text_query(text, fields, options)?,
+            QueryNode::Field(filter) => self.field_query(filter)?,
+            QueryNode::Not(inner) => {
+                // Négation seule: "tout sauf"
+                Box::new(BooleanQuery::new(vec![
+                    (Occur::Must, Box::new(AllQuery) as Box<dyn Query>),
+                    (Occur::MustNot, self.compile_node(inner, fields, options)?),
+                ]))
+            }
+            QueryNode::And(children) => {
+                let mut clauses = Vec::with_capacity(children.len() + 1);
+                let mut has_positive = false;
+                for child in children {
+                    match child {
+                        QueryNode::Not(inner) => {
+                            clauses.push((Occur::MustNot, self.compile_node(inner, fields, options)?));
+                        }
+                        _ => {
+                            has_positive = true;
+                            clauses.push((Occur::Must, self.compile_node(child, fields, options)?));
+                        }
+                    }
+                }
+                // Tantivy ne retourne rien pour une requête uniquement négative
+                if !has_positive {
+                    clauses.push((Occur::Must, Box::new(AllQuery) as Box<dyn Query>));
+                }
+                Box::new(BooleanQuery::new(clauses))
+            }
+            QueryNode::Or(children) => {
+                let clauses = children
+                    .iter()
+                    .map(|child| Ok((Occur::Should, self.compile_node(child, fields, options)?)))
+                    .collect::<Result<Vec<_>>>()?;
+                Box::new(BooleanQuery::new(clauses))
+            }
+        };
+        Ok(query)
+    }
 
-            Box::new(BooleanQuery::new(term_queries))
+    // Requête texte (mot libre ou phrase) selon le mode: fuzzy, exact ou n-grams
+    fn text_query(&self, text: &str, fields: &[Field], options: &SearchOptions) -> Result<Box<dyn Query>> {
+        let clean_text = if options.case_sensitive {
+            text.to_string()
         } else {
-            // Mode flexible: utiliser QueryParser avec n-grams
-            let query_parser = QueryParser::for_index(&self.index, search_fields);
-            Box::new(query_parser
-                .parse_query(&clean_query)
-                .context("Impossible de parser la requête")?)
+            text.to_lowercase()
         };
 
-        // Lance la recherche et récupère les N meilleurs documents
-        // TopDocs collecte les résultats triés par score de pertinence
-        let top_docs = searcher
-            .search(&*query, &TopDocs::with_limit(limit))
-            .context("Erreur lors de la recherche")?;
+        let mut clauses: Vec<(Occur, Box<dyn Query>)> = Vec::with_capacity(fields.len());
+        for field in fields {
+            let query: Box<dyn Query> = if options.fuzzy_search {
+                // Mode fuzzy: FuzzyTermQuery pour tolérer les fautes de frappe
+                let term = Term::from_field_text(*field, &clean_text);
+                Box::new(FuzzyTermQuery::new(term, options.fuzzy_distance, true))
+            } else if options.exact_match {
+                // Mode exact: TermQuery sans découpage
+                let term = Term::from_field_text(*field, &clean_text);
+                Box::new(TermQuery::new(term, IndexRecordOption::Basic))
+            } else {
+                // Mode flexible: n-grams
+                self.ngram_query(*field, &clean_text)?
+            };
+            clauses.push((Occur::Should, query));
+        }
 
-        // Convertir les résultats Tantivy en SearchResult
-        // On déduplique par chemin pour éviter les doublons
-        let mut results = Vec::new();
-        let mut seen_paths = std::collections::HashSet::new();
+        // Mot entier dans le nom (casse et accents ignorés): bonus de score,
+        // "ops" classe DataOps.pdf devant Topsoil.txt. Le texte d'origine
+        // est découpé, pour garder les frontières camelCase de la saisie
+        if fields.contains(&self.filename_field) {
+            let word_query = self.word_query(text)?;
+            clauses.push((Occur::Should, Box::new(BoostQuery::new(word_query, WORD_MATCH_BOOST))));
+        }
 
-        for (score, doc_address) in top_docs {
-            let retrieved_doc: TantivyDocument = searcher.doc(doc_address)?;
-            let path = retrieved_doc
-                .get_first(self.path_field)
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string();
-            let filename = retrieved_doc
-                .get_first(self.filename_field)
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string();
+        Ok(Box::new(BooleanQuery::new(clauses)))
+    }
 
-            // Skip si déjà vu
-            if !seen_paths.insert(path.clone()) {
-                continue;
+    // Requête n-gram sur un champ: tous les n-grams du texte doivent être présents
+    // (même construction que le QueryParser de Tantivy, sans son échappement)
+    fn ngram_query(&self, field: Field, text: &str) -> Result<Box<dyn Query>> {
+        let mut tokenizer = self
+            .index
+            .tokenizer_for_field(field)
+            .context("Tokenizer introuvable pour le champ")?;
+
+        let mut tokens = Vec::new();
+        let mut stream = tokenizer.token_stream(text);
+        stream.process(&mut |token| {
+            tokens.push((token.position, token.text.clone()));
+        });
+
+        // Seuls les n-grams les plus longs sont nécessaires: les plus courts en
+        // sont des sous-chaînes. Un fragment <= max_ngram_size est lui-même un
+        // n-gram indexé et se réduit à un seul TermQuery (beaucoup plus rapide)
+        let longest = tokens.iter().map(|(_, text)| text.chars().count()).max().unwrap_or(0);
+        let mut terms: Vec<(usize, Term)> = tokens
+            .into_iter()
+            .filter(|(_, text)| text.chars().count() == longest)
+            .map(|(position, text)| (position, Term::from_field_text(field, &text)))
+            .collect();
+
+        Ok(match terms.len() {
+            // Fragment plus court que les n-grams: aucun résultat possible
+            0 => Box::new(EmptyQuery),
+            1 => {
+                let (_, term) = terms.pop().unwrap();
+                Box::new(TermQuery::new(term, IndexRecordOption::WithFreqs))
             }
+            _ => Box::new(PhraseQuery::new_with_offset(terms)),
+        })
+    }
+
+    // Requête pour un opérateur de champ (ext:, size:, modified:, path:, name:)
+    fn field_query(&self, filter: &FieldFilter) -> Result<Box<dyn Query>> {
+        let query: Box<dyn Query> = match filter {
+            FieldFilter::Extension(extensions) => self.field_query_extensions(extensions),
+            FieldFilter::Size { min, max } => Box::new(RangeQuery::new_u64_bounds(
+                self.schema.get_field_name(self.size_field).to_string(),
+                *min,
+                *max,
+            )),
+            FieldFilter::Modified { min, max } => Box::new(RangeQuery::new_i64_bounds(
+                self.schema.get_field_name(self.modified_field).to_string(),
+                *min,
+                *max,
+            )),
+            FieldFilter::Path(value) => self.ngram_query(self.path_field, &value.to_lowercase())?,
+            FieldFilter::Name(value) => self.ngram_query(self.filename_field, &value.to_lowercase())?,
+        };
+        Ok(query)
+    }
+}
+
+// Poids du bonus de frecency dans le score (voir apply_frecency)
+const FRECENCY_BOOST_WEIGHT: f64 = 2.0;
+
+// Dossiers d'un chemin, séparateurs / et \ acceptés quel que soit l'OS
+// "C:\Users\a\b.txt" -> [C:, Users, a, b.txt] ; "/home/a" -> [home, a]
+fn path_components(path: &str) -> Vec<&str> {
+    path.split(['/', '\\']).filter(|c| !c.is_empty()).collect()
+}
 
-            results.push(SearchResult::new(path, filename, score));
+// Chemin affichable d'une facette de dossier (inverse de path_components)
+fn facet_to_path(facet: &Facet) -> String {
+    let components = facet.to_path();
+    match components.first() {
+        // Lecteur Windows: C:\Users\a
+        Some(drive) if drive.ends_with(':') => {
+            if components.len() == 1 {
+                format!("{}\\", drive)
+            } else {
+                components.join("\\")
+            }
         }
+        _ => format!("/{}", components.join("/")),
+    }
+}
+
+// Multiplicateur du score d'un mot entier par rapport aux n-grams (voir text_query)
+const WORD_MATCH_BOOST: f32 = 2.0;
+
+// Tri par nom complet (champ name_sort), ex-aequo départagés par l'adresse
+// du document comme TopDocs: l'ordre est total, les pages se suivent sans
+// trou ni doublon. Chaque segment garde ses keep premiers documents d'après
+// l'ordinal du nom dans son dictionnaire (trié); les noms ne sont lus que
+// pour ceux-là, puis les segments sont fusionnés sur le nom
+struct NameOrder {
+    field: String,
+    offset: usize,
+    keep: usize, // offset + taille de page
+    ascending: bool,
+}
+
+struct NameOrderSegment {
+    column: Option<StrColumn>,
+    segment: SegmentOrdinal,
+    keep: usize,
+    ascending: bool,
+    // Les keep plus petits (rang, doc), rang dans l'ordre demandé (tas max)
+    top: BinaryHeap<(u64, DocId)>,
+}
+
+impl Collector for NameOrder {
+    type Fruit = Vec<(String, DocAddress)>;
+    type Child = NameOrderSegment;
+
+    fn for_segment(&self, segment: SegmentOrdinal, reader: &SegmentReader) -> tantivy::Result<NameOrderSegment> {
+        Ok(NameOrderSegment {
+            column: reader.fast_fields().str(&self.field)?,
+            segment,
+            keep: self.keep,
+            ascending: self.ascending,
+            top: BinaryHeap::new(),
+        })
+    }
+
+    fn requires_scoring(&self) -> bool {
+        false
+    }
 
-        Ok(results)
+    fn merge_fruits(&self, fruits: Vec<Vec<(String, DocAddress)>>) -> tantivy::Result<Self::Fruit> {
+        let mut docs: Vec<(String, DocAddress)> = fruits.into_iter().flatten().collect();
+        if self.ascending {
+            docs.sort();
+        } else {
+            docs.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        }
+        Ok(docs.into_iter().skip(self.offset).take(self.keep - self.offset).collect())
+    }
+}
+
+impl SegmentCollector for NameOrderSegment {
+    type Fruit = Vec<(String, DocAddress)>;
+
+    fn collect(&mut self, doc: DocId, _score: Score) {
+        let ord = self.column.as_ref().and_then(|column| column.term_ords(doc).next());
+        // Sans nom: "" (en tête en A→Z, en fin en Z→A)
+        let rank = match (self.ascending, ord) {
+            (true, None) => 0,
+            (true, Some(ord)) => ord + 1,
+            (false, None) => u64::MAX,
+            (false, Some(ord)) => u64::MAX - 1 - ord,
+        };
+        if self.top.len() < self.keep {
+            self.top.push((rank, doc));
+        } else if self.top.peek().is_some_and(|&worst| (rank, doc) < worst) {
+            self.top.pop();
+            self.top.push((rank, doc));
+        }
+    }
+
+    fn harvest(self) -> Self::Fruit {
+        let Some(column) = self.column else {
+            return self.top.into_iter().map(|(_, doc)| (String::new(), DocAddress::new(self.segment, doc))).collect();
+        };
+        self.top
+            .into_iter()
+            .map(|(_, doc)| {
+                let mut name = String::new();
+                if let Some(ord) = column.term_ords(doc).next() {
+                    let _ = column.ord_to_str(ord, &mut name);
+                }
+                (name, DocAddress::new(self.segment, doc))
+            })
+            .collect()
+    }
+}
+
+// Mots libres positifs de la requête (ni phrases, ni filtres, ni exclusions)
+fn collect_free_words(node: &QueryNode, words: &mut Vec<String>) {
+    match node {
+        QueryNode::Term(text) => words.push(text.clone()),
+        QueryNode::And(children) | QueryNode::Or(children) => {
+            for child in children {
+                collect_free_words(child, words);
+            }
+        }
+        QueryNode::Phrase(_) | QueryNode::Field(_) | QueryNode::Not(_) => {}
     }
 }
 
@@ -336,6 +1418,7 @@ impl SearchIndex {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::search::{FolderCount, QueryParseError};
 
     #[test]
     fn test_index_creation() {
@@ -389,6 +1472,569 @@ mod tests {
         let _ = std::fs::remove_dir_all(&temp_dir);
     }
 
+    #[test]
+    fn test_structured_query() {
+        let temp_dir = std::env::temp_dir().join("xfinder_test_index_4");
+        let _ = std::fs::remove_dir_all(&temp_dir);
+        let files_dir = temp_dir.join("files");
+        std::fs::create_dir_all(files_dir.join("projects")).unwrap();
+
+        // Fichiers réels pour que size/modified soient indexés
+        let files = [
+            ("projects/report_2024.pdf", 20 * 1024),
+            ("projects/report_draft.pdf", 20 * 1024),
+            ("projects/invoice.PDF", 100),
+            ("notes/report.txt", 100),
+        ];
+        let index = SearchIndex::new(&temp_dir.join("index"), 2, 20).unwrap();
+        let mut writer = index.create_writer().unwrap();
+        for (relative, size) in files {
+            let path = files_dir.join(relative);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, vec![0u8; size]).unwrap();
+            let filename = path.file_name().unwrap().to_string_lossy().to_string();
+            index.add_file(&mut writer, &path.to_string_lossy(), &filename).unwrap();
+        }
+        writer.commit().unwrap();
+
+        let names = |query: &str| -> Vec<String> {
+            let mut names: Vec<String> = index
+                .search(query, 10, SearchOptions::default())
+                .unwrap()
+                .into_iter()
+                .map(|r| r.filename)
+                .collect();
+            names.sort();
+            names
+        };
+
+        assert_eq!(names("ext:pdf -draft"), vec!["invoice.PDF", "report_2024.pdf"]);
+        assert_eq!(names("report -draft ext:pdf"), vec!["report_2024.pdf"]);
+        assert_eq!(names("size:>10KB"), vec!["report_2024.pdf", "report_draft.pdf"]);
+        assert_eq!(names("path:projects invoice OR 2024"), vec!["invoice.PDF", "report_2024.pdf"]);
+        assert_eq!(names("name:report ext:txt"), vec!["report.txt"]);
+        assert!(names("modified:<2000-01-01").is_empty());
+        assert_eq!(names("modified:>2000").len(), 4);
+
+        // Requête malformée: erreur explicite
+        let err = index.search("report OR", 10, SearchOptions::default()).unwrap_err();
+        assert!(err.to_string().contains("Requête invalide"));
+
+        let _ = std::fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn test_fast_fields_filters_and_sort() {
+        let temp_dir = std::env::temp_dir().join("xfinder_test_index_5");
+        let _ = std::fs::remove_dir_all(&temp_dir);
+        let index = SearchIndex::new(&temp_dir, 2, 20).unwrap();
+
+        // Métadonnées fournies directement: aucun de ces fichiers n'existe
+        let entry = |path: &str, size: u64, modified: i64| FileEntry {
+            path: path.to_string(),
+            filename: Path::new(path).file_name().unwrap().to_string_lossy().to_string(),
+            size,
+            modified: Some(modified),
+            created: Some(modified - 10),
+        };
+        let mut writer = index.create_writer().unwrap();
+        index.add_entry(&mut writer, &entry("/mnt/usb/beta_report.pdf", 3000, 1_700_000_000)).unwrap();
+        index.add_entry(&mut writer, &entry("/mnt/usb/alpha_report.txt", 100, 1_600_000_000)).unwrap();
+        index.add_entry(&mut writer, &entry("/mnt/usb/gamma_report.pdf", 50_000, 1_650_000_000)).unwrap();
+        writer.commit().unwrap();
+
+        let search = |options: SearchOptions| -> Vec<SearchResult> {
+            index.search("report", 10, options).unwrap()
+        };
+        let names = |results: Vec<SearchResult>| -> Vec<String> {
+            results.into_iter().map(|r| r.filename).collect()
+        };
+
+        // Métadonnées lues depuis l'index (fichiers absents du disque)
+        let results = search(SearchOptions { sort_by: SortBy::SizeDesc, ..Default::default() });
+        assert_eq!(results[0].filename, "gamma_report.pdf");
+        assert_eq!(results[0].size_bytes, 50_000);
+        assert!(results[0].modified.is_some());
+        assert!(results[0].created.is_some());
+
+        assert_eq!(
+            names(search(SearchOptions { sort_by: SortBy::NameAsc, ..Default::default() })),
+            vec!["alpha_report.txt", "beta_report.pdf", "gamma_report.pdf"]
+        );
+        assert_eq!(
+            names(search(SearchOptions { sort_by: SortBy::DateDesc, ..Default::default() })),
+            vec!["beta_report.pdf", "gamma_report.pdf", "alpha_report.txt"]
+        );
+
+        // Filtres appliqués avant la limite
+        let results = search(SearchOptions {
+            extensions: vec!["pdf".to_string()],
+            size_max: Some(10_000),
+            ..Default::default()
+        });
+        assert_eq!(names(results), vec!["beta_report.pdf"]);
+
+        let results = search(SearchOptions {
+            excluded_extensions: vec!["pdf".to_string()],
+            ..Default::default()
+        });
+        assert_eq!(names(results), vec!["alpha_report.txt"]);
+
+        let results = search(SearchOptions {
+            modified_after: Some(1_650_000_000),
+            sort_by: SortBy::DateAsc,
+            ..Default::default()
+        });
+        assert_eq!(names(results), vec!["gamma_report.pdf", "beta_report.pdf"]);
+
+        let _ = std::fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn test_update_file_replaces_document() {
+        let temp_dir = std::env::temp_dir().join("xfinder_test_index_6");
+        let _ = std::fs::remove_dir_all(&temp_dir);
+        let index = SearchIndex::new(&temp_dir, 2, 20).unwrap();
+
+        // Chemin plus long que les n-grams: la suppression passe par path_key
+        let path = "/home/user/documents/projects/very_long_folder_name/report.txt";
+        let mut writer = index.create_writer().unwrap();
+        index.add_file(&mut writer, path, "report.txt").unwrap();
+        writer.commit().unwrap();
+        drop(writer); // Libérer le verrou du writer
+
+        index.update_file_path(path, "/home/user/renamed.txt", "renamed.txt").unwrap();
+        index.flush().unwrap();
+        assert_eq!(index.count_documents().unwrap(), 1);
+        assert!(index.search("report", 10, SearchOptions::default()).unwrap().is_empty());
+
+        let _ = std::fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn test_shared_reader_concurrent_search() {
+        let temp_dir = std::env::temp_dir().join("xfinder_test_index_9");
+        let _ = std::fs::remove_dir_all(&temp_dir);
+        let index = SearchIndex::new(&temp_dir, 2, 20).unwrap();
+
+        let mut writer = index.create_writer().unwrap();
+        index.add_file(&mut writer, "/data/report.txt", "report.txt").unwrap();
+        writer.commit().unwrap();
+
+        // Commit hors writer partagé: visible dès le retour de commit()
+        assert_eq!(index.count_documents().unwrap(), 1);
+        drop(writer);
+
+        // Plusieurs threads (UI, CLI, IPC) cherchent sur des copies de l'index
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let index = index.clone();
+                std::thread::spawn(move || index.search("report", 10, SearchOptions::default()).unwrap().len())
+            })
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), 1);
+        }
+
+        // Les commits du writer partagé sont visibles dès le flush
+        index.update_file_path("/data/report.txt", "/data/summary.txt", "summary.txt").unwrap();
+        index.flush().unwrap();
+        assert_eq!(index.search("summary", 10, SearchOptions::default()).unwrap().len(), 1);
+
+        drop(index);
+        let _ = std::fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn test_frecency_boost() {
+        let temp_dir = std::env::temp_dir().join("xfinder_test_index_10");
+        let _ = std::fs::remove_dir_all(&temp_dir);
+        let index = SearchIndex::new(&temp_dir, 2, 20).unwrap();
+
+        let mut writer = index.create_writer().unwrap();
+        for name in ["report_2023.pdf", "report_2024.pdf", "report_2025.pdf"] {
+            index.add_file(&mut writer, &format!("/docs/{}", name), name).unwrap();
+        }
+        index.add_file(&mut writer, "/docs/invoice.pdf", "invoice.pdf").unwrap();
+        writer.commit().unwrap();
+
+        let names = |results: Vec<SearchResult>| results.into_iter().map(|r| r.filename).collect::<Vec<_>>();
+        let frecency = vec![
+            ("/docs/report_2023.pdf".to_string(), 3.0),
+            ("/docs/invoice.pdf".to_string(), 10.0),
+        ];
+
+        // Le fichier souvent ouvert passe en tête
+        let results = index.search("rep", 10, SearchOptions { frecency: frecency.clone(), ..Default::default() }).unwrap();
+        assert_eq!(results.len(), 3); // invoice.pdf ne correspond pas: pas ajouté
+        assert_eq!(results[0].filename, "report_2023.pdf");
+
+        // Désactivé: ordre BM25 seul, le fichier n'a plus d'avantage
+        let plain = index.search("rep", 10, SearchOptions::default()).unwrap();
+        assert!(plain.iter().all(|r| r.score <= results[0].score - 1.0));
+
+        // Tri explicite: la frecency n'intervient pas
+        let sorted = index.search("rep", 10, SearchOptions { frecency, sort_by: SortBy::NameDesc, ..Default::default() }).unwrap();
+        assert_eq!(names(sorted), vec!["report_2025.pdf", "report_2024.pdf", "report_2023.pdf"]);
+
+        let _ = std::fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn test_suggest_corrections() {
+        let temp_dir = std::env::temp_dir().join("xfinder_test_index_11");
+        let _ = std::fs::remove_dir_all(&temp_dir);
+        let index = SearchIndex::new(&temp_dir, 2, 20).unwrap();
+
+        let mut writer = index.create_writer().unwrap();
+        for name in ["rapport_annuel.pdf", "rapport_mensuel.pdf", "rapports.txt", "report.txt"] {
+            index.add_file(&mut writer, &format!("/docs/{}", name), name).unwrap();
+        }
+        writer.commit().unwrap();
+
+        // Inversion de lettres: le mot le plus proche puis le plus fréquent
+        assert!(index.search("rapprot", 10, SearchOptions::default()).unwrap().is_empty());
+        let suggestions = index.suggest("rapprot", 3).unwrap();
+        assert_eq!(suggestions, vec!["rapport", "rapports"]);
+
+        // Les filtres et les mots connus sont conservés
+        assert_eq!(index.suggest("anuel ext:pdf", 3).unwrap(), vec!["annuel ext:pdf"]);
+        assert_eq!(index.suggest("rapport mensul", 1).unwrap(), vec!["rapport mensuel"]);
+
+        // Rien à corriger (ou rien d'assez proche)
+        assert!(index.suggest("rapport", 3).unwrap().is_empty());
+        assert!(index.suggest("xyzzy", 3).unwrap().is_empty());
+
+        let _ = std::fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn test_complete_prefix() {
+        let temp_dir = std::env::temp_dir().join("xfinder_test_index_12");
+        let _ = std::fs::remove_dir_all(&temp_dir);
+        let index = SearchIndex::new(&temp_dir, 2, 20).unwrap();
+
+        let mut writer = index.create_writer().unwrap();
+        for name in ["Rapport_annuel.pdf", "rapport_mensuel.pdf", "rappel.txt", "facture.pdf"] {
+            index.add_file(&mut writer, &format!("/docs/{}", name), name).unwrap();
+        }
+        writer.commit().unwrap();
+
+        let texts = |completions: Vec<Completion>| completions.into_iter().map(|c| c.text).collect::<Vec<_>>();
+
+        // Mots des noms (sans casse), les plus fréquents d'abord
+        assert_eq!(texts(index.complete("rapp", &[], 5).unwrap()), vec!["rapport", "rappel"]);
+        assert_eq!(texts(index.complete("ext:pdf ann", &[], 5).unwrap()), vec!["ext:pdf annuel"]);
+
+        // L'historique passe devant
+        let history = vec![("rapport 2024".to_string(), 3)];
+        assert_eq!(texts(index.complete("rapp", &history, 2).unwrap()), vec!["rapport 2024", "rapport"]);
+        assert!(index.complete("zz", &[], 5).unwrap().is_empty());
+
+        let _ = std::fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn test_whole_word_ranking() {
+        let temp_dir = std::env::temp_dir().join("xfinder_test_index_13");
+        let _ = std::fs::remove_dir_all(&temp_dir);
+        let index = SearchIndex::new(&temp_dir, 2, 20).unwrap();
+
+        let mut writer = index.create_writer().unwrap();
+        for name in ["Topsoil.txt", "DataOps.pdf", "stops_list.md", "Été_2024.jpg", "XMLParser.rs"] {
+            index.add_file(&mut writer, &format!("/docs/{}", name), name).unwrap();
+        }
+        writer.commit().unwrap();
+
+        let names = |query: &str| {
+            index.search(query, 10, SearchOptions::default()).unwrap()
+                .into_iter().map(|r| r.filename).collect::<Vec<_>>()
+        };
+
+        // Mot entier devant les simples sous-chaînes
+        let results = names("ops");
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0], "DataOps.pdf");
+        assert_eq!(names("parser")[0], "XMLParser.rs");
+
+        // Sans accents: "ete" trouve "Été" (absent des n-grams)
+        assert_eq!(names("ete"), vec!["Été_2024.jpg"]);
+        // Frontières camelCase de la saisie
+        assert_eq!(names("DataOps"), vec!["DataOps.pdf"]);
+
+        let _ = std::fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn test_glob_and_regex_modes() {
+        let temp_dir = std::env::temp_dir().join("xfinder_test_index_14");
+        let _ = std::fs::remove_dir_all(&temp_dir);
+        let index = SearchIndex::new(&temp_dir, 2, 20).unwrap();
+
+        let mut writer = index.create_writer().unwrap();
+        for name in ["backup.tar.gz", "IMG_2024_plage.jpg", "IMG_2024_plage.png", "report-2024.xlsx", "old-report-2023.xlsx"] {
+            index.add_file(&mut writer, &format!("/data/{}", name), name).unwrap();
+        }
+        writer.commit().unwrap();
+
+        let names = |query: &str, pattern_mode: PatternMode| {
+            let options = SearchOptions { pattern_mode, sort_by: SortBy::NameAsc, ..Default::default() };
+            index.search(query, 10, options).unwrap().into_iter().map(|r| r.filename).collect::<Vec<_>>()
+        };
+
+        assert_eq!(names("*.TAR.GZ", PatternMode::Glob), vec!["backup.tar.gz"]);
+        assert_eq!(names("IMG_20??_*.jpg", PatternMode::Glob), vec!["IMG_2024_plage.jpg"]);
+        assert_eq!(names(r"^report-\d{4}\.xlsx$", PatternMode::Regex), vec!["report-2024.xlsx"]);
+        assert_eq!(names(r"report-\d{4}", PatternMode::Regex), vec!["old-report-2023.xlsx", "report-2024.xlsx"]);
+        // Sans automate (\b): filtrage du dictionnaire, même résultat
+        assert_eq!(names(r"\breport\b", PatternMode::Regex), vec!["old-report-2023.xlsx", "report-2024.xlsx"]);
+
+        // Les filtres s'appliquent aussi aux motifs, le surlignage suit le motif
+        let options = SearchOptions { pattern_mode: PatternMode::Glob, extensions: vec!["png".to_string()], ..Default::default() };
+        let results = index.search("IMG_*", 10, options).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].filename_matches, vec![0..results[0].filename.len()]);
+
+        // Motif invalide: erreur de requête explicite
+        let options = SearchOptions { pattern_mode: PatternMode::Regex, ..Default::default() };
+        let err = index.search("report(\\d", 10, options).unwrap_err();
+        assert!(err.downcast_ref::<QueryParseError>().is_some());
+
+        let _ = std::fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn test_scope_and_folder_counts() {
+        let temp_dir = std::env::temp_dir().join("xfinder_test_index_15");
+        let _ = std::fs::remove_dir_all(&temp_dir);
+        let index = SearchIndex::new(&temp_dir, 2, 20).unwrap();
+
+        let mut writer = index.create_writer().unwrap();
+        for path in [
+            "/home/me/work/clientX/devis.pdf",
+            "/home/me/work/clientX/2024/devis_v2.pdf",
+            "/home/me/work/clientY/devis.pdf",
+            "/home/me/perso/devis_cuisine.pdf",
+            "C:\\Users\\me\\devis.pdf",
+        ] {
+            let filename = path.rsplit(['/', '\\']).next().unwrap();
+            index.add_file(&mut writer, path, filename).unwrap();
+        }
+        writer.commit().unwrap();
+
+        let scoped = |scope: &[&str]| SearchOptions {
+            scope: scope.iter().map(|s| s.to_string()).collect(),
+            ..Default::default()
+        };
+
+        // Sous-dossiers inclus, séparateurs / ou \ indifférents
+        assert_eq!(index.search("devis", 10, scoped(&["/home/me/work/clientX"])).unwrap().len(), 2);
+        assert_eq!(index.search("devis", 10, scoped(&["/home/me/work/clientX/", "/home/me/perso"])).unwrap().len(), 3);
+        assert_eq!(index.search("devis", 10, scoped(&["C:\\Users"])).unwrap().len(), 1);
+        // "clientX" n'inclut pas un dossier voisin au nom plus long
+        assert!(index.search("devis", 10, scoped(&["/home/me/work/client"])).unwrap().is_empty());
+
+        // Répartition: racines différentes au premier niveau
+        let counts = index.folder_counts("devis", 10, &SearchOptions::default()).unwrap();
+        assert_eq!(counts, vec![
+            FolderCount { path: "/home".to_string(), count: 4 },
+            FolderCount { path: "C:\\".to_string(), count: 1 },
+        ]);
+
+        // Préfixe commun /home/me sauté, puis dossier de portée
+        let options = scoped(&["/home"]);
+        let counts = index.folder_counts("devis", 10, &options).unwrap();
+        assert_eq!(counts[0], FolderCount { path: "/home/me/work".to_string(), count: 3 });
+        assert_eq!(counts[1], FolderCount { path: "/home/me/perso".to_string(), count: 1 });
+
+        let counts = index.folder_counts("devis", 10, &scoped(&["/home/me/work/clientX"])).unwrap();
+        assert_eq!(counts, vec![FolderCount { path: "/home/me/work/clientX/2024".to_string(), count: 1 }]);
+
+        let _ = std::fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn test_search_pages() {
+        let temp_dir = std::env::temp_dir().join("xfinder_test_index_16");
+        let _ = std::fs::remove_dir_all(&temp_dir);
+        let index = SearchIndex::new(&temp_dir, 2, 20).unwrap();
+
+        let mut writer = index.create_writer().unwrap();
+        for i in 0..25 {
+            let name = format!("photo_{:02}.jpg", i);
+            index.add_file(&mut writer, &format!("/img/{}", name), &name).unwrap();
+        }
+        index.add_file(&mut writer, "/img/notes.txt", "notes.txt").unwrap();
+        writer.commit().unwrap();
+
+        let page = |offset: usize, sort_by: SortBy| {
+            index.search_page("photo", offset, 10, SearchOptions { sort_by, ..Default::default() }).unwrap()
+        };
+
+        // Total indépendant de la taille de page, pages disjointes et dans l'ordre
+        let first = page(0, SortBy::NameAsc);
+        let last = page(20, SortBy::NameAsc);
+        assert_eq!(first.total, 25);
+        assert_eq!(first.results.len(), 10);
+        assert_eq!(first.results[0].filename, "photo_00.jpg");
+        assert_eq!(page(10, SortBy::NameAsc).results[0].filename, "photo_10.jpg");
+        assert_eq!(last.results.len(), 5);
+        assert_eq!(last.results[4].filename, "photo_24.jpg");
+
+        // Pertinence: toutes les pages réunies couvrent chaque fichier une fois
+        let mut paths: Vec<String> = (0..3)
+            .flat_map(|p| page(p * 10, SortBy::Relevance).results)
+            .map(|r| r.path)
+            .collect();
+        paths.sort();
+        paths.dedup();
+        assert_eq!(paths.len(), 25);
+        assert!(page(30, SortBy::Relevance).results.is_empty());
+
+        let _ = std::fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn test_name_sort_pages() {
+        let temp_dir = std::env::temp_dir().join("xfinder_test_index_17");
+        let _ = std::fs::remove_dir_all(&temp_dir);
+        let index = SearchIndex::new(&temp_dir, 2, 20).unwrap();
+
+        // Noms au même début (> 8 octets) et noms identiques, sur deux segments
+        let mut writer = index.create_writer().unwrap();
+        for name in ["rapport_annuel_c.pdf", "rapport_annuel_a.pdf", "Rapport_annuel_d.pdf"] {
+            index.add_file(&mut writer, &format!("/docs/{}", name), name).unwrap();
+        }
+        writer.commit().unwrap();
+        for path in ["/docs/rapport_annuel_b.pdf", "/x/rapport.pdf", "/y/rapport.pdf", "/z/rapport.pdf"] {
+            index.add_file(&mut writer, path, path.rsplit('/').next().unwrap()).unwrap();
+        }
+        writer.commit().unwrap();
+
+        // Pages de 2: l'ordre vaut sur toute la liste, sans trou ni doublon
+        let all = |sort_by: SortBy| {
+            let mut paths = Vec::new();
+            let mut offset = 0;
+            loop {
+                let page = index.search_page("rapport", offset, 2, SearchOptions { sort_by, ..Default::default() }).unwrap();
+                if page.consumed == 0 {
+                    return paths;
+                }
+                offset += page.consumed;
+                paths.extend(page.results.into_iter().map(|r| r.path));
+            }
+        };
+        let ascending = all(SortBy::NameAsc);
+        assert_eq!(ascending, vec![
+            "/x/rapport.pdf",
+            "/y/rapport.pdf",
+            "/z/rapport.pdf",
+            "/docs/rapport_annuel_a.pdf",
+            "/docs/rapport_annuel_b.pdf",
+            "/docs/rapport_annuel_c.pdf",
+            "/docs/Rapport_annuel_d.pdf",
+        ]);
+        let descending = all(SortBy::NameDesc);
+        assert_eq!(&descending[..4], &[
+            "/docs/Rapport_annuel_d.pdf",
+            "/docs/rapport_annuel_c.pdf",
+            "/docs/rapport_annuel_b.pdf",
+            "/docs/rapport_annuel_a.pdf",
+        ]);
+        let mut same_name = descending[4..].to_vec();
+        same_name.sort();
+        assert_eq!(same_name, &ascending[..3]);
+
+        let _ = std::fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn test_manifest_mismatch_and_rebuild() {
+        let temp_dir = std::env::temp_dir().join("xfinder_test_index_7");
+        let _ = std::fs::remove_dir_all(&temp_dir);
+        let index_dir = temp_dir.join("tantivy");
+        let rebuild_dir = temp_dir.join("tantivy.rebuild");
+
+        {
+            let index = SearchIndex::new(&index_dir, 3, 10).unwrap();
+            let mut writer = index.create_writer().unwrap();
+            index.add_file(&mut writer, "/data/ab_notes.txt", "ab_notes.txt").unwrap();
+            writer.commit().unwrap();
+        }
+
+        // Autres n-grams: erreur explicite au lieu de résultats faux
+        let err = SearchIndex::new(&index_dir, 2, 20).err().unwrap();
+        assert!(err.to_string().contains("n-grams 3-10 → 2-20"));
+
+        // L'ancien index reste interrogeable avec ses propres paramètres
+        let old = SearchIndex::open_existing(&index_dir).unwrap();
+        assert_eq!(old.search("notes", 10, SearchOptions::default()).unwrap().len(), 1);
+        assert!(old.search("no", 10, SearchOptions::default()).unwrap().is_empty()); // < 3 caractères
+
+        // Reconstruction pendant que l'ancien index sert les recherches
+        let mut last_progress = (0, 0);
+        let count = SearchIndex::rebuild(&index_dir, &rebuild_dir, 2, 20, |done, total| {
+            last_progress = (done, total);
+        }).unwrap();
+        assert_eq!(count, 1);
+        assert_eq!(last_progress, (1, 1));
+        assert_eq!(old.search("notes", 10, SearchOptions::default()).unwrap().len(), 1);
+
+        drop(old);
+        SearchIndex::replace_with_rebuilt(&index_dir, &rebuild_dir).unwrap();
+        assert!(!rebuild_dir.exists());
+
+        let index = SearchIndex::new(&index_dir, 2, 20).unwrap();
+        assert_eq!(index.search("no", 10, SearchOptions::default()).unwrap().len(), 1);
+
+        let _ = std::fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn test_rebuild_legacy_index() {
+        let temp_dir = std::env::temp_dir().join("xfinder_test_index_8");
+        let _ = std::fs::remove_dir_all(&temp_dir);
+        std::fs::create_dir_all(&temp_dir).unwrap();
+        std::fs::write(temp_dir.join("xfinder.db"), "sqlite").unwrap();
+
+        // Index v1 (path + filename, sans manifeste) à côté de la base SQLite
+        {
+            let mut schema_builder = Schema::builder();
+            let path = schema_builder.add_text_field("path", TEXT | STORED);
+            let filename = schema_builder.add_text_field("filename", TEXT | STORED);
+            let index = Index::create_in_dir(&temp_dir, schema_builder.build()).unwrap();
+            let mut writer: IndexWriter = index.writer(15_000_000).unwrap();
+            let mut doc = TantivyDocument::default();
+            doc.add_text(path, "/legacy/report.pdf");
+            doc.add_text(filename, "report.pdf");
+            writer.add_document(doc).unwrap();
+            writer.commit().unwrap();
+        }
+
+        assert_eq!(
+            IndexManifest::check(&temp_dir, &IndexManifest::new(2, 20)).unwrap(),
+            IndexCompatibility::SchemaOutdated { found: None }
+        );
+        assert!(SearchIndex::new(&temp_dir, 2, 20).is_err());
+
+        let index_dir = temp_dir.join("tantivy");
+        let rebuild_dir = temp_dir.join("tantivy.rebuild");
+        assert_eq!(SearchIndex::rebuild(&temp_dir, &rebuild_dir, 2, 20, |_, _| {}).unwrap(), 1);
+        SearchIndex::replace_with_rebuilt(&index_dir, &rebuild_dir).unwrap();
+        SearchIndex::remove_index_files(&temp_dir).unwrap();
+
+        // Fichiers Tantivy supprimés, base SQLite conservée
+        assert!(!temp_dir.join("meta.json").exists());
+        assert!(temp_dir.join("xfinder.db").exists());
+
+        let index = SearchIndex::new(&index_dir, 2, 20).unwrap();
+        let results = index.search("ext:pdf", 10, SearchOptions::default()).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].path, "/legacy/report.pdf");
+
+        let _ = std::fs::remove_dir_all(&temp_dir);
+    }
+
     #[test]
     #[ignore] // Benchmark - run with --ignored
     fn bench_search_100k_files() {
@@ -446,6 +2092,17 @@ mod tests {
         println!("   Fuzzy search 'fle': {} results in {:?} ({:.2}ms)",
             results.len(), fuzzy_time, fuzzy_time.as_secs_f64() * 1000.0);
 
+        // Bench complétion (à chaque frappe: doit rester de l'ordre de la ms)
+        println!("✅ Benchmarking completion...");
+        for prefix in ["fi", "12", "999"] {
+            let start = Instant::now();
+            let completions = index.complete(prefix, &[], 8).unwrap();
+            let complete_time = start.elapsed();
+            println!("   Complete '{}': {} completions in {:?}", prefix, completions.len(), complete_time);
+            assert!(complete_time.as_millis() < 20,
+                "Completion for '{}' took {:?}, expected < 20ms", prefix, complete_time);
+        }
+
         let _ = std::fs::remove_dir_all(&temp_dir);
         println!("✅ Search benchmark completed!");
     }
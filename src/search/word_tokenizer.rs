@@ -0,0 +1,151 @@
+// src/search/word_tokenizer.rs
+// Tokenizer "mots" des noms de fichiers
+//
+// Les n-grams trouvent n'importe quel fragment mais ne savent pas qu'un
+// fragment est un mot entier: "ops" a le même poids dans DataOps.pdf que dans
+// Topsoil.txt. Ce tokenizer découpe le nom en mots:
+// - séparateurs: tout caractère non alphanumérique (_ - . espace...)
+// - camelCase: "DataOps" -> data, ops ; "XMLParser" -> xml, parser
+// - lettres/chiffres: "rapport2024v2" -> rapport, 2024, v, 2
+// puis minuscules et suppression des accents ("Été" -> "ete").
+//
+// Il alimente le champ filename_words, indexé à côté des n-grams: un mot
+// entier y correspond et ajoute du score, une simple sous-chaîne non.
+
+use std::ops::Range;
+use tantivy::tokenizer::{AsciiFoldingFilter, LowerCaser, RemoveLongFilter, TextAnalyzer, Token, TokenStream, Tokenizer};
+
+// Nom du tokenizer enregistré dans l'index (champ filename_words)
+pub const WORD_TOKENIZER: &str = "filename_words";
+
+// Mots plus longs ignorés (hashs, identifiants...), comme le tokenizer par défaut
+const MAX_WORD_LEN: usize = 40;
+
+// Analyseur complet: découpage, minuscules, sans accents
+pub fn word_analyzer() -> TextAnalyzer {
+    TextAnalyzer::builder(FilenameWordTokenizer)
+        .filter(RemoveLongFilter::limit(MAX_WORD_LEN))
+        .filter(LowerCaser)
+        .filter(AsciiFoldingFilter)
+        .build()
+}
+
+#[derive(Clone, Default)]
+pub struct FilenameWordTokenizer;
+
+pub struct FilenameWordTokenStream {
+    tokens: Vec<Token>,
+    next: usize,
+}
+
+impl Tokenizer for FilenameWordTokenizer {
+    type TokenStream<'a> = FilenameWordTokenStream;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        let tokens = split_words(text)
+            .into_iter()
+            .enumerate()
+            .map(|(position, range)| Token {
+                offset_from: range.start,
+                offset_to: range.end,
+                position,
+                text: text[range].to_string(),
+                position_length: 1,
+            })
+            .collect();
+        FilenameWordTokenStream { tokens, next: 0 }
+    }
+}
+
+impl TokenStream for FilenameWordTokenStream {
+    fn advance(&mut self) -> bool {
+        if self.next < self.tokens.len() {
+            self.next += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn token(&self) -> &Token {
+        &self.tokens[self.next - 1]
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        &mut self.tokens[self.next - 1]
+    }
+}
+
+// Plages d'octets des mots de text
+pub fn split_words(text: &str) -> Vec<Range<usize>> {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let mut words = Vec::new();
+    let mut start: Option<usize> = None; // Début du mot courant (octets)
+
+    for (i, &(offset, c)) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if let Some(s) = start.take() {
+                words.push(s..offset);
+            }
+            continue;
+        }
+
+        match start {
+            Some(s) if is_boundary(chars[i - 1].1, c, chars.get(i + 1).map(|(_, n)| *n)) => {
+                words.push(s..offset);
+                start = Some(offset);
+            }
+            Some(_) => {}
+            None => start = Some(offset),
+        }
+    }
+    if let Some(s) = start {
+        words.push(s..text.len());
+    }
+
+    words
+}
+
+// Début d'un nouveau mot entre prev et c (next: caractère suivant c)
+fn is_boundary(prev: char, c: char, next: Option<char>) -> bool {
+    // Lettres <-> chiffres
+    prev.is_numeric() != c.is_numeric()
+        // camelCase: "dataOps"
+        || prev.is_lowercase() && c.is_uppercase()
+        // Acronyme suivi d'un mot: "XMLParser" (coupure avant le P)
+        || prev.is_uppercase() && c.is_uppercase() && next.is_some_and(char::is_lowercase)
+}
+
+// Tests TDD
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(text: &str) -> Vec<&str> {
+        split_words(text).into_iter().map(|r| &text[r]).collect()
+    }
+
+    fn analyzed(text: &str) -> Vec<String> {
+        let mut analyzer = word_analyzer();
+        let mut stream = analyzer.token_stream(text);
+        let mut tokens = Vec::new();
+        stream.process(&mut |token| tokens.push(token.text.clone()));
+        tokens
+    }
+
+    #[test]
+    fn test_split_words() {
+        assert_eq!(words("DataOps.pdf"), vec!["Data", "Ops", "pdf"]);
+        assert_eq!(words("my_project-notes v2.txt"), vec!["my", "project", "notes", "v", "2", "txt"]);
+        assert_eq!(words("XMLParser2024Final"), vec!["XML", "Parser", "2024", "Final"]);
+        assert_eq!(words("getHTTPResponse"), vec!["get", "HTTP", "Response"]);
+        assert_eq!(words("Été_photos"), vec!["Été", "photos"]);
+        assert!(words("__--..").is_empty());
+    }
+
+    #[test]
+    fn test_analyzer_lowercase_and_folding() {
+        assert_eq!(analyzed("Été_2024_Réunion.docx"), vec!["ete", "2024", "reunion", "docx"]);
+        assert_eq!(analyzed("DataOps.pdf"), vec!["data", "ops", "pdf"]);
+    }
+}
@@ -2,33 +2,106 @@
 // Panneau central avec recherche et résultats
 
 use eframe::egui;
+use eframe::egui::text::LayoutJob;
+use std::ops::Range;
 use crate::app::XFinderApp;
+use crate::database::queries::OpenAction;
+use crate::search::PatternMode;
 
 pub fn render_main_ui(ctx: &egui::Context, app: &mut XFinderApp) {
     egui::CentralPanel::default().show(ctx, |ui| {
         ui.add_space(10.0);
 
         // Barre de recherche
-        ui.horizontal(|ui| {
+        let search_box_id = egui::Id::new("search_box");
+
+        // Navigation dans les complétions: avant le TextEdit, qui utiliserait
+        // sinon les flèches et Échap
+        if !app.search_completions.is_empty() && ui.memory(|m| m.has_focus(search_box_id)) {
+            let count = app.search_completions.len();
+            ui.input_mut(|i| {
+                if i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown) {
+                    app.completion_selected = Some(app.completion_selected.map_or(0, |s| (s + 1) % count));
+                }
+                if i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp) {
+                    app.completion_selected = Some(app.completion_selected.map_or(count - 1, |s| (s + count - 1) % count));
+                }
+                if i.consume_key(egui::Modifiers::NONE, egui::Key::Escape) {
+                    app.search_completions.clear();
+                    app.completion_selected = None;
+                }
+            });
+        }
+
+        let search_response = ui.horizontal(|ui| {
             ui.label("Rechercher:");
-            let response = ui.text_edit_singleline(&mut app.search_query);
+            let response = ui.add(egui::TextEdit::singleline(&mut app.search_query).id(search_box_id))
+                .on_hover_text("Syntaxe: ext:pdf size:>10MB modified:<2025-01-01 path:projets name:rapport\n-brouillon \"phrase exacte\" rapport OR facture (groupe)");
 
-            if response.changed()
-                || response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter))
-            {
-                app.perform_search();
+            if response.changed() {
+                // Complétions immédiates, recherche complète après une pause de frappe
+                app.on_search_query_changed();
+            }
+            if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                match app.completion_selected {
+                    Some(idx) => app.accept_completion(idx),
+                    None => app.submit_search(),
+                }
             }
 
             if ui.button("Rechercher").clicked() {
-                app.perform_search();
+                app.submit_search();
             }
 
             if ui.button("Effacer").clicked() {
+                // Requête vide: résultats, pagination, répartition par dossier
+                // et suggestions remis à zéro
                 app.search_query.clear();
-                app.search_results.clear();
+                app.perform_search();
+                app.search_completions.clear();
+                app.completion_selected = None;
                 app.error_message = None;
+                app.query_error = None;
             }
-        });
+
+            response
+        }).inner;
+
+        // Liste déroulante des complétions sous la barre de recherche
+        if !app.search_completions.is_empty() {
+            let mut accepted = None;
+            let area = egui::Area::new(egui::Id::new("search_completions"))
+                .order(egui::Order::Foreground)
+                .fixed_pos(search_response.rect.left_bottom())
+                .show(ui.ctx(), |ui| {
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.set_min_width(search_response.rect.width());
+                        for (idx, completion) in app.search_completions.iter().enumerate() {
+                            ui.horizontal(|ui| {
+                                let selected = app.completion_selected == Some(idx);
+                                if ui.selectable_label(selected, &completion.text).clicked() {
+                                    accepted = Some(idx);
+                                }
+                                if completion.from_history {
+                                    ui.weak("récent");
+                                }
+                            });
+                        }
+                    });
+                });
+
+            if let Some(idx) = accepted {
+                app.accept_completion(idx);
+            } else if area.response.clicked_elsewhere() && !search_response.has_focus() {
+                app.search_completions.clear();
+                app.completion_selected = None;
+            }
+        }
+
+        // Erreur de syntaxe de la requête (ex: guillemet non fermé)
+        if let Some(ref msg) = app.query_error {
+            ui.colored_label(egui::Color32::from_rgb(220, 50, 50), msg);
+        }
 
         // Options de recherche avancée
         ui.horizontal(|ui| {
@@ -70,6 +143,22 @@ pub fn render_main_ui(ctx: &egui::Context, app: &mut XFinderApp) {
                 }
             }
 
+            ui.separator();
+            ui.label("Motif:");
+            for mode in [PatternMode::Text, PatternMode::Glob, PatternMode::Regex] {
+                let hint = match mode {
+                    PatternMode::Text => "Mots et opérateurs (ext:, size:, OR, -exclusion...)",
+                    PatternMode::Glob => "Nom complet: *.tar.gz, IMG_20??_*.jpg, rapport.{doc,docx}",
+                    PatternMode::Regex => "Expression régulière sur le nom: ^report-\\d{4}\\.xlsx$",
+                };
+                if ui.selectable_value(&mut app.search_pattern_mode, mode, mode.label())
+                    .on_hover_text(hint)
+                    .changed()
+                {
+                    changed = true;
+                }
+            }
+
             // Relancer la recherche si une option a changé et qu'il y a une query
             if changed && !app.search_query.trim().is_empty() {
                 app.perform_search();
@@ -125,9 +214,9 @@ pub fn render_main_ui(ctx: &egui::Context, app: &mut XFinderApp) {
         });
 
         // Re-filtrer et re-trier si changement
-        if filters_changed && !app.raw_search_results.is_empty() {
-            // Réappliquer les filtres sur les résultats bruts (pas de nouvelle recherche Tantivy)
-            app.apply_filters_and_sort();
+        if filters_changed {
+            // Filtres et tri appliqués par Tantivy: relancer la recherche
+            app.perform_search();
         }
 
         ui.add_space(5.0);
@@ -346,8 +435,8 @@ pub fn render_main_ui(ctx: &egui::Context, app: &mut XFinderApp) {
                 }
 
                 // Appliquer les changements
-                if advanced_filters_changed && !app.raw_search_results.is_empty() {
-                    app.apply_filters_and_sort();
+                if advanced_filters_changed {
+                    app.perform_search();
                 }
             });
 
@@ -363,8 +452,8 @@ pub fn render_main_ui(ctx: &egui::Context, app: &mut XFinderApp) {
         ui.add_space(5.0);
 
         // Résultats de recherche
-        let total_results = app.search_results.len();
-        let displayed = app.results_display_limit.min(total_results);
+        let total_results = app.search_total_hits;
+        let displayed = app.search_results.len();
 
         ui.label(format!(
             "Resultats: {} trouve(s) - Affichage: {}/{}",
@@ -378,10 +467,11 @@ pub fn render_main_ui(ctx: &egui::Context, app: &mut XFinderApp) {
             // Force toute la largeur disponible
             ui.set_width(ui.available_width());
 
-            // N'afficher que jusqu'à la limite
-            for (idx, result) in app.search_results.iter()
-                .take(app.results_display_limit)
-                .enumerate() {
+            // Résultat ouvert pendant cette frame (enregistré après la boucle)
+            let mut opened: Option<(String, OpenAction)> = None;
+
+            // Pages déjà chargées (load_more_results ajoute la suivante)
+            for (idx, result) in app.search_results.iter().enumerate() {
                 ui.push_id(idx, |ui| {
                 ui.group(|ui| {
                     ui.set_width(ui.available_width());
@@ -389,8 +479,9 @@ pub fn render_main_ui(ctx: &egui::Context, app: &mut XFinderApp) {
                         ui.label(format!("#{}", idx + 1));
                         ui.separator();
                         ui.vertical(|ui| {
-                            ui.label(format!("Fichier: {}", result.filename));
-                            ui.label(format!("Chemin: {}", result.path));
+                            // Fragments correspondant à la requête en gras
+                            highlighted_label(ui, "Fichier: ", &result.filename, &result.filename_matches);
+                            highlighted_label(ui, "Chemin: ", &result.path, &result.path_matches);
 
                             // Métadonnées: taille, dates
                             let size_kb = result.size_bytes as f64 / 1024.0;
@@ -415,11 +506,14 @@ pub fn render_main_ui(ctx: &egui::Context, app: &mut XFinderApp) {
                                 if ui.button("Ouvrir").clicked() {
                                     // Ouvrir le fichier avec l'app par défaut
                                     let _ = opener::open(&result.path);
+                                    opened = Some((result.path.clone(), OpenAction::Open));
                                 }
                                 if ui.button("Previsualiser").clicked() {
                                     app.preview_file_path = Some(result.path.clone());
+                                    opened = Some((result.path.clone(), OpenAction::Preview));
                                 }
                                 if ui.button("Dossier").clicked() {
+                                    opened = Some((result.path.clone(), OpenAction::Folder));
                                     // Sur Windows: ouvrir avec le fichier sélectionné
                                     // Autres OS: ouvrir juste le dossier parent
                                     #[cfg(target_os = "windows")]
@@ -447,23 +541,73 @@ pub fn render_main_ui(ctx: &egui::Context, app: &mut XFinderApp) {
                 ui.add_space(5.0);
             }
 
+            // Historique d'ouverture pour la frecency
+            if let Some((path, action)) = opened {
+                app.record_open(&path, action);
+            }
+
             if app.search_results.is_empty() && !app.search_query.is_empty() {
                 if app.search_index.is_some() {
                     ui.label("Aucun resultat pour cette recherche.");
+
+                    // Corrections proposées: un clic relance la recherche
+                    let mut corrected = None;
+                    if !app.search_suggestions.is_empty() {
+                        ui.horizontal_wrapped(|ui| {
+                            ui.label("Vouliez-vous dire:");
+                            for suggestion in &app.search_suggestions {
+                                if ui.link(suggestion).clicked() {
+                                    corrected = Some(suggestion.clone());
+                                }
+                            }
+                        });
+                    }
+                    if let Some(query) = corrected {
+                        app.search_query = query;
+                        app.submit_search();
+                    }
                 } else {
                     ui.label("Index non charge. Lancez une indexation d'abord.");
                 }
             }
 
             // Bouton "Charger plus" si il y a encore des résultats
-            if displayed < total_results {
+            if app.has_more_results() {
                 ui.add_space(10.0);
                 ui.separator();
                 if ui.button(format!("Charger {} resultats supplementaires...",
-                    (total_results - displayed).min(50))).clicked() {
+                    total_results.saturating_sub(displayed).min(app.results_display_limit))).clicked() {
                     app.load_more_results();
                 }
             }
         });
     });
 }
+
+// Affiche prefix + text, les plages d'octets ranges mises en évidence
+// (texte fort + fond léger: l'équivalent du gras dans egui)
+fn highlighted_label(ui: &mut egui::Ui, prefix: &str, text: &str, ranges: &[Range<usize>]) {
+    let font_id = egui::TextStyle::Body.resolve(ui.style());
+    let normal = egui::TextFormat::simple(font_id.clone(), ui.visuals().text_color());
+    let strong = egui::TextFormat {
+        background: ui.visuals().selection.bg_fill.gamma_multiply(0.5),
+        ..egui::TextFormat::simple(font_id, ui.visuals().strong_text_color())
+    };
+
+    let mut job = LayoutJob::default();
+    job.append(prefix, 0.0, normal.clone());
+
+    let mut cursor = 0;
+    for range in ranges {
+        // Plages triées et alignées sur des caractères (voir search::highlight)
+        if range.start < cursor || text.get(range.clone()).is_none() {
+            continue;
+        }
+        job.append(&text[cursor..range.start], 0.0, normal.clone());
+        job.append(&text[range.clone()], 0.0, strong.clone());
+        cursor = range.end;
+    }
+    job.append(&text[cursor..], 0.0, normal);
+
+    ui.label(job);
+}
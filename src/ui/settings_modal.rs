@@ -153,7 +153,8 @@ fn render_exclusions_tab(ui: &mut egui::Ui, app: &mut XFinderApp) {
                         }
                     }
                 });
-                ui.small("Ex: node_modules, .git, __pycache__, *.tmp");
+                ui.small("Noms ou globs comparés à chaque dossier/fichier du chemin");
+                ui.small("Ex: node_modules, .git, __pycache__, *.tmp, target/debug");
 
                 ui.add_space(15.0);
                 ui.separator();
@@ -191,6 +192,50 @@ fn render_exclusions_tab(ui: &mut egui::Ui, app: &mut XFinderApp) {
                     }
                 }
 
+                ui.add_space(15.0);
+                ui.separator();
+                ui.add_space(15.0);
+
+                // Autres règles
+                ui.heading("Autres règles");
+                ui.add_space(5.0);
+                if ui.checkbox(&mut app.config.exclusions.hidden, "Exclure les fichiers et dossiers cachés (.nom)").changed() {
+                    app.save_config();
+                }
+                ui.horizontal(|ui| {
+                    ui.label("Taille max:");
+                    if ui.add(egui::DragValue::new(&mut app.config.exclusions.max_file_size_mb).speed(10).clamp_range(0..=1_000_000)).changed() {
+                        app.save_config();
+                    }
+                    ui.label("Mo (0 = pas de limite)");
+                });
+
+                ui.add_space(15.0);
+                ui.separator();
+                ui.add_space(15.0);
+
+                // Tester un chemin: quelle règle l'exclut ?
+                ui.heading("Tester un chemin");
+                ui.horizontal(|ui| {
+                    ui.label("Chemin:");
+                    ui.text_edit_singleline(&mut app.exclusion_test_input);
+                    if ui.button("📁").clicked() {
+                        if let Some(path) = rfd::FileDialog::new().pick_file() {
+                            app.exclusion_test_input = path.to_string_lossy().to_string();
+                        }
+                    }
+                });
+                if !app.exclusion_test_input.trim().is_empty() {
+                    match app.explain_exclusion(&app.exclusion_test_input) {
+                        Some(reason) => {
+                            ui.colored_label(egui::Color32::from_rgb(200, 100, 50), reason);
+                        }
+                        None => {
+                            ui.colored_label(egui::Color32::from_rgb(100, 160, 100), "Indexé");
+                        }
+                    }
+                }
+
                 ui.add_space(15.0);
                 ui.separator();
                 ui.add_space(10.0);
@@ -211,6 +256,8 @@ fn render_exclusions_tab(ui: &mut egui::Ui, app: &mut XFinderApp) {
                         "target/release".to_string(),
                     ];
                     app.excluded_dirs.clear();
+                    app.config.exclusions.hidden = false;
+                    app.config.exclusions.max_file_size_mb = 0;
                     app.save_config();
                 }
 
@@ -231,9 +278,30 @@ fn render_general_tab(ui: &mut egui::Ui, app: &mut XFinderApp) {
         if ui.add(egui::DragValue::new(&mut app.results_display_limit).speed(10).clamp_range(10..=1000)).changed() {
             app.save_config();
         }
-        ui.label("résultats affichés");
+        ui.label("résultats par page");
     });
-    ui.small("Affecte uniquement l'affichage dans la liste, pas la recherche");
+    ui.small("Nombre de résultats chargés à chaque page (bouton \"Charger plus\")");
+
+    ui.add_space(20.0);
+    ui.separator();
+    ui.add_space(15.0);
+
+    // Frecency: fichiers souvent ouverts favorisés
+    ui.heading("Classement des résultats");
+    ui.add_space(5.0);
+
+    let mut frecency_enabled = app.config.ui.frecency_enabled;
+    if ui.checkbox(&mut frecency_enabled, "Favoriser les fichiers souvent ouverts").changed() {
+        app.config.ui.frecency_enabled = frecency_enabled;
+        app.save_config();
+        app.perform_search();
+    }
+    ui.small("Les fichiers ouverts, prévisualisés ou affichés dans leur dossier remontent dans le tri par pertinence");
+
+    ui.add_space(5.0);
+    if ui.button("🗑 Effacer l'historique d'ouverture").clicked() {
+        app.clear_open_history();
+    }
 
     ui.add_space(20.0);
     ui.separator();
@@ -336,21 +404,13 @@ fn render_system_tab(ui: &mut egui::Ui, app: &mut XFinderApp) {
     if ui.checkbox(&mut scheduler_enabled, "Activer l'indexation planifiée automatique").changed() {
         app.config.system.scheduler_enabled = scheduler_enabled;
 
-        // TODO: Démarrer/arrêter le scheduler
+        // Démarrer/arrêter le scheduler (rafraîchissement incrémental à l'heure prévue)
         if scheduler_enabled {
-            // Créer et démarrer le scheduler si pas déjà fait
             if app.scheduler.is_none() {
-                use crate::system::Scheduler;
-                let scheduler = Scheduler::new(
-                    app.config.system.scheduler_hour,
-                    app.config.system.scheduler_minute
-                );
-                // TODO: Démarrer avec callback d'indexation
-                app.scheduler = Some(scheduler);
+                app.start_scheduler();
             }
         } else {
-            // Arrêter le scheduler
-            app.scheduler = None;
+            app.stop_scheduler();
         }
 
         app.save_config();
@@ -3,6 +3,7 @@
 
 use eframe::egui;
 use crate::app::{XFinderApp, AppMode};
+use crate::search::WatchMode;
 
 pub fn render_side_panel(ctx: &egui::Context, app: &mut XFinderApp) {
     egui::SidePanel::left("side_panel")
@@ -22,6 +23,8 @@ pub fn render_side_panel(ctx: &egui::Context, app: &mut XFinderApp) {
 }
 
 fn render_classic_sidebar(ui: &mut egui::Ui, app: &mut XFinderApp) {
+    render_folder_breakdown(ui, app);
+
     ui.heading("Statut de l'Index");
     ui.add_space(10.0);
 
@@ -29,13 +32,33 @@ fn render_classic_sidebar(ui: &mut egui::Ui, app: &mut XFinderApp) {
 
             ui.label(format!(
                 "Etat: {}",
-                if app.index_status.is_ready {
+                if app.index_rebuild.is_some() {
+                    "Reconstruction"
+                } else if app.index_status.is_ready {
                     "Pret"
                 } else {
                     "Non charge"
                 }
             ));
 
+            // Reconstruction en arrière-plan (schéma ou n-grams modifiés)
+            if let Some(ref rebuild) = app.index_rebuild {
+                ui.colored_label(egui::Color32::from_rgb(255, 150, 50), &rebuild.reason);
+                let progress = if rebuild.total > 0 {
+                    rebuild.done as f32 / rebuild.total as f32
+                } else {
+                    0.0
+                };
+                ui.add(egui::ProgressBar::new(progress)
+                    .text(format!("{}/{} fichiers", rebuild.done, rebuild.total))
+                    .fill(egui::Color32::from_rgb(200, 150, 100)));
+                if app.search_index.is_some() {
+                    ui.small("L'ancien index reste utilisable pendant la reconstruction");
+                } else {
+                    ui.small("Recherche indisponible jusqu'à la fin de la reconstruction");
+                }
+            }
+
             ui.label(format!("Emplacement: {}", app.index_dir.display()));
 
             ui.label(format!(
@@ -47,10 +70,15 @@ fn render_classic_sidebar(ui: &mut egui::Ui, app: &mut XFinderApp) {
             if app.indexing_in_progress {
                 ui.add_space(5.0);
                 ui.label(format!(
-                    "Indexation: {}/{} fichiers",
+                    "Indexation: {}/{} fichiers{}",
                     app.index_status.current_indexed,
-                    app.index_status.total_to_index
+                    app.index_status.total_to_index,
+                    // Total encore provisoire tant que le parcours continue
+                    if app.index_status.scanning { "+" } else { "" }
                 ));
+                if app.index_status.files_per_sec > 0.0 {
+                    ui.small(format!("Debit: {:.0} fichiers/s", app.index_status.files_per_sec));
+                }
 
                 // Progress bar (couleur cohérente avec l'UI)
                 if app.index_status.total_to_index > 0 {
@@ -98,15 +126,77 @@ fn render_classic_sidebar(ui: &mut egui::Ui, app: &mut XFinderApp) {
 
             // Afficher la liste des dossiers
             let mut to_remove = None;
+            let mut vcs_toggle = None;
+            let mut depth_change = None;
+            let mut traversal_change = None;
             for (idx, path) in app.scan_paths.iter().enumerate() {
                 ui.horizontal(|ui| {
                     ui.label(format!("{}. {}", idx + 1, path));
+                    let mut respect_vcs_ignore = app.config.root_config(path).respect_vcs_ignore;
+                    if ui.checkbox(&mut respect_vcs_ignore, ".gitignore")
+                        .on_hover_text("Exclure les fichiers listés dans les .gitignore de ce dossier\n(.ignore et .xfinderignore sont toujours respectés)")
+                        .changed()
+                    {
+                        vcs_toggle = Some((path.clone(), respect_vcs_ignore));
+                    }
+                    // Profondeur max (0: illimitée)
+                    let mut max_depth = app.config.root_config(path).max_depth.unwrap_or(0);
+                    if ui.add(
+                        egui::DragValue::new(&mut max_depth)
+                            .clamp_range(0..=64)
+                            .custom_formatter(|depth, _| if depth < 1.0 { "∞".to_string() } else { format!("{}", depth) })
+                            .prefix("prof. "),
+                    )
+                    .on_hover_text("Profondeur max parcourue sous ce dossier (∞: illimitée)\nPris en compte au prochain rafraîchissement")
+                    .changed()
+                    {
+                        depth_change = Some((path.clone(), (max_depth > 0).then_some(max_depth)));
+                    }
                     if !app.scan_entire_pc && ui.button("X").clicked() {
                         to_remove = Some(idx);
                     }
                 });
+
+                // Politique de parcours (liens, cachés, montages)
+                egui::CollapsingHeader::new("Parcours")
+                    .id_source(("parcours", idx))
+                    .show(ui, |ui| {
+                        let mut root = app.config.root_config(path);
+                        let mut changed = ui
+                            .checkbox(&mut root.follow_symlinks, "Suivre les liens symboliques")
+                            .on_hover_text("Chaque dossier n'est parcouru qu'une fois (pas de boucle)")
+                            .changed();
+                        let mut include_hidden = !root.exclude_hidden.unwrap_or(app.config.exclusions.hidden);
+                        if ui
+                            .checkbox(&mut include_hidden, "Fichiers et dossiers cachés")
+                            .on_hover_text("Indexer les noms commençant par \".\" sous ce dossier\n(par défaut: réglage des exclusions)")
+                            .changed()
+                        {
+                            root.exclude_hidden = Some(!include_hidden);
+                            changed = true;
+                        }
+                        changed |= ui
+                            .checkbox(&mut root.same_filesystem, "Rester sur le même système de fichiers")
+                            .on_hover_text("Ne pas descendre dans les disques et partages montés sous ce dossier")
+                            .changed();
+                        changed |= ui
+                            .checkbox(&mut root.skip_pseudo_fs, "Ignorer les pseudo-systèmes (proc, sysfs, tmpfs...)")
+                            .changed();
+                        if changed {
+                            traversal_change = Some(root);
+                        }
+                    });
             }
 
+            if let Some((path, respect_vcs_ignore)) = vcs_toggle {
+                app.set_respect_vcs_ignore(&path, respect_vcs_ignore);
+            }
+            if let Some((path, max_depth)) = depth_change {
+                app.set_root_max_depth(&path, max_depth);
+            }
+            if let Some(root) = traversal_change {
+                app.set_root_traversal(root);
+            }
             if let Some(idx) = to_remove {
                 app.remove_scan_path(idx);
             }
@@ -202,7 +292,20 @@ fn render_classic_sidebar(ui: &mut egui::Ui, app: &mut XFinderApp) {
             });
             ui.small(format!("Range: {}-{} chars", app.min_ngram_size, app.max_ngram_size));
             ui.small("Fragments recherche | 2-20: rapide | 2-100: lent+flexible");
-            ui.colored_label(egui::Color32::from_rgb(255, 150, 50), "⚠ Réindexer pour appliquer");
+
+            // N-grams différents de ceux de l'index chargé: proposer la reconstruction
+            let ngrams_changed = app.search_index.as_ref().is_some_and(|index| {
+                let manifest = index.manifest();
+                manifest.min_ngram_size != app.min_ngram_size || manifest.max_ngram_size != app.max_ngram_size
+            });
+            if ngrams_changed && app.index_rebuild.is_none() {
+                ui.colored_label(egui::Color32::from_rgb(255, 150, 50), "⚠ Index construit avec d'autres n-grams");
+                if ui.button("Reconstruire l'index").clicked() {
+                    app.save_config();
+                    app.index_status.rebuild_failed = false;
+                    app.load_index();
+                }
+            }
 
             ui.add_space(10.0);
             ui.separator();
@@ -280,6 +383,29 @@ fn render_classic_sidebar(ui: &mut egui::Ui, app: &mut XFinderApp) {
             if app.watchdog_enabled {
                 ui.label("Detection auto: ajout/modification/suppression");
             }
+            render_reconcile_progress(ui, app);
+
+            // Fenêtre de calme: regroupe les rafales d'un éditeur en une mise à jour
+            let mut quiet_ms = app.config.ui.watchdog_quiet_ms;
+            if ui
+                .add(egui::DragValue::new(&mut quiet_ms).clamp_range(0..=5000).speed(10).prefix("Calme: ").suffix(" ms"))
+                .on_hover_text("Délai sans nouvel événement avant d'indexer un fichier modifié\n(un enregistrement d'éditeur ne compte qu'une fois)")
+                .changed()
+            {
+                app.set_watchdog_quiet_ms(quiet_ms);
+            }
+
+            // Partages réseau, FUSE...: scrutés faute d'événements natifs
+            let mut poll_secs = app.config.ui.watchdog_poll_secs;
+            if ui
+                .add(egui::DragValue::new(&mut poll_secs).clamp_range(1..=3600).prefix("Scrutation: ").suffix(" s"))
+                .on_hover_text("Intervalle de vérification des dossiers sans événements natifs
+(partages réseau, FUSE, volumes de VM)")
+                .changed()
+            {
+                app.set_watchdog_poll_secs(poll_secs);
+            }
+            render_watch_modes(ui, app);
 
             ui.add_space(10.0);
             ui.separator();
@@ -294,6 +420,80 @@ fn render_classic_sidebar(ui: &mut egui::Ui, app: &mut XFinderApp) {
             ui.add_space(20.0);
 }
 
+// Mode de surveillance de chaque dossier (natif ou scrutation)
+fn render_watch_modes(ui: &mut egui::Ui, app: &XFinderApp) {
+    let Some(ref watcher) = app.file_watcher else {
+        return;
+    };
+    for root in watcher.watched_roots() {
+        let mode = match root.fs_type {
+            Some(ref fs_type) => format!("{} ({})", root.mode.label(), fs_type),
+            None => root.mode.label().to_string(),
+        };
+        let label = ui.small(format!("{}: {}", root.path.display(), mode));
+        if root.mode == WatchMode::Polling {
+            label.on_hover_text(format!(
+                "Pas d'événements natifs sur ce dossier: vérifié toutes les {} s",
+                app.config.ui.watchdog_poll_secs
+            ));
+        }
+    }
+}
+
+// Réconciliation au démarrage du watchdog (changements faits pendant la fermeture)
+fn render_reconcile_progress(ui: &mut egui::Ui, app: &XFinderApp) {
+    let Some(ref reconcile) = app.startup_reconcile else {
+        return;
+    };
+    ui.horizontal(|ui| {
+        ui.spinner();
+        ui.label(format!("Rattrapage: {}/{} dossiers", reconcile.roots_done, reconcile.roots_total));
+    });
+    if !reconcile.current_root.is_empty() {
+        ui.small(format!("{} ({} fichiers vérifiés)", reconcile.current_root, reconcile.checked));
+    }
+    ui.small(format!("{} changements trouvés, appliqués dossier par dossier", reconcile.changes));
+}
+
+// Répartition des résultats par dossier: un clic limite la recherche au dossier
+fn render_folder_breakdown(ui: &mut egui::Ui, app: &mut XFinderApp) {
+    if app.folder_counts.is_empty() && app.search_scope.is_empty() {
+        return;
+    }
+
+    ui.heading("Dossiers");
+    ui.add_space(5.0);
+
+    let mut new_scope = None;
+
+    if !app.search_scope.is_empty() {
+        for dir in &app.search_scope {
+            ui.horizontal(|ui| {
+                ui.label(format!("Dans: {}", dir));
+                if ui.small_button("x").on_hover_text("Chercher partout").clicked() {
+                    new_scope = Some(Vec::new());
+                }
+            });
+        }
+        ui.add_space(5.0);
+    }
+
+    for folder in &app.folder_counts {
+        let label = format!("{} ({})", folder.path, folder.count);
+        if ui.link(label).on_hover_text("Limiter la recherche à ce dossier").clicked() {
+            new_scope = Some(vec![folder.path.clone()]);
+        }
+    }
+
+    if let Some(scope) = new_scope {
+        app.set_search_scope(scope);
+    }
+
+    ui.add_space(10.0);
+    ui.separator();
+    ui.add_space(10.0);
+}
+
 fn render_assist_me_sidebar(ui: &mut egui::Ui, app: &mut XFinderApp) {
     ui.heading("Assist Me 🤖");
     ui.add_space(10.0);
@@ -396,6 +596,8 @@ fn render_assist_me_sidebar(ui: &mut egui::Ui, app: &mut XFinderApp) {
         }
     });
 
+    render_reconcile_progress(ui, app);
+
     if app.watchdog_enabled {
         ui.label("Détection auto: ajout/modification/suppression");
 
@@ -3,6 +3,7 @@
 
 use eframe::egui;
 use crate::app::XFinderApp;
+use crate::search::scanner::TruncationReason;
 
 pub fn render_statistics_modal(ctx: &egui::Context, app: &mut XFinderApp) {
     if !app.show_statistics_modal {
@@ -37,6 +38,15 @@ pub fn render_statistics_modal(ctx: &egui::Context, app: &mut XFinderApp) {
                         }
                     }
 
+                    // Resynchronisations du watchdog (événements perdus)
+                    ui.horizontal(|ui| {
+                        ui.label("Resynchronisations du watchdog :");
+                        ui.strong(format!("{}", app.watchdog_resync_count));
+                        if !app.watchdog_resyncing.is_empty() {
+                            ui.label(format!("({} en cours)", app.watchdog_resyncing.len()));
+                        }
+                    });
+
                     ui.add_space(5.0);
 
                     // Stats par extension
@@ -108,6 +118,61 @@ pub fn render_statistics_modal(ctx: &egui::Context, app: &mut XFinderApp) {
                     ui.separator();
                     ui.add_space(10.0);
 
+                    // Section: Sous-arbres non indexés entièrement
+                    ui.heading("✂ Couverture des dossiers");
+                    ui.add_space(10.0);
+
+                    match db.get_scan_reports() {
+                        Ok(reports) if !reports.is_empty() => {
+                            ui.push_id("scan_reports", |ui| {
+                                for report in &reports {
+                                    ui.horizontal(|ui| {
+                                        ui.strong(&report.root);
+                                        ui.label(format!("{} fichiers", report.files_indexed));
+                                        if report.truncated_total == 0 {
+                                            ui.colored_label(egui::Color32::from_rgb(100, 200, 100), "complet");
+                                        } else {
+                                            ui.colored_label(
+                                                egui::Color32::from_rgb(255, 200, 100),
+                                                format!("{} sous-dossiers tronqués", report.truncated_total)
+                                            );
+                                        }
+                                    });
+
+                                    if !report.truncated.is_empty() {
+                                        egui::CollapsingHeader::new("Détail")
+                                            .id_source(&report.root)
+                                            .show(ui, |ui| {
+                                                for (path, reason) in &report.truncated {
+                                                    let reason = TruncationReason::parse(reason)
+                                                        .map(|r| r.label())
+                                                        .unwrap_or(reason.as_str());
+                                                    ui.label(format!("• {} ({})", path, reason));
+                                                }
+                                                let hidden = (report.truncated_total as usize).saturating_sub(report.truncated.len());
+                                                if hidden > 0 {
+                                                    ui.label(format!("... et {} autres", hidden));
+                                                }
+                                            });
+                                    }
+                                }
+                            });
+                        }
+                        Ok(_) => {
+                            ui.label("Aucun scan enregistré");
+                        }
+                        Err(e) => {
+                            ui.colored_label(
+                                egui::Color32::from_rgb(255, 100, 100),
+                                format!("Erreur bilan des scans: {}", e)
+                            );
+                        }
+                    }
+
+                    ui.add_space(15.0);
+                    ui.separator();
+                    ui.add_space(10.0);
+
                     // Section: Recherches récentes
                     ui.heading("🔍 Recherches fréquentes");
                     ui.add_space(10.0);
@@ -41,6 +41,11 @@ pub fn render_top_panel(ctx: &egui::Context, app: &mut XFinderApp) {
                     ui.label("Indexation classique...");
                 }
 
+                if app.index_rebuild.is_some() {
+                    ui.spinner();
+                    ui.label("Reconstruction de l'index...");
+                }
+
                 if app.semantic_indexing_in_progress {
                     ui.spinner();
                     ui.label(format!("Indexation IA ({} fichiers)...", app.semantic_stats.files_indexed));